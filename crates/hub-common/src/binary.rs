@@ -0,0 +1,173 @@
+//! Tool binary lookup - one search order shared by everything that launches tools
+
+use std::path::{Path, PathBuf};
+
+/// Base directories searched for tool binaries.
+///
+/// `SearchRoots::current` uses the running hub's locations; construct it
+/// directly to search somewhere else.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchRoots {
+    /// User-configured tool directory (searched first)
+    pub tool_directory: Option<PathBuf>,
+    /// Directory containing the hub executable (install layout)
+    pub exe_dir: Option<PathBuf>,
+    /// Working directory (dev layout: workspace root or `crates/hub-dashboard`)
+    pub cwd: Option<PathBuf>,
+}
+
+impl SearchRoots {
+    /// Search roots for the running hub, with an optional user tool directory
+    pub fn current(tool_directory: Option<PathBuf>) -> Self {
+        Self {
+            tool_directory,
+            exe_dir: std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(Path::to_path_buf)),
+            cwd: std::env::current_dir().ok(),
+        }
+    }
+
+    /// Every path checked for a binary, in search order.
+    ///
+    /// `binary_name` is without extension (".exe" is added on Windows);
+    /// `folder` is the tool's submodule folder under `tools/`, if any.
+    pub fn candidates(&self, binary_name: &str, folder: Option<&str>) -> Vec<PathBuf> {
+        let binary_name = if cfg!(windows) {
+            format!("{}.exe", binary_name)
+        } else {
+            binary_name.to_string()
+        };
+        let mut paths = Vec::new();
+
+        if let Some(dir) = &self.tool_directory {
+            paths.push(dir.join(&binary_name));
+        }
+
+        if let Some(exe_dir) = &self.exe_dir {
+            // Same directory as hub (for portable/dev installs)
+            paths.push(exe_dir.join(&binary_name));
+            // In a 'tools' subdirectory (bundled install)
+            paths.push(exe_dir.join("tools").join(&binary_name));
+            // Tauri resource path: <exe_dir>/resources/tools/
+            paths.push(exe_dir.join("resources").join("tools").join(&binary_name));
+        }
+
+        if let Some(cwd) = &self.cwd {
+            // Workspace root, then crates/hub-dashboard (when running with cargo run)
+            for root in [cwd.clone(), cwd.join("..").join("..")] {
+                // Workspace target directory (cargo builds all workspace members here)
+                paths.push(root.join("target").join("release").join(&binary_name));
+                paths.push(root.join("target").join("debug").join(&binary_name));
+                // Submodule's own target
+                if let Some(folder) = folder {
+                    let target = root.join("tools").join(folder).join("target");
+                    paths.push(target.join("release").join(&binary_name));
+                    paths.push(target.join("debug").join(&binary_name));
+                }
+            }
+        }
+
+        paths
+    }
+
+    /// Find a binary, returning the first candidate that exists
+    pub fn locate(&self, binary_name: &str, folder: Option<&str>) -> Option<PathBuf> {
+        self.find(binary_name, folder).ok()
+    }
+
+    /// Like `locate`, but on failure returns every path that was checked so
+    /// the user can be told where to put the binary
+    pub fn find(&self, binary_name: &str, folder: Option<&str>) -> Result<PathBuf, Vec<PathBuf>> {
+        let candidates = self.candidates(binary_name, folder);
+        match candidates.iter().find(|path| path.exists()) {
+            Some(path) => Ok(path.canonicalize().unwrap_or_else(|_| path.clone())),
+            None => Err(candidates),
+        }
+    }
+}
+
+/// Find a tool binary in the install and dev build locations
+pub fn locate_binary(binary_name: &str, folder: Option<&str>) -> Option<PathBuf> {
+    SearchRoots::current(None).locate(binary_name, folder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn binary_file(name: &str) -> String {
+        if cfg!(windows) {
+            format!("{}.exe", name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Create an empty binary at `dir/name`, creating `dir` as needed
+    fn touch(dir: &Path, name: &str) -> PathBuf {
+        fs::create_dir_all(dir).unwrap();
+        let path = dir.join(binary_file(name));
+        fs::write(&path, b"").unwrap();
+        path.canonicalize().unwrap()
+    }
+
+    #[test]
+    fn tool_directory_is_searched_first() {
+        let base = tempfile::tempdir().unwrap();
+        let tool_directory = base.path().join("custom");
+        let exe_dir = base.path().join("install");
+        let expected = touch(&tool_directory, "tool");
+        touch(&exe_dir, "tool");
+
+        let roots = SearchRoots {
+            tool_directory: Some(tool_directory),
+            exe_dir: Some(exe_dir),
+            cwd: None,
+        };
+        assert_eq!(roots.locate("tool", None), Some(expected));
+    }
+
+    #[test]
+    fn bundled_tools_directory_is_found() {
+        let base = tempfile::tempdir().unwrap();
+        let expected = touch(&base.path().join("resources").join("tools"), "tool");
+
+        let roots = SearchRoots {
+            exe_dir: Some(base.path().to_path_buf()),
+            ..SearchRoots::default()
+        };
+        assert_eq!(roots.locate("tool", None), Some(expected));
+    }
+
+    #[test]
+    fn submodule_target_is_found_in_dev_layout() {
+        let base = tempfile::tempdir().unwrap();
+        let target = base.path().join("tools").join("my-tool").join("target").join("debug");
+        let expected = touch(&target, "tool");
+
+        let roots = SearchRoots {
+            cwd: Some(base.path().to_path_buf()),
+            ..SearchRoots::default()
+        };
+        assert_eq!(roots.locate("tool", Some("my-tool")), Some(expected));
+        // Without a folder, submodule targets aren't searched
+        assert_eq!(roots.locate("tool", None), None);
+    }
+
+    #[test]
+    fn missing_binary_reports_every_candidate() {
+        let base = tempfile::tempdir().unwrap();
+        let roots = SearchRoots {
+            tool_directory: Some(base.path().join("custom")),
+            exe_dir: Some(base.path().join("install")),
+            cwd: Some(base.path().join("workspace")),
+        };
+
+        let searched = roots.find("tool", Some("my-tool")).unwrap_err();
+        assert_eq!(searched, roots.candidates("tool", Some("my-tool")));
+        assert_eq!(searched[0], base.path().join("custom").join(binary_file("tool")));
+        assert!(searched.iter().all(|path| path.starts_with(base.path())));
+    }
+}
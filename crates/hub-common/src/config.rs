@@ -3,11 +3,46 @@
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fs, path::PathBuf};
+use fs4::fs_std::FileExt;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tracing::warn;
 
-use crate::hotkeys::RegisteredHotkey;
+use crate::hotkeys::{HotkeyKey, HotkeyModifier, HotkeyRegistry, NamedKey, RegisteredHotkey};
 use crate::tools::ToolId;
 
+/// Environment variable overriding the config directory, for portable installs
+/// and isolated test runs
+pub const CONFIG_DIR_ENV: &str = "HUB_CONFIG_DIR";
+
+/// The directory all hub state lives in - main config, license, logs - so
+/// every crate resolves the same folder: `HUB_CONFIG_DIR` if set, else the OS
+/// config dir for the app. Created if missing.
+pub fn app_dirs() -> Result<PathBuf> {
+    let config_dir = match config_dir_override() {
+        Some(dir) => dir,
+        None => ProjectDirs::from("com", "hub", "productivity-hub")
+            .context("Failed to determine project directories")?
+            .config_dir()
+            .to_path_buf(),
+    };
+    fs::create_dir_all(&config_dir).with_context(|| {
+        format!("Failed to create config directory {}", config_dir.display())
+    })?;
+    Ok(config_dir)
+}
+
+/// The directory `HUB_CONFIG_DIR` points at, if it's set
+pub fn config_dir_override() -> Option<PathBuf> {
+    std::env::var_os(CONFIG_DIR_ENV)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
 /// Main Hub configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HubConfig {
@@ -19,17 +54,100 @@ pub struct HubConfig {
     #[serde(default)]
     pub start_minimized: bool,
 
-    /// Dark mode preference
+    /// UI theme
+    #[serde(default)]
+    pub theme: ThemeMode,
+
+    /// The `dark_mode` bool configs from before themes have instead of
+    /// `theme`. Moved into `theme` when the config is read (see `from_json`)
+    /// and never written back.
+    #[serde(default, skip_serializing)]
+    pub dark_mode: Option<bool>,
+
+    /// Whether to restart the tools that were running when Hub last ran
+    #[serde(default)]
+    pub restore_session: bool,
+
+    /// Global pause: no tools are started (manually or automatically) while set
+    #[serde(default)]
+    pub paused: bool,
+
+    /// Whether quitting Hub stops the tools it started (tools started outside
+    /// Hub are left alone either way)
+    #[serde(default)]
+    pub stop_tools_on_exit: bool,
+
+    /// Tools that were running in the last session (used by `restore_session`)
     #[serde(default)]
-    pub dark_mode: bool,
+    pub last_running: Vec<ToolId>,
+
+    /// Custom directory to search for tool binaries (checked before the defaults)
+    #[serde(default)]
+    pub tool_directory: Option<PathBuf>,
 
     /// Per-tool configuration
     #[serde(default)]
     pub tools: HashMap<ToolId, ToolConfig>,
 
+    /// Configuration for third-party tools, keyed by descriptor ID
+    #[serde(default)]
+    pub plugin_tools: HashMap<String, ToolConfig>,
+
     /// Registered hotkeys for all tools
     #[serde(default)]
     pub hotkeys: Vec<RegisteredHotkey>,
+
+    /// Seconds between automatic scans for tools started outside the hub
+    /// (`None` = only scan on startup or when asked)
+    #[serde(default)]
+    pub external_scan_interval_secs: Option<u64>,
+
+    /// Service the shared API key belongs to
+    #[serde(default)]
+    pub api_provider: ApiProvider,
+
+    /// API endpoint passed to tools (required for Azure and other
+    /// OpenAI-compatible servers, `None` = OpenAI's default)
+    #[serde(default)]
+    pub api_base_url: Option<String>,
+}
+
+/// UI color theme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeMode {
+    Light,
+    #[default]
+    Dark,
+    /// Follow the OS light/dark setting
+    System,
+}
+
+/// Service behind the shared API key. Tools speak the OpenAI API either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiProvider {
+    #[default]
+    OpenAi,
+    /// Azure OpenAI
+    Azure,
+    /// Any other OpenAI-compatible server, e.g. a local one
+    Compatible,
+}
+
+impl ApiProvider {
+    /// Environment variable tools read `api_base_url` from
+    pub fn base_url_env(self) -> &'static str {
+        match self {
+            ApiProvider::Azure => "AZURE_OPENAI_ENDPOINT",
+            ApiProvider::OpenAi | ApiProvider::Compatible => "OPENAI_BASE_URL",
+        }
+    }
+
+    /// Whether keys have OpenAI's `sk-` format
+    pub fn uses_openai_keys(self) -> bool {
+        self == ApiProvider::OpenAi
+    }
 }
 
 impl Default for HubConfig {
@@ -37,9 +155,19 @@ impl Default for HubConfig {
         Self {
             auto_start: false,
             start_minimized: false,
-            dark_mode: true, // Dark mode by default
+            theme: ThemeMode::Dark,
+            dark_mode: None,
+            restore_session: false,
+            paused: false,
+            stop_tools_on_exit: false,
+            last_running: Vec::new(),
+            tool_directory: None,
             tools: HashMap::new(),
+            plugin_tools: HashMap::new(),
             hotkeys: Vec::new(),
+            external_scan_interval_secs: None,
+            api_provider: ApiProvider::OpenAi,
+            api_base_url: None,
         }
     }
 }
@@ -47,7 +175,9 @@ impl Default for HubConfig {
 /// Per-tool configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolConfig {
-    /// Whether this tool is enabled
+    /// Whether this tool is enabled. Disabled tools are never started, whether
+    /// by auto-start or manually; one already running keeps running until
+    /// stopped.
     #[serde(default = "default_true")]
     pub enabled: bool,
 
@@ -63,19 +193,173 @@ pub struct ToolConfig {
     #[serde(default)]
     pub special_hotkey: Option<u32>,
 
+    /// Modifiers that must be held with `hotkey` (e.g. Ctrl for Ctrl+F13).
+    /// Only passed to tools that accept them. Set by editing `config.json`.
+    #[serde(default)]
+    pub modifiers: Vec<HotkeyModifier>,
+
     /// AI voice for TTS tools (speak-selected, quick-assistant)
     #[serde(default)]
     pub voice: Option<String>,
 
-    /// Speech speed for TTS tools (0.5 to 4.0, default 1.0)
+    /// Speech speed for TTS tools (0.25 to 4.0, default 1.0)
     #[serde(default)]
     pub speech_speed: Option<f32>,
 
+    /// How long to watch a freshly spawned process for an early exit, in
+    /// milliseconds (defaults to 500ms when unset)
+    #[serde(default)]
+    pub startup_grace_ms: Option<u64>,
+
+    /// Position in the auto-start sequence (lower starts first; ties keep the
+    /// default tool order)
+    #[serde(default)]
+    pub autostart_order: u32,
+
+    /// How long to wait after the previous auto-started tool before starting
+    /// this one, in milliseconds
+    #[serde(default)]
+    pub autostart_delay_ms: u32,
+
+    /// Extra command-line arguments appended after the hub's own (hotkey,
+    /// voice, ...), e.g. a model override. Each entry is passed as one
+    /// argument, never through a shell. Set by editing `config.json`.
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+
+    /// Extra environment variables for the tool (e.g. `RUST_LOG`, a model
+    /// name). Applied after the API key, so they can override it, but never
+    /// `PROTECTED_ENV_VARS`.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+
     /// Tool-specific settings (stored as JSON value for flexibility)
     #[serde(default)]
     pub settings: serde_json::Value,
 }
 
+/// Default startup health-check window for spawned tools
+pub const DEFAULT_STARTUP_GRACE_MS: u64 = 500;
+
+/// Shortest allowed auto-scan interval - a full scan lists every process
+pub const MIN_EXTERNAL_SCAN_INTERVAL_SECS: u64 = 10;
+
+/// Voices the TTS tools accept (OpenAI's text-to-speech voices)
+pub const TTS_VOICES: &[&str] = &[
+    "alloy", "ash", "coral", "echo", "fable", "nova", "onyx", "sage", "shimmer",
+];
+
+/// Variables a tool's `env` can't set: the system and hub depend on them, and
+/// overriding them changes which binaries and libraries get loaded
+pub const PROTECTED_ENV_VARS: &[&str] = &[
+    "PATH",
+    "PATHEXT",
+    "SYSTEMROOT",
+    "WINDIR",
+    "COMSPEC",
+    "HOME",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+    "TEMP",
+    "TMP",
+    "LD_PRELOAD",
+    "LD_LIBRARY_PATH",
+    "DYLD_INSERT_LIBRARIES",
+    "DYLD_LIBRARY_PATH",
+];
+
+/// Speech speed range the OpenAI text-to-speech API accepts
+pub const MIN_SPEECH_SPEED: f32 = 0.25;
+pub const MAX_SPEECH_SPEED: f32 = 4.0;
+
+/// An invalid value in a `ToolConfig`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolConfigError {
+    /// `voice` isn't one of `TTS_VOICES`
+    InvalidVoice { voice: String },
+    /// `speech_speed` is outside `MIN_SPEECH_SPEED..=MAX_SPEECH_SPEED`
+    InvalidSpeechSpeed { speed: f32 },
+    /// An `extra_args` entry is empty or contains control characters
+    InvalidExtraArg { arg: String },
+    /// An `env` name isn't a valid variable name, or its value contains
+    /// control characters
+    InvalidEnvVar { name: String },
+    /// An `env` name is one of `PROTECTED_ENV_VARS`
+    ProtectedEnvVar { name: String },
+}
+
+impl ToolConfigError {
+    /// Name of the offending `ToolConfig` field
+    pub fn field(&self) -> &'static str {
+        match self {
+            ToolConfigError::InvalidVoice { .. } => "voice",
+            ToolConfigError::InvalidSpeechSpeed { .. } => "speech_speed",
+            ToolConfigError::InvalidExtraArg { .. } => "extra_args",
+            ToolConfigError::InvalidEnvVar { .. } | ToolConfigError::ProtectedEnvVar { .. } => {
+                "env"
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ToolConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ToolConfigError::InvalidVoice { voice } => write!(
+                f,
+                "Unknown voice \"{}\" (expected one of: {})",
+                voice,
+                TTS_VOICES.join(", ")
+            ),
+            ToolConfigError::InvalidSpeechSpeed { speed } => write!(
+                f,
+                "Speech speed {} is out of range ({} to {})",
+                speed, MIN_SPEECH_SPEED, MAX_SPEECH_SPEED
+            ),
+            ToolConfigError::InvalidExtraArg { arg } => write!(
+                f,
+                "Extra argument {:?} is empty or contains control characters",
+                arg
+            ),
+            ToolConfigError::InvalidEnvVar { name } => write!(
+                f,
+                "Environment variable {:?} needs a name of letters, digits and \
+                 underscores, and a value without control characters",
+                name
+            ),
+            ToolConfigError::ProtectedEnvVar { name } => {
+                write!(f, "Environment variable {} can't be set per tool", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ToolConfigError {}
+
+/// Args go straight to the process (no shell, so quoting and metacharacters
+/// are harmless), but an empty arg or an embedded NUL/newline is never
+/// intended and can confuse a tool's argument parser
+pub fn is_valid_extra_arg(arg: &str) -> bool {
+    !arg.is_empty() && !arg.chars().any(char::is_control)
+}
+
+/// Whether a tool's `env` may set `name` to `value`: a portable variable name
+/// (letters, digits, underscores, not starting with a digit) outside
+/// `PROTECTED_ENV_VARS`, and a value without control characters
+pub fn check_env_var(name: &str, value: &str) -> Result<(), ToolConfigError> {
+    let valid_name = name.chars().next().is_some_and(|c| !c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid_name || value.chars().any(char::is_control) {
+        return Err(ToolConfigError::InvalidEnvVar { name: name.to_string() });
+    }
+    // Windows variable names are case-insensitive
+    if PROTECTED_ENV_VARS.iter().any(|protected| protected.eq_ignore_ascii_case(name)) {
+        return Err(ToolConfigError::ProtectedEnvVar { name: name.to_string() });
+    }
+    Ok(())
+}
+
 fn default_true() -> bool {
     true
 }
@@ -87,21 +371,102 @@ impl Default for ToolConfig {
             auto_start: false,
             hotkey: None,
             special_hotkey: None,
+            modifiers: Vec::new(),
             voice: None,
             speech_speed: None,
+            startup_grace_ms: None,
+            autostart_order: 0,
+            autostart_delay_ms: 0,
+            extra_args: Vec::new(),
+            env: HashMap::new(),
             settings: serde_json::Value::Null,
         }
     }
 }
 
+impl ToolConfig {
+    /// Whether switching from `other` to this config changes the CLI args a tool
+    /// is launched with (so a running instance must be restarted to pick it up)
+    pub fn launch_args_differ(&self, other: &ToolConfig) -> bool {
+        self.hotkey != other.hotkey
+            || self.special_hotkey != other.special_hotkey
+            || self.modifiers != other.modifiers
+            || self.voice != other.voice
+            || self.speech_speed != other.speech_speed
+            || self.extra_args != other.extra_args
+            || self.env != other.env
+    }
+
+    /// The key the tool is triggered with: `hotkey` if it names a known key,
+    /// else `special_hotkey`
+    pub fn hotkey_key(&self) -> Option<HotkeyKey> {
+        match self.hotkey.as_deref() {
+            Some(name) => NamedKey::from_config_name(name).map(HotkeyKey::Named),
+            None => self.special_hotkey.map(HotkeyKey::Unknown),
+        }
+    }
+
+    /// Why the configured hotkey may never reach the tool, if the OS commonly
+    /// reserves it (see `HotkeyRegistry::is_system_reserved`)
+    pub fn reserved_hotkey_reason(&self) -> Option<&'static str> {
+        let key = NamedKey::from_config_name(self.hotkey.as_deref()?)?;
+        HotkeyRegistry::is_system_reserved(&HotkeyKey::Named(key), &self.modifiers)
+    }
+
+    /// Check the values a tool would be launched with (voice, speech speed,
+    /// extra args, environment)
+    pub fn validate(&self) -> Result<(), ToolConfigError> {
+        if let Some(ref voice) = self.voice {
+            if !TTS_VOICES.contains(&voice.as_str()) {
+                return Err(ToolConfigError::InvalidVoice {
+                    voice: voice.clone(),
+                });
+            }
+        }
+
+        if let Some(speed) = self.speech_speed {
+            // NaN fails the range check too
+            if !(MIN_SPEECH_SPEED..=MAX_SPEECH_SPEED).contains(&speed) {
+                return Err(ToolConfigError::InvalidSpeechSpeed { speed });
+            }
+        }
+
+        if let Some(arg) = self.extra_args.iter().find(|arg| !is_valid_extra_arg(arg)) {
+            return Err(ToolConfigError::InvalidExtraArg { arg: arg.clone() });
+        }
+
+        for (name, value) in &self.env {
+            check_env_var(name, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Time to wait after spawning before the tool is considered healthy
+    pub fn startup_grace(&self) -> Duration {
+        Duration::from_millis(self.startup_grace_ms.unwrap_or(DEFAULT_STARTUP_GRACE_MS))
+    }
+}
+
 impl HubConfig {
-    /// Get the configuration directory path
+    /// How often to scan for externally started tools, if auto-scan is on
+    pub fn external_scan_interval(&self) -> Option<Duration> {
+        self.external_scan_interval_secs
+            .filter(|&secs| secs > 0)
+            .map(|secs| Duration::from_secs(secs.max(MIN_EXTERNAL_SCAN_INTERVAL_SECS)))
+    }
+
+    /// The API endpoint to pass to tools, if one is set
+    pub fn api_base_url(&self) -> Option<&str> {
+        self.api_base_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+    }
+
+    /// Get the configuration directory path (see `app_dirs`)
     pub fn config_dir() -> Result<PathBuf> {
-        let proj_dirs = ProjectDirs::from("com", "hub", "productivity-hub")
-            .context("Failed to determine project directories")?;
-        let config_dir = proj_dirs.config_dir().to_path_buf();
-        fs::create_dir_all(&config_dir)?;
-        Ok(config_dir)
+        app_dirs()
     }
 
     /// Get the configuration file path
@@ -109,18 +474,76 @@ impl HubConfig {
         Ok(Self::config_dir()?.join("config.json"))
     }
 
-    /// Load configuration from disk
+    /// Get the directory third-party tool descriptors are loaded from
+    pub fn plugin_dir() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("tools.d"))
+    }
+
+    /// Load configuration from disk (see `load_or_recover`)
     pub fn load() -> Result<Self> {
+        Self::load_or_recover().map(|(config, _)| config)
+    }
+
+    /// Load configuration from disk. A config file that can't be parsed is
+    /// moved to `config.json.corrupt-<timestamp>` and defaults are used
+    /// instead, so one bad edit doesn't lose the settings for good; the
+    /// returned `ConfigRecovery` says where the file went.
+    pub fn load_or_recover() -> Result<(Self, Option<ConfigRecovery>)> {
         let config_path = Self::config_path()?;
+        let lock = lock_config(&config_path, false)?;
+        if let Ok(config) = read_config_file(&config_path)? {
+            return Ok((config, None));
+        }
+        drop(lock);
 
-        if config_path.exists() {
-            let contents =
-                fs::read_to_string(&config_path).context("Failed to read config file")?;
-            let config: HubConfig =
-                serde_json::from_str(&contents).context("Failed to parse config file")?;
-            Ok(config)
-        } else {
-            Ok(HubConfig::default())
+        // Moving the file aside needs the exclusive lock. A shared lock can't
+        // be upgraded in place, so read the file again once it's held: another
+        // process may have fixed or replaced it meanwhile.
+        let _lock = lock_config(&config_path, true)?;
+        let (contents, e) = match read_config_file(&config_path)? {
+            Ok(config) => return Ok((config, None)),
+            Err(invalid) => invalid,
+        };
+
+        // Explain hand-edited mistakes field by field where possible
+        let error = match HubConfig::validate_json(&contents) {
+            Err(errors) => join_validation_errors(&errors),
+            Ok(()) => e.to_string(),
+        };
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let backup_path = config_path.with_extension(format!("json.corrupt-{}", timestamp));
+        fs::rename(&config_path, &backup_path).with_context(|| {
+            format!(
+                "Invalid config file {} ({}), and failed to move it aside",
+                config_path.display(),
+                error
+            )
+        })?;
+        warn!(
+            "Invalid config file {}: {}. Moved it to {} and using defaults",
+            config_path.display(),
+            error,
+            backup_path.display()
+        );
+        Ok((HubConfig::default(), Some(ConfigRecovery { backup_path, error })))
+    }
+
+    /// Parse a config file, moving a legacy `dark_mode` into `theme`
+    pub fn from_json(contents: &str) -> serde_json::Result<Self> {
+        let mut config: HubConfig = serde_json::from_str(contents)?;
+        if config.dark_mode.is_some() {
+            config.migrate_dark_mode(&serde_json::from_str(contents)?);
+        }
+        Ok(config)
+    }
+
+    /// Read `dark_mode` as `Dark` (true) or `Light` (false), unless the config
+    /// `value` it was parsed from also has a `theme`, which wins
+    fn migrate_dark_mode(&mut self, value: &serde_json::Value) {
+        if let Some(dark_mode) = self.dark_mode.take() {
+            if value.get("theme").is_none() {
+                self.theme = if dark_mode { ThemeMode::Dark } else { ThemeMode::Light };
+            }
         }
     }
 
@@ -128,7 +551,8 @@ impl HubConfig {
     pub fn save(&self) -> Result<()> {
         let config_path = Self::config_path()?;
         let contents = serde_json::to_string_pretty(self).context("Failed to serialize config")?;
-        fs::write(&config_path, contents).context("Failed to write config file")?;
+        let _lock = lock_config(&config_path, true)?;
+        write_with_retry(&config_path, &contents).context("Failed to write config file")?;
         Ok(())
     }
 
@@ -141,6 +565,496 @@ impl HubConfig {
     pub fn set_tool_config(&mut self, tool_id: ToolId, config: ToolConfig) {
         self.tools.insert(tool_id, config);
     }
+
+    /// The hotkeys built-in tools are configured with, for conflict checks
+    pub fn tool_hotkey_registry(&self) -> HotkeyRegistry {
+        let hotkeys = self
+            .tools
+            .iter()
+            .filter_map(|(tool_id, tool_config)| {
+                Some(RegisteredHotkey {
+                    tool_id: tool_id.clone(),
+                    action_name: format!("{} hotkey", tool_id.display_name()),
+                    key: tool_config.hotkey_key()?,
+                    modifiers: tool_config.modifiers.clone(),
+                })
+            })
+            .collect();
+        HotkeyRegistry::from_hotkeys(hotkeys)
+    }
+}
+
+/// Read and parse the config file (defaults if there is none). A file that
+/// doesn't parse is returned with the error.
+fn read_config_file(config_path: &Path) -> Result<Result<HubConfig, (String, serde_json::Error)>> {
+    if !config_path.exists() {
+        return Ok(Ok(HubConfig::default()));
+    }
+    let contents = fs::read_to_string(config_path).context("Failed to read config file")?;
+    Ok(HubConfig::from_json(&contents).map_err(|e| (contents, e)))
+}
+
+/// A config file `HubConfig::load_or_recover` couldn't parse and replaced
+/// with defaults
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigRecovery {
+    /// Where the unreadable file was moved
+    pub backup_path: PathBuf,
+    /// Why it couldn't be parsed
+    pub error: String,
+}
+
+// === Cross-process locking ===
+
+/// How long `load`/`save` wait for another process (a tool or a second
+/// dashboard) to release the config before giving up
+const CONFIG_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Lock `config.json` against other processes: shared for reads, exclusive for
+/// writes. A sidecar `config.json.lock` is locked rather than the config file
+/// itself, which gets truncated on every write. Released when dropped.
+///
+/// Returns `None` for a read when the lock file can't be opened, e.g. in a
+/// read-only directory: the config is then read unlocked rather than not at
+/// all. Nothing can be saved there anyway.
+fn lock_config(config_path: &Path, exclusive: bool) -> Result<Option<fs::File>> {
+    let lock_path = config_path.with_extension("json.lock");
+    let opened = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path);
+    let file = match opened {
+        Ok(file) => file,
+        Err(e) if !exclusive => {
+            warn!("Reading the config unlocked, can't open {}: {}", lock_path.display(), e);
+            return Ok(None);
+        }
+        Err(e) => {
+            return Err(e)
+                .with_context(|| format!("Failed to open config lock {}", lock_path.display()))
+        }
+    };
+
+    let deadline = Instant::now() + CONFIG_LOCK_TIMEOUT;
+    loop {
+        // Called through the trait: `std::fs::File` has inherent methods of the same name
+        let locked = if exclusive {
+            FileExt::try_lock_exclusive(&file)
+        } else {
+            FileExt::try_lock_shared(&file)
+        }
+        .context("Failed to lock config file")?;
+
+        if locked {
+            return Ok(Some(file));
+        }
+        if Instant::now() >= deadline {
+            anyhow::bail!(
+                "Timed out waiting for another process to release {}",
+                config_path.display()
+            );
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Attempts `write_with_retry` makes before giving up
+const WRITE_ATTEMPTS: u64 = 4;
+
+/// Write a file, retrying for a moment while something else holds it (on
+/// Windows, antivirus and backup tools briefly lock freshly written files).
+/// Other errors, e.g. a full disk, fail right away.
+pub fn write_with_retry(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut attempt = 1;
+    loop {
+        match fs::write(path, contents) {
+            Err(e) if attempt < WRITE_ATTEMPTS && is_transient_write_error(&e) => {
+                warn!("Writing {} failed ({}), retrying", path.display(), e);
+                std::thread::sleep(Duration::from_millis(100 * attempt));
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn is_transient_write_error(error: &std::io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION
+    #[cfg(windows)]
+    if matches!(error.raw_os_error(), Some(32 | 33)) {
+        return true;
+    }
+    matches!(
+        error.kind(),
+        std::io::ErrorKind::PermissionDenied | std::io::ErrorKind::ResourceBusy
+    )
+}
+
+// === Export / Import ===
+
+/// Tool configs with their `env` cleared, for exports
+fn without_env<K: Clone + Eq + std::hash::Hash>(
+    tools: &HashMap<K, ToolConfig>,
+) -> HashMap<K, ToolConfig> {
+    tools
+        .iter()
+        .map(|(id, tc)| (id.clone(), ToolConfig { env: HashMap::new(), ..tc.clone() }))
+        .collect()
+}
+
+/// Current version of the portable config export format
+pub const CONFIG_EXPORT_VERSION: u32 = 1;
+
+/// Portable config file for moving settings to another machine.
+///
+/// Secrets are never included; `has_api_key` only records whether one was set
+/// so the importing side can prompt for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigExport {
+    /// Export format version (`CONFIG_EXPORT_VERSION` when written)
+    pub version: u32,
+    /// When the export was written (RFC3339)
+    #[serde(default)]
+    pub exported_at: Option<String>,
+    /// Whether an OpenAI API key was configured on the exporting machine
+    #[serde(default)]
+    pub has_api_key: bool,
+    /// The exported settings
+    pub config: HubConfig,
+}
+
+impl HubConfig {
+    /// Write the config (tools, hotkeys, preferences) to a portable JSON file.
+    ///
+    /// Machine-specific state (tool directory, last session's running tools)
+    /// is left out, as are per-tool `env` variables, which may hold secrets.
+    pub fn export_to(&self, path: &Path) -> Result<()> {
+        let export = ConfigExport {
+            version: CONFIG_EXPORT_VERSION,
+            exported_at: Some(chrono::Utc::now().to_rfc3339()),
+            has_api_key: has_api_key(),
+            config: HubConfig {
+                tool_directory: None,
+                last_running: Vec::new(),
+                tools: without_env(&self.tools),
+                plugin_tools: without_env(&self.plugin_tools),
+                ..self.clone()
+            },
+        };
+        let contents =
+            serde_json::to_string_pretty(&export).context("Failed to serialize config export")?;
+        fs::write(path, contents)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Read a portable config file written by `export_to`.
+    ///
+    /// A plain `config.json` copied from another machine (which predates the
+    /// export format) is accepted as version 0.
+    pub fn import_from(path: &Path) -> Result<ConfigExport> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let value: serde_json::Value =
+            serde_json::from_str(&contents).context("Config file is not valid JSON")?;
+
+        let export = match value.get("version").and_then(|v| v.as_u64()) {
+            Some(version) if version > CONFIG_EXPORT_VERSION as u64 => {
+                anyhow::bail!(
+                    "Config was exported by a newer Hub (format {}, this version reads up to {})",
+                    version,
+                    CONFIG_EXPORT_VERSION
+                );
+            }
+            Some(_) => {
+                let config = value.get("config").cloned().unwrap_or_default();
+                check_config_value(&config, "config")?;
+                let mut export = serde_json::from_value::<ConfigExport>(value)
+                    .context("Config export doesn't match the expected format")?;
+                export.config.migrate_dark_mode(&config);
+                export
+            }
+            // Version 0: raw config.json
+            None => {
+                check_config_value(&value, "")?;
+                ConfigExport {
+                    version: 0,
+                    exported_at: None,
+                    has_api_key: false,
+                    config: {
+                        let mut config: HubConfig = serde_json::from_value(value.clone())
+                            .context("Config file doesn't match the expected format")?;
+                        config.migrate_dark_mode(&value);
+                        config
+                    },
+                }
+            }
+        };
+
+        Ok(export)
+    }
+}
+
+// === Validation ===
+
+/// One problem found by `HubConfig::validate_json`, e.g.
+/// `tools.desk-talk.speech_speed` "must be a number or null"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigValidationError {
+    /// Dotted path to the offending value (`config` for the whole document)
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.path, self.message)
+    }
+}
+
+impl std::error::Error for ConfigValidationError {}
+
+/// Expected JSON type of a config field
+#[derive(Clone, Copy)]
+enum FieldType {
+    Bool,
+    String,
+    UInt,
+    OptUInt,
+    OptNumber,
+    OptString,
+    StringList,
+    /// Object with string values
+    StringMap,
+    /// Free-form (`settings`)
+    Any,
+}
+
+impl FieldType {
+    /// What the value must be, if `value` doesn't match
+    fn mismatch(self, value: &serde_json::Value) -> Option<&'static str> {
+        let ok = match self {
+            FieldType::Bool => value.is_boolean(),
+            FieldType::String => value.is_string(),
+            FieldType::UInt => value.is_u64(),
+            FieldType::OptUInt => value.is_null() || value.is_u64(),
+            FieldType::OptNumber => value.is_null() || value.is_number(),
+            FieldType::OptString => value.is_null() || value.is_string(),
+            FieldType::StringList => value
+                .as_array()
+                .is_some_and(|items| items.iter().all(|item| item.is_string())),
+            FieldType::StringMap => value
+                .as_object()
+                .is_some_and(|items| items.values().all(|item| item.is_string())),
+            FieldType::Any => true,
+        };
+        if ok {
+            return None;
+        }
+        Some(match self {
+            FieldType::Bool => "must be true or false",
+            FieldType::String => "must be a string",
+            FieldType::UInt => "must be a whole number",
+            FieldType::OptUInt => "must be a whole number or null",
+            FieldType::OptNumber => "must be a number or null",
+            FieldType::OptString => "must be a string or null",
+            FieldType::StringList => "must be a list of strings",
+            FieldType::StringMap => "must be an object with string values",
+            FieldType::Any => unreachable!(),
+        })
+    }
+}
+
+/// Top-level `HubConfig` fields with a simple type (the maps and lists are
+/// checked separately)
+const HUB_CONFIG_FIELDS: &[(&str, FieldType)] = &[
+    ("auto_start", FieldType::Bool),
+    ("start_minimized", FieldType::Bool),
+    ("theme", FieldType::String),
+    // Legacy name of `theme`
+    ("dark_mode", FieldType::Bool),
+    ("restore_session", FieldType::Bool),
+    ("paused", FieldType::Bool),
+    ("stop_tools_on_exit", FieldType::Bool),
+    ("tool_directory", FieldType::OptString),
+    ("external_scan_interval_secs", FieldType::OptUInt),
+    ("api_provider", FieldType::String),
+    ("api_base_url", FieldType::OptString),
+];
+
+/// `ToolConfig` fields
+const TOOL_CONFIG_FIELDS: &[(&str, FieldType)] = &[
+    ("enabled", FieldType::Bool),
+    ("auto_start", FieldType::Bool),
+    ("hotkey", FieldType::OptString),
+    ("special_hotkey", FieldType::OptUInt),
+    ("modifiers", FieldType::StringList),
+    ("voice", FieldType::OptString),
+    ("speech_speed", FieldType::OptNumber),
+    ("startup_grace_ms", FieldType::OptUInt),
+    ("autostart_order", FieldType::UInt),
+    ("autostart_delay_ms", FieldType::UInt),
+    ("extra_args", FieldType::StringList),
+    ("env", FieldType::StringMap),
+    ("settings", FieldType::Any),
+];
+
+impl HubConfig {
+    /// Check a `config.json` document, reporting every problem with the
+    /// field it's in instead of serde's first error. Unknown fields are
+    /// ignored, as they are when loading.
+    pub fn validate_json(json: &str) -> Result<(), Vec<ConfigValidationError>> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|e| {
+            vec![ConfigValidationError {
+                path: "config".to_string(),
+                message: format!("is not valid JSON ({})", e),
+            }]
+        })?;
+        validate_config_value(&value, "")
+    }
+}
+
+fn validate_config_value(
+    value: &serde_json::Value,
+    path: &str,
+) -> Result<(), Vec<ConfigValidationError>> {
+    let mut errors = Vec::new();
+    // `path` is where the config sits in the document ("" for config.json)
+    let root = if path.is_empty() { "config" } else { path };
+    let prefix = if path.is_empty() {
+        String::new()
+    } else {
+        format!("{}.", path)
+    };
+
+    let Some(fields) = value.as_object() else {
+        push_error(&mut errors, root.to_string(), "must be a JSON object");
+        return Err(errors);
+    };
+
+    for (name, field_type) in HUB_CONFIG_FIELDS {
+        if let Some(message) = fields.get(*name).and_then(|v| field_type.mismatch(v)) {
+            push_error(&mut errors, format!("{}{}", prefix, name), message);
+        }
+    }
+
+    if let Some(last_running) = fields.get("last_running") {
+        match last_running.as_array() {
+            Some(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    if serde_json::from_value::<ToolId>(item.clone()).is_err() {
+                        push_error(
+                            &mut errors,
+                            format!("{}last_running[{}]", prefix, i),
+                            "is not a known tool".to_string(),
+                        );
+                    }
+                }
+            }
+            None => push_error(
+                &mut errors,
+                format!("{}last_running", prefix),
+                "must be a list",
+            ),
+        }
+    }
+
+    for map in ["tools", "plugin_tools"] {
+        let Some(tools) = fields.get(map) else {
+            continue;
+        };
+        let Some(tools) = tools.as_object() else {
+            push_error(
+                &mut errors,
+                format!("{}{}", prefix, map),
+                "must be a JSON object",
+            );
+            continue;
+        };
+        for (key, tool) in tools {
+            let tool_path = format!("{}{}.{}", prefix, map, key);
+            if map == "tools" && key.parse::<ToolId>().is_err() {
+                push_error(&mut errors, tool_path, "is not a known tool");
+                continue;
+            }
+            let Some(tool_fields) = tool.as_object() else {
+                push_error(&mut errors, tool_path, "must be a JSON object");
+                continue;
+            };
+
+            let mut well_typed = true;
+            for (name, field_type) in TOOL_CONFIG_FIELDS {
+                if let Some(message) = tool_fields.get(*name).and_then(|v| field_type.mismatch(v)) {
+                    push_error(&mut errors, format!("{}.{}", tool_path, name), message);
+                    well_typed = false;
+                }
+            }
+            // Values of the right type can still be out of range
+            if well_typed {
+                if let Ok(tool_config) = serde_json::from_value::<ToolConfig>(tool.clone()) {
+                    if let Err(e) = tool_config.validate() {
+                        push_error(
+                            &mut errors,
+                            format!("{}.{}", tool_path, e.field()),
+                            format!("is invalid: {}", e),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(hotkeys) = fields.get("hotkeys") {
+        match hotkeys.as_array() {
+            Some(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    if let Err(e) = serde_json::from_value::<RegisteredHotkey>(item.clone()) {
+                        push_error(
+                            &mut errors,
+                            format!("{}hotkeys[{}]", prefix, i),
+                            format!("is invalid: {}", e),
+                        );
+                    }
+                }
+            }
+            None => push_error(&mut errors, format!("{}hotkeys", prefix), "must be a list"),
+        }
+    }
+
+    // Anything the checks above don't cover still has to deserialize
+    if errors.is_empty() {
+        if let Err(e) = serde_json::from_value::<HubConfig>(value.clone()) {
+            push_error(&mut errors, root.to_string(), format!("is invalid: {}", e));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn push_error(errors: &mut Vec<ConfigValidationError>, path: String, message: impl Into<String>) {
+    errors.push(ConfigValidationError {
+        path,
+        message: message.into(),
+    });
+}
+
+/// `validate_config_value` as an `anyhow` error listing every problem
+fn check_config_value(value: &serde_json::Value, path: &str) -> Result<()> {
+    validate_config_value(value, path)
+        .map_err(|errors| anyhow::anyhow!("Invalid config: {}", join_validation_errors(&errors)))
+}
+
+fn join_validation_errors(errors: &[ConfigValidationError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 // === API Key Management ===
@@ -148,40 +1062,120 @@ impl HubConfig {
 const KEYRING_SERVICE: &str = "productivity-hub";
 const KEYRING_USER: &str = "openai-api-key";
 
-/// Load the shared OpenAI API key from secure storage
-pub fn load_api_key() -> Result<String> {
-    // Try keyring first
-    if let Ok(entry) = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER) {
-        if let Ok(key) = entry.get_password() {
-            return Ok(key);
+/// Environment variables overriding the keyring service/user, so separate
+/// installs on one OS account (e.g. work and personal profiles) keep separate
+/// API keys
+pub const KEYRING_SERVICE_ENV: &str = "HUB_KEYRING_SERVICE";
+pub const KEYRING_USER_ENV: &str = "HUB_KEYRING_USER";
+
+/// Why the API key couldn't be loaded or stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiKeyError {
+    /// Neither the keyring nor the `.env` fallback holds a key
+    NotFound,
+    /// The OS has no usable keyring (e.g. headless Linux without a secret
+    /// service) and the `.env` fallback couldn't be used either
+    KeyringUnavailable(String),
+    /// Reading or writing the stored key failed
+    Storage(String),
+}
+
+impl std::fmt::Display for ApiKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiKeyError::NotFound => write!(f, "No API key found in keyring or .env file"),
+            ApiKeyError::KeyringUnavailable(message) => {
+                write!(f, "No system keyring available: {}", message)
+            }
+            ApiKeyError::Storage(message) => write!(f, "{}", message),
         }
     }
+}
 
-    // Fallback to .env file in config directory
-    load_api_key_from_env()
+impl std::error::Error for ApiKeyError {}
+
+/// The keyring entry holding the API key (defaults unless overridden)
+fn keyring_entry() -> keyring::Result<keyring::Entry> {
+    let setting = |var: &str, default: &str| {
+        std::env::var(var)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| default.to_string())
+    };
+    let service = setting(KEYRING_SERVICE_ENV, KEYRING_SERVICE);
+    let user = setting(KEYRING_USER_ENV, KEYRING_USER);
+    keyring::Entry::new(&service, &user)
 }
 
-/// Save the shared OpenAI API key to secure storage
-pub fn save_api_key(api_key: &str) -> Result<()> {
-    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
-        .context("Failed to create keyring entry")?;
-    entry
-        .set_password(api_key)
-        .context("Failed to save API key to keyring")?;
+/// Whether a keyring error means there's no usable keyring backend at all
+/// (as opposed to e.g. the entry simply not existing)
+fn is_keyring_unavailable(error: &keyring::Error) -> bool {
+    matches!(
+        error,
+        keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_)
+    )
+}
 
-    // Also save to .env as backup
-    let _ = save_api_key_to_env(api_key);
+/// Whether the API key can be kept in the OS keyring. When this is false the
+/// key is only stored in the config directory's `.env` file.
+pub fn has_secure_storage() -> bool {
+    match keyring_entry().and_then(|entry| entry.get_password()) {
+        Ok(_) => true,
+        Err(e) => !is_keyring_unavailable(&e),
+    }
+}
 
-    Ok(())
+/// Load the shared OpenAI API key from secure storage
+pub fn load_api_key() -> std::result::Result<String, ApiKeyError> {
+    // Try keyring first
+    let keyring_error = match keyring_entry().and_then(|entry| entry.get_password()) {
+        Ok(key) => return Ok(key),
+        Err(e) if is_keyring_unavailable(&e) => Some(e.to_string()),
+        Err(_) => None,
+    };
+
+    // Fallback to .env file in config directory
+    match load_api_key_from_env() {
+        Ok(Some(key)) => Ok(key),
+        Ok(None) => Err(ApiKeyError::NotFound),
+        Err(e) => Err(match keyring_error {
+            Some(message) => ApiKeyError::KeyringUnavailable(message),
+            None => ApiKeyError::Storage(format!("{:#}", e)),
+        }),
+    }
 }
 
-/// Delete the shared OpenAI API key from secure storage
-pub fn delete_api_key() -> Result<()> {
-    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
-        .context("Failed to create keyring entry")?;
-    entry
-        .delete_credential()
-        .context("Failed to delete API key from keyring")
+/// Save the shared OpenAI API key to secure storage.
+///
+/// Without a usable keyring the key goes to the `.env` file only.
+pub fn save_api_key(api_key: &str) -> std::result::Result<(), ApiKeyError> {
+    match keyring_entry().and_then(|entry| entry.set_password(api_key)) {
+        Ok(()) => {
+            // Also save to .env as backup
+            let _ = save_api_key_to_env(api_key);
+            Ok(())
+        }
+        Err(e) if is_keyring_unavailable(&e) => save_api_key_to_env(api_key)
+            .map_err(|env_error| {
+                ApiKeyError::KeyringUnavailable(format!("{}; {:#}", e, env_error))
+            }),
+        Err(e) => Err(ApiKeyError::Storage(format!("Failed to save API key to keyring: {}", e))),
+    }
+}
+
+/// Delete the shared OpenAI API key from secure storage (and the `.env` copy)
+pub fn delete_api_key() -> std::result::Result<(), ApiKeyError> {
+    delete_api_key_from_env().map_err(|e| ApiKeyError::Storage(format!("{:#}", e)))?;
+
+    match keyring_entry().and_then(|entry| entry.delete_credential()) {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) if is_keyring_unavailable(&e) => Ok(()),
+        Err(e) => Err(ApiKeyError::Storage(format!(
+            "Failed to delete API key from keyring: {}",
+            e
+        ))),
+    }
 }
 
 /// Check if an API key is configured
@@ -189,27 +1183,46 @@ pub fn has_api_key() -> bool {
     load_api_key().is_ok()
 }
 
-fn load_api_key_from_env() -> Result<String> {
-    let config_dir = HubConfig::config_dir()?;
-    let env_path = config_dir.join(".env");
+fn env_path() -> Result<PathBuf> {
+    Ok(HubConfig::config_dir()?.join(".env"))
+}
 
-    if env_path.exists() {
-        let contents = fs::read_to_string(&env_path)?;
-        for line in contents.lines() {
-            if let Some(key) = line.strip_prefix("OPENAI_API_KEY=") {
-                return Ok(key.to_string());
-            }
-        }
+fn load_api_key_from_env() -> Result<Option<String>> {
+    let env_path = env_path()?;
+    if !env_path.exists() {
+        return Ok(None);
     }
 
-    Err(anyhow::anyhow!("No API key found in keyring or .env file"))
+    let contents = fs::read_to_string(&env_path)
+        .with_context(|| format!("Failed to read {}", env_path.display()))?;
+    Ok(contents
+        .lines()
+        .find_map(|line| line.strip_prefix("OPENAI_API_KEY="))
+        .map(str::to_string))
 }
 
 fn save_api_key_to_env(api_key: &str) -> Result<()> {
-    let config_dir = HubConfig::config_dir()?;
-    let env_path = config_dir.join(".env");
+    let env_path = env_path()?;
     fs::write(&env_path, format!("OPENAI_API_KEY={}", api_key))
         .context("Failed to write .env file")?;
+
+    // Only the current user may read the plain-text key
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&env_path, fs::Permissions::from_mode(0o600))
+            .context("Failed to restrict .env file permissions")?;
+    }
+
+    Ok(())
+}
+
+fn delete_api_key_from_env() -> Result<()> {
+    let env_path = env_path()?;
+    if env_path.exists() {
+        fs::remove_file(&env_path)
+            .with_context(|| format!("Failed to remove {}", env_path.display()))?;
+    }
     Ok(())
 }
 
@@ -255,3 +1268,149 @@ pub fn enable_autostart() -> Result<()> {
 pub fn disable_autostart() -> Result<()> {
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    /// Serializes tests that point `HUB_CONFIG_DIR` somewhere
+    static CONFIG_DIR_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Run `test` with the config dir set to a fresh temporary directory
+    fn with_config_dir<T>(test: impl FnOnce(&Path) -> T) -> T {
+        let _guard = CONFIG_DIR_LOCK.lock();
+        let dir = tempfile::tempdir().unwrap();
+        std::env::set_var(CONFIG_DIR_ENV, dir.path());
+        let result = test(dir.path());
+        std::env::remove_var(CONFIG_DIR_ENV);
+        result
+    }
+
+    fn theme_of(json: &str) -> ThemeMode {
+        HubConfig::from_json(json).unwrap().theme
+    }
+
+    #[test]
+    fn legacy_dark_mode_migrates_to_theme() {
+        assert_eq!(theme_of(r#"{"dark_mode": true}"#), ThemeMode::Dark);
+        assert_eq!(theme_of(r#"{"dark_mode": false}"#), ThemeMode::Light);
+    }
+
+    #[test]
+    fn theme_wins_over_legacy_dark_mode() {
+        assert_eq!(theme_of(r#"{"theme": "system", "dark_mode": false}"#), ThemeMode::System);
+        assert_eq!(theme_of(r#"{"dark_mode": true, "theme": "light"}"#), ThemeMode::Light);
+    }
+
+    #[test]
+    fn theme_is_read_and_saved_by_name() {
+        assert_eq!(theme_of(r#"{"theme": "system"}"#), ThemeMode::System);
+        assert_eq!(theme_of("{}"), ThemeMode::Dark);
+
+        let config = HubConfig { theme: ThemeMode::System, ..HubConfig::default() };
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["theme"], "system");
+        assert!(json.get("dark_mode").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn config_in_a_read_only_directory_is_read_unlocked() {
+        use std::os::unix::fs::PermissionsExt;
+
+        with_config_dir(|dir| {
+            let contents = r#"{"theme": "light"}"#;
+            fs::write(dir.join("config.json"), contents).unwrap();
+            fs::set_permissions(dir, fs::Permissions::from_mode(0o555)).unwrap();
+
+            // Permissions don't stop root, so there's nothing to check then
+            let writable = fs::write(dir.join("probe"), "").is_ok();
+            let loaded = (!writable).then(HubConfig::load);
+            fs::set_permissions(dir, fs::Permissions::from_mode(0o755)).unwrap();
+
+            if let Some(loaded) = loaded {
+                assert_eq!(loaded.unwrap().theme, ThemeMode::Light);
+                assert!(!dir.join("config.json.lock").exists());
+            }
+        });
+    }
+
+    #[test]
+    fn corrupt_config_is_backed_up_and_reset() {
+        with_config_dir(|dir| {
+            let config_path = dir.join("config.json");
+            fs::write(&config_path, "{ not json").unwrap();
+
+            let (config, recovery) = HubConfig::load_or_recover().unwrap();
+
+            let recovery = recovery.expect("corrupt config should be recovered");
+            assert!(recovery.backup_path.starts_with(dir));
+            assert!(recovery.backup_path.to_string_lossy().contains("config.json.corrupt-"));
+            assert_eq!(fs::read_to_string(&recovery.backup_path).unwrap(), "{ not json");
+            assert!(!config_path.exists());
+            assert!(!recovery.error.is_empty());
+            assert_eq!(config.theme, HubConfig::default().theme);
+
+            // The defaults load cleanly from then on
+            let (_, recovery) = HubConfig::load_or_recover().unwrap();
+            assert!(recovery.is_none());
+        });
+    }
+
+    #[test]
+    fn missing_config_loads_defaults_without_recovery() {
+        with_config_dir(|dir| {
+            let (_, recovery) = HubConfig::load_or_recover().unwrap();
+            assert!(recovery.is_none());
+            assert!(!dir.join("config.json").exists());
+        });
+    }
+
+    #[test]
+    fn concurrent_saves_leave_valid_json() {
+        with_config_dir(|dir| {
+            let writers: Vec<_> = (0..2)
+                .map(|writer| {
+                    std::thread::spawn(move || {
+                        for round in 0..25 {
+                            // Differing lengths, so an interleaved write would leave junk
+                            let config = HubConfig {
+                                api_base_url: Some("x".repeat(writer * 500 + round)),
+                                ..HubConfig::default()
+                            };
+                            config.save().unwrap();
+                        }
+                    })
+                })
+                .collect();
+            for writer in writers {
+                writer.join().unwrap();
+            }
+
+            let contents = fs::read_to_string(dir.join("config.json")).unwrap();
+            assert!(serde_json::from_str::<HubConfig>(&contents).is_ok(), "{}", contents);
+        });
+    }
+
+    #[test]
+    fn loads_during_saves_see_whole_files() {
+        with_config_dir(|_| {
+            HubConfig::default().save().unwrap();
+            let writer = std::thread::spawn(|| {
+                for round in 0..25 {
+                    let config = HubConfig {
+                        api_base_url: Some("x".repeat(round * 100)),
+                        ..HubConfig::default()
+                    };
+                    config.save().unwrap();
+                }
+            });
+            for _ in 0..25 {
+                let (_, recovery) = HubConfig::load_or_recover().unwrap();
+                assert!(recovery.is_none(), "{:?}", recovery);
+            }
+            writer.join().unwrap();
+        });
+    }
+}
@@ -0,0 +1,60 @@
+//! Optional liveness heartbeat for hub-managed tools.
+//!
+//! A running process isn't necessarily a working one - a tool can hang and
+//! stop reacting to its hotkey. Tools that want the hub to notice opt in to
+//! this protocol:
+//!
+//! 1. While running, touch `<temp dir>/hub-heartbeats/<tool id>.heartbeat`
+//!    (see `heartbeat_path`) at least every `HEARTBEAT_INTERVAL`, from the
+//!    thread that does the real work so a hang stops the heartbeat. Calling
+//!    `beat` does this.
+//! 2. The hub deletes the file whenever it starts the tool, and reports a
+//!    running tool whose file is older than `HEARTBEAT_TIMEOUT` as not
+//!    responding.
+//!
+//! Tools that never write the file are reported as running, as before.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// How often a tool should touch its heartbeat file
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A heartbeat older than this means the tool is not responding
+pub const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Directory heartbeat files live in
+pub fn heartbeat_dir() -> PathBuf {
+    std::env::temp_dir().join("hub-heartbeats")
+}
+
+/// Heartbeat file for a tool (`ToolId` string or third-party tool ID)
+pub fn heartbeat_path(tool_id: &str) -> PathBuf {
+    heartbeat_dir().join(format!("{}.heartbeat", tool_id))
+}
+
+/// Record that the tool is alive (for tool authors)
+pub fn beat(tool_id: &str) -> std::io::Result<()> {
+    fs::create_dir_all(heartbeat_dir())?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    fs::write(heartbeat_path(tool_id), now.as_secs().to_string())
+}
+
+/// Forget a previous run's heartbeat, so a freshly started tool isn't judged
+/// by it
+pub fn clear(tool_id: &str) {
+    let _ = fs::remove_file(heartbeat_path(tool_id));
+}
+
+/// Whether the tool has a heartbeat file that stopped being updated.
+/// `false` for tools that don't write one.
+pub fn is_stale(tool_id: &str) -> bool {
+    fs::metadata(heartbeat_path(tool_id))
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age > HEARTBEAT_TIMEOUT)
+}
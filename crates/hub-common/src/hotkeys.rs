@@ -3,6 +3,7 @@
 use rdev::Key;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use tracing::warn;
 
 use crate::tools::ToolId;
 
@@ -57,6 +58,251 @@ pub enum NamedKey {
     // Media keys
     MediaPlayPause, MediaStop, MediaPrevious, MediaNext,
     VolumeUp, VolumeDown, VolumeMute,
+    
+    // Modifier keys used on their own (e.g. push-to-talk on Right Ctrl)
+    Alt, AltGr, ControlLeft, ControlRight, ShiftLeft, ShiftRight, MetaLeft, MetaRight,
+    
+    // Letters and punctuation
+    KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM,
+    KeyN, KeyO, KeyP, KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ,
+    BackQuote, Minus, Equal, LeftBracket, RightBracket, SemiColon, Quote, BackSlash,
+    Comma, Dot, Slash,
+    
+    // Numpad digits
+    Numpad0, Numpad1, Numpad2, Numpad3, Numpad4,
+    Numpad5, Numpad6, Numpad7, Numpad8, Numpad9, NumpadDelete,
+}
+
+/// Group a key is listed under in the hotkey picker
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyCategory {
+    Function,
+    Navigation,
+    Modifier,
+    Special,
+    Alphanumeric,
+    Numpad,
+    Media,
+}
+
+impl NamedKey {
+    /// Every named key, in the order the hotkey picker lists them
+    pub const ALL: &'static [NamedKey] = &[
+        // Function
+        NamedKey::F1, NamedKey::F2, NamedKey::F3, NamedKey::F4, NamedKey::F5, NamedKey::F6,
+        NamedKey::F7, NamedKey::F8, NamedKey::F9, NamedKey::F10, NamedKey::F11, NamedKey::F12,
+        NamedKey::F13, NamedKey::F14, NamedKey::F15, NamedKey::F16, NamedKey::F17, NamedKey::F18,
+        NamedKey::F19, NamedKey::F20, NamedKey::F21, NamedKey::F22, NamedKey::F23, NamedKey::F24,
+        // Navigation
+        NamedKey::Insert, NamedKey::Delete, NamedKey::Home, NamedKey::End, NamedKey::PageUp,
+        NamedKey::PageDown, NamedKey::UpArrow, NamedKey::DownArrow, NamedKey::LeftArrow,
+        NamedKey::RightArrow,
+        // Modifier
+        NamedKey::Alt, NamedKey::AltGr, NamedKey::ControlLeft, NamedKey::ControlRight,
+        NamedKey::ShiftLeft, NamedKey::ShiftRight, NamedKey::MetaLeft, NamedKey::MetaRight,
+        // Special
+        NamedKey::Escape, NamedKey::Tab, NamedKey::CapsLock, NamedKey::Space, NamedKey::Backspace,
+        NamedKey::Return, NamedKey::PrintScreen, NamedKey::ScrollLock, NamedKey::Pause,
+        NamedKey::NumLock,
+        // Alphanumeric
+        NamedKey::BackQuote, NamedKey::Num1, NamedKey::Num2, NamedKey::Num3, NamedKey::Num4,
+        NamedKey::Num5, NamedKey::Num6, NamedKey::Num7, NamedKey::Num8, NamedKey::Num9,
+        NamedKey::Num0, NamedKey::Minus, NamedKey::Equal, NamedKey::KeyQ, NamedKey::KeyW,
+        NamedKey::KeyE, NamedKey::KeyR, NamedKey::KeyT, NamedKey::KeyY, NamedKey::KeyU,
+        NamedKey::KeyI, NamedKey::KeyO, NamedKey::KeyP, NamedKey::LeftBracket,
+        NamedKey::RightBracket, NamedKey::KeyA, NamedKey::KeyS, NamedKey::KeyD, NamedKey::KeyF,
+        NamedKey::KeyG, NamedKey::KeyH, NamedKey::KeyJ, NamedKey::KeyK, NamedKey::KeyL,
+        NamedKey::SemiColon, NamedKey::Quote, NamedKey::BackSlash, NamedKey::KeyZ, NamedKey::KeyX,
+        NamedKey::KeyC, NamedKey::KeyV, NamedKey::KeyB, NamedKey::KeyN, NamedKey::KeyM,
+        NamedKey::Comma, NamedKey::Dot, NamedKey::Slash,
+        // Numpad
+        NamedKey::Numpad0, NamedKey::Numpad1, NamedKey::Numpad2, NamedKey::Numpad3,
+        NamedKey::Numpad4, NamedKey::Numpad5, NamedKey::Numpad6, NamedKey::Numpad7,
+        NamedKey::Numpad8, NamedKey::Numpad9, NamedKey::NumpadEnter, NamedKey::NumpadAdd,
+        NamedKey::NumpadSubtract, NamedKey::NumpadMultiply, NamedKey::NumpadDivide,
+        NamedKey::NumpadDelete,
+        // Media
+        NamedKey::MediaPlayPause, NamedKey::MediaStop, NamedKey::MediaPrevious,
+        NamedKey::MediaNext, NamedKey::VolumeUp, NamedKey::VolumeDown, NamedKey::VolumeMute,
+    ];
+
+    /// Name used in `ToolConfig::hotkey` and on tool command lines (rdev's
+    /// key name in kebab case, e.g. "f13", "print-screen"). `None` for keys
+    /// tools only accept as a special key code (media keys).
+    pub fn config_name(self) -> Option<&'static str> {
+        self.names().0
+    }
+
+    /// Display name for pickers
+    pub fn label(self) -> &'static str {
+        self.names().1
+    }
+
+    fn names(self) -> (Option<&'static str>, &'static str) {
+        use NamedKey::*;
+        match self {
+            F1 => (Some("f1"), "F1"),
+            F2 => (Some("f2"), "F2"),
+            F3 => (Some("f3"), "F3"),
+            F4 => (Some("f4"), "F4"),
+            F5 => (Some("f5"), "F5"),
+            F6 => (Some("f6"), "F6"),
+            F7 => (Some("f7"), "F7"),
+            F8 => (Some("f8"), "F8"),
+            F9 => (Some("f9"), "F9"),
+            F10 => (Some("f10"), "F10"),
+            F11 => (Some("f11"), "F11"),
+            F12 => (Some("f12"), "F12"),
+            F13 => (Some("f13"), "F13"),
+            F14 => (Some("f14"), "F14"),
+            F15 => (Some("f15"), "F15"),
+            F16 => (Some("f16"), "F16"),
+            F17 => (Some("f17"), "F17"),
+            F18 => (Some("f18"), "F18"),
+            F19 => (Some("f19"), "F19"),
+            F20 => (Some("f20"), "F20"),
+            F21 => (Some("f21"), "F21"),
+            F22 => (Some("f22"), "F22"),
+            F23 => (Some("f23"), "F23"),
+            F24 => (Some("f24"), "F24"),
+            Insert => (Some("insert"), "Insert"),
+            Delete => (Some("delete"), "Delete"),
+            Home => (Some("home"), "Home"),
+            End => (Some("end"), "End"),
+            PageUp => (Some("page-up"), "Page Up"),
+            PageDown => (Some("page-down"), "Page Down"),
+            UpArrow => (Some("up-arrow"), "Up Arrow"),
+            DownArrow => (Some("down-arrow"), "Down Arrow"),
+            LeftArrow => (Some("left-arrow"), "Left Arrow"),
+            RightArrow => (Some("right-arrow"), "Right Arrow"),
+            Alt => (Some("alt"), "Alt"),
+            AltGr => (Some("alt-gr"), "AltGr"),
+            ControlLeft => (Some("control-left"), "Left Ctrl"),
+            ControlRight => (Some("control-right"), "Right Ctrl"),
+            ShiftLeft => (Some("shift-left"), "Left Shift"),
+            ShiftRight => (Some("shift-right"), "Right Shift"),
+            MetaLeft => (Some("meta-left"), "Left Win"),
+            MetaRight => (Some("meta-right"), "Right Win"),
+            Escape => (Some("escape"), "Escape"),
+            Tab => (Some("tab"), "Tab"),
+            CapsLock => (Some("caps-lock"), "Caps Lock"),
+            Space => (Some("space"), "Space"),
+            Backspace => (Some("backspace"), "Backspace"),
+            Return => (Some("return"), "Enter"),
+            PrintScreen => (Some("print-screen"), "Print Screen"),
+            ScrollLock => (Some("scroll-lock"), "Scroll Lock"),
+            Pause => (Some("pause"), "Pause"),
+            NumLock => (Some("num-lock"), "Num Lock"),
+            BackQuote => (Some("back-quote"), "` (Backtick)"),
+            Num1 => (Some("num1"), "1"),
+            Num2 => (Some("num2"), "2"),
+            Num3 => (Some("num3"), "3"),
+            Num4 => (Some("num4"), "4"),
+            Num5 => (Some("num5"), "5"),
+            Num6 => (Some("num6"), "6"),
+            Num7 => (Some("num7"), "7"),
+            Num8 => (Some("num8"), "8"),
+            Num9 => (Some("num9"), "9"),
+            Num0 => (Some("num0"), "0"),
+            Minus => (Some("minus"), "- (Minus)"),
+            Equal => (Some("equal"), "= (Equal)"),
+            KeyQ => (Some("key-q"), "Q"),
+            KeyW => (Some("key-w"), "W"),
+            KeyE => (Some("key-e"), "E"),
+            KeyR => (Some("key-r"), "R"),
+            KeyT => (Some("key-t"), "T"),
+            KeyY => (Some("key-y"), "Y"),
+            KeyU => (Some("key-u"), "U"),
+            KeyI => (Some("key-i"), "I"),
+            KeyO => (Some("key-o"), "O"),
+            KeyP => (Some("key-p"), "P"),
+            LeftBracket => (Some("left-bracket"), "[ (Left Bracket)"),
+            RightBracket => (Some("right-bracket"), "] (Right Bracket)"),
+            KeyA => (Some("key-a"), "A"),
+            KeyS => (Some("key-s"), "S"),
+            KeyD => (Some("key-d"), "D"),
+            KeyF => (Some("key-f"), "F"),
+            KeyG => (Some("key-g"), "G"),
+            KeyH => (Some("key-h"), "H"),
+            KeyJ => (Some("key-j"), "J"),
+            KeyK => (Some("key-k"), "K"),
+            KeyL => (Some("key-l"), "L"),
+            SemiColon => (Some("semi-colon"), "; (Semicolon)"),
+            Quote => (Some("quote"), "' (Quote)"),
+            BackSlash => (Some("back-slash"), "\\ (Backslash)"),
+            KeyZ => (Some("key-z"), "Z"),
+            KeyX => (Some("key-x"), "X"),
+            KeyC => (Some("key-c"), "C"),
+            KeyV => (Some("key-v"), "V"),
+            KeyB => (Some("key-b"), "B"),
+            KeyN => (Some("key-n"), "N"),
+            KeyM => (Some("key-m"), "M"),
+            Comma => (Some("comma"), ", (Comma)"),
+            Dot => (Some("dot"), ". (Period)"),
+            Slash => (Some("slash"), "/ (Slash)"),
+            Numpad0 => (Some("kp0"), "Numpad 0"),
+            Numpad1 => (Some("kp1"), "Numpad 1"),
+            Numpad2 => (Some("kp2"), "Numpad 2"),
+            Numpad3 => (Some("kp3"), "Numpad 3"),
+            Numpad4 => (Some("kp4"), "Numpad 4"),
+            Numpad5 => (Some("kp5"), "Numpad 5"),
+            Numpad6 => (Some("kp6"), "Numpad 6"),
+            Numpad7 => (Some("kp7"), "Numpad 7"),
+            Numpad8 => (Some("kp8"), "Numpad 8"),
+            Numpad9 => (Some("kp9"), "Numpad 9"),
+            NumpadEnter => (Some("kp-return"), "Numpad Enter"),
+            NumpadAdd => (Some("kp-plus"), "Numpad +"),
+            NumpadSubtract => (Some("kp-minus"), "Numpad -"),
+            NumpadMultiply => (Some("kp-multiply"), "Numpad *"),
+            NumpadDivide => (Some("kp-divide"), "Numpad /"),
+            NumpadDelete => (Some("kp-delete"), "Numpad Delete"),
+            MediaPlayPause => (None, "Play/Pause"),
+            MediaStop => (None, "Stop"),
+            MediaPrevious => (None, "Previous Track"),
+            MediaNext => (None, "Next Track"),
+            VolumeUp => (None, "Volume Up"),
+            VolumeDown => (None, "Volume Down"),
+            VolumeMute => (None, "Mute"),
+        }
+    }
+
+    /// Picker group the key is listed under
+    pub fn category(self) -> KeyCategory {
+        use NamedKey::*;
+        match self {
+            F1 | F2 | F3 | F4 | F5 | F6 | F7 | F8 | F9 | F10 | F11 | F12 | F13 | F14 | F15 | F16 |
+            F17 | F18 | F19 | F20 | F21 | F22 | F23 | F24 => KeyCategory::Function,
+            Insert | Delete | Home | End | PageUp | PageDown | UpArrow | DownArrow | LeftArrow |
+            RightArrow => KeyCategory::Navigation,
+            Alt | AltGr | ControlLeft | ControlRight | ShiftLeft | ShiftRight | MetaLeft |
+            MetaRight => KeyCategory::Modifier,
+            Escape | Tab | CapsLock | Space | Backspace | Return | PrintScreen | ScrollLock |
+            Pause | NumLock => KeyCategory::Special,
+            BackQuote | Num1 | Num2 | Num3 | Num4 | Num5 | Num6 | Num7 | Num8 | Num9 | Num0 |
+            Minus | Equal | KeyQ | KeyW | KeyE | KeyR | KeyT | KeyY | KeyU | KeyI | KeyO | KeyP |
+            LeftBracket | RightBracket | KeyA | KeyS | KeyD | KeyF | KeyG | KeyH | KeyJ | KeyK |
+            KeyL | SemiColon | Quote | BackSlash | KeyZ | KeyX | KeyC | KeyV | KeyB | KeyN | KeyM |
+            Comma | Dot | Slash => KeyCategory::Alphanumeric,
+            Numpad0 | Numpad1 | Numpad2 | Numpad3 | Numpad4 | Numpad5 | Numpad6 | Numpad7 |
+            Numpad8 | Numpad9 | NumpadEnter | NumpadAdd | NumpadSubtract | NumpadMultiply |
+            NumpadDivide | NumpadDelete => KeyCategory::Numpad,
+            MediaPlayPause | MediaStop | MediaPrevious | MediaNext | VolumeUp | VolumeDown |
+            VolumeMute => KeyCategory::Media,
+        }
+    }
+
+    /// Parse a key as named in `ToolConfig::hotkey`. Keys that have no
+    /// `NamedKey` give `None`.
+    pub fn from_config_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|key| key.config_name() == Some(name))
+    }
+
+    /// F1-F12 (the keys laptops often double as media keys)
+    fn is_standard_function_key(self) -> bool {
+        use NamedKey::*;
+        matches!(self, F1 | F2 | F3 | F4 | F5 | F6 | F7 | F8 | F9 | F10 | F11 | F12)
+    }
 }
 
 /// Modifier keys
@@ -68,6 +314,18 @@ pub enum HotkeyModifier {
     Meta, // Windows key
 }
 
+impl HotkeyModifier {
+    /// Name used on tool command lines (`--modifiers ctrl,shift`)
+    pub fn as_arg(&self) -> &'static str {
+        match self {
+            HotkeyModifier::Ctrl => "ctrl",
+            HotkeyModifier::Alt => "alt",
+            HotkeyModifier::Shift => "shift",
+            HotkeyModifier::Meta => "meta",
+        }
+    }
+}
+
 impl From<HotkeyKey> for Key {
     fn from(key: HotkeyKey) -> Self {
         match key {
@@ -146,6 +404,62 @@ impl From<NamedKey> for Key {
             NamedKey::VolumeUp => Key::Unknown(175),
             NamedKey::VolumeDown => Key::Unknown(174),
             NamedKey::VolumeMute => Key::Unknown(173),
+            NamedKey::Alt => Key::Alt,
+            NamedKey::AltGr => Key::AltGr,
+            NamedKey::ControlLeft => Key::ControlLeft,
+            NamedKey::ControlRight => Key::ControlRight,
+            NamedKey::ShiftLeft => Key::ShiftLeft,
+            NamedKey::ShiftRight => Key::ShiftRight,
+            NamedKey::MetaLeft => Key::MetaLeft,
+            NamedKey::MetaRight => Key::MetaRight,
+            NamedKey::BackQuote => Key::BackQuote,
+            NamedKey::Minus => Key::Minus,
+            NamedKey::Equal => Key::Equal,
+            NamedKey::KeyQ => Key::KeyQ,
+            NamedKey::KeyW => Key::KeyW,
+            NamedKey::KeyE => Key::KeyE,
+            NamedKey::KeyR => Key::KeyR,
+            NamedKey::KeyT => Key::KeyT,
+            NamedKey::KeyY => Key::KeyY,
+            NamedKey::KeyU => Key::KeyU,
+            NamedKey::KeyI => Key::KeyI,
+            NamedKey::KeyO => Key::KeyO,
+            NamedKey::KeyP => Key::KeyP,
+            NamedKey::LeftBracket => Key::LeftBracket,
+            NamedKey::RightBracket => Key::RightBracket,
+            NamedKey::KeyA => Key::KeyA,
+            NamedKey::KeyS => Key::KeyS,
+            NamedKey::KeyD => Key::KeyD,
+            NamedKey::KeyF => Key::KeyF,
+            NamedKey::KeyG => Key::KeyG,
+            NamedKey::KeyH => Key::KeyH,
+            NamedKey::KeyJ => Key::KeyJ,
+            NamedKey::KeyK => Key::KeyK,
+            NamedKey::KeyL => Key::KeyL,
+            NamedKey::SemiColon => Key::SemiColon,
+            NamedKey::Quote => Key::Quote,
+            NamedKey::BackSlash => Key::BackSlash,
+            NamedKey::KeyZ => Key::KeyZ,
+            NamedKey::KeyX => Key::KeyX,
+            NamedKey::KeyC => Key::KeyC,
+            NamedKey::KeyV => Key::KeyV,
+            NamedKey::KeyB => Key::KeyB,
+            NamedKey::KeyN => Key::KeyN,
+            NamedKey::KeyM => Key::KeyM,
+            NamedKey::Comma => Key::Comma,
+            NamedKey::Dot => Key::Dot,
+            NamedKey::Slash => Key::Slash,
+            NamedKey::Numpad0 => Key::Kp0,
+            NamedKey::Numpad1 => Key::Kp1,
+            NamedKey::Numpad2 => Key::Kp2,
+            NamedKey::Numpad3 => Key::Kp3,
+            NamedKey::Numpad4 => Key::Kp4,
+            NamedKey::Numpad5 => Key::Kp5,
+            NamedKey::Numpad6 => Key::Kp6,
+            NamedKey::Numpad7 => Key::Kp7,
+            NamedKey::Numpad8 => Key::Kp8,
+            NamedKey::Numpad9 => Key::Kp9,
+            NamedKey::NumpadDelete => Key::KpDelete,
         }
     }
 }
@@ -197,6 +511,62 @@ impl TryFrom<Key> for HotkeyKey {
             Key::Num8 => Ok(HotkeyKey::Named(NamedKey::Num8)),
             Key::Num9 => Ok(HotkeyKey::Named(NamedKey::Num9)),
             Key::NumLock => Ok(HotkeyKey::Named(NamedKey::NumLock)),
+            Key::Alt => Ok(HotkeyKey::Named(NamedKey::Alt)),
+            Key::AltGr => Ok(HotkeyKey::Named(NamedKey::AltGr)),
+            Key::ControlLeft => Ok(HotkeyKey::Named(NamedKey::ControlLeft)),
+            Key::ControlRight => Ok(HotkeyKey::Named(NamedKey::ControlRight)),
+            Key::ShiftLeft => Ok(HotkeyKey::Named(NamedKey::ShiftLeft)),
+            Key::ShiftRight => Ok(HotkeyKey::Named(NamedKey::ShiftRight)),
+            Key::MetaLeft => Ok(HotkeyKey::Named(NamedKey::MetaLeft)),
+            Key::MetaRight => Ok(HotkeyKey::Named(NamedKey::MetaRight)),
+            Key::BackQuote => Ok(HotkeyKey::Named(NamedKey::BackQuote)),
+            Key::Minus => Ok(HotkeyKey::Named(NamedKey::Minus)),
+            Key::Equal => Ok(HotkeyKey::Named(NamedKey::Equal)),
+            Key::KeyQ => Ok(HotkeyKey::Named(NamedKey::KeyQ)),
+            Key::KeyW => Ok(HotkeyKey::Named(NamedKey::KeyW)),
+            Key::KeyE => Ok(HotkeyKey::Named(NamedKey::KeyE)),
+            Key::KeyR => Ok(HotkeyKey::Named(NamedKey::KeyR)),
+            Key::KeyT => Ok(HotkeyKey::Named(NamedKey::KeyT)),
+            Key::KeyY => Ok(HotkeyKey::Named(NamedKey::KeyY)),
+            Key::KeyU => Ok(HotkeyKey::Named(NamedKey::KeyU)),
+            Key::KeyI => Ok(HotkeyKey::Named(NamedKey::KeyI)),
+            Key::KeyO => Ok(HotkeyKey::Named(NamedKey::KeyO)),
+            Key::KeyP => Ok(HotkeyKey::Named(NamedKey::KeyP)),
+            Key::LeftBracket => Ok(HotkeyKey::Named(NamedKey::LeftBracket)),
+            Key::RightBracket => Ok(HotkeyKey::Named(NamedKey::RightBracket)),
+            Key::KeyA => Ok(HotkeyKey::Named(NamedKey::KeyA)),
+            Key::KeyS => Ok(HotkeyKey::Named(NamedKey::KeyS)),
+            Key::KeyD => Ok(HotkeyKey::Named(NamedKey::KeyD)),
+            Key::KeyF => Ok(HotkeyKey::Named(NamedKey::KeyF)),
+            Key::KeyG => Ok(HotkeyKey::Named(NamedKey::KeyG)),
+            Key::KeyH => Ok(HotkeyKey::Named(NamedKey::KeyH)),
+            Key::KeyJ => Ok(HotkeyKey::Named(NamedKey::KeyJ)),
+            Key::KeyK => Ok(HotkeyKey::Named(NamedKey::KeyK)),
+            Key::KeyL => Ok(HotkeyKey::Named(NamedKey::KeyL)),
+            Key::SemiColon => Ok(HotkeyKey::Named(NamedKey::SemiColon)),
+            Key::Quote => Ok(HotkeyKey::Named(NamedKey::Quote)),
+            Key::BackSlash => Ok(HotkeyKey::Named(NamedKey::BackSlash)),
+            Key::KeyZ => Ok(HotkeyKey::Named(NamedKey::KeyZ)),
+            Key::KeyX => Ok(HotkeyKey::Named(NamedKey::KeyX)),
+            Key::KeyC => Ok(HotkeyKey::Named(NamedKey::KeyC)),
+            Key::KeyV => Ok(HotkeyKey::Named(NamedKey::KeyV)),
+            Key::KeyB => Ok(HotkeyKey::Named(NamedKey::KeyB)),
+            Key::KeyN => Ok(HotkeyKey::Named(NamedKey::KeyN)),
+            Key::KeyM => Ok(HotkeyKey::Named(NamedKey::KeyM)),
+            Key::Comma => Ok(HotkeyKey::Named(NamedKey::Comma)),
+            Key::Dot => Ok(HotkeyKey::Named(NamedKey::Dot)),
+            Key::Slash => Ok(HotkeyKey::Named(NamedKey::Slash)),
+            Key::Kp0 => Ok(HotkeyKey::Named(NamedKey::Numpad0)),
+            Key::Kp1 => Ok(HotkeyKey::Named(NamedKey::Numpad1)),
+            Key::Kp2 => Ok(HotkeyKey::Named(NamedKey::Numpad2)),
+            Key::Kp3 => Ok(HotkeyKey::Named(NamedKey::Numpad3)),
+            Key::Kp4 => Ok(HotkeyKey::Named(NamedKey::Numpad4)),
+            Key::Kp5 => Ok(HotkeyKey::Named(NamedKey::Numpad5)),
+            Key::Kp6 => Ok(HotkeyKey::Named(NamedKey::Numpad6)),
+            Key::Kp7 => Ok(HotkeyKey::Named(NamedKey::Numpad7)),
+            Key::Kp8 => Ok(HotkeyKey::Named(NamedKey::Numpad8)),
+            Key::Kp9 => Ok(HotkeyKey::Named(NamedKey::Numpad9)),
+            Key::KpDelete => Ok(HotkeyKey::Named(NamedKey::NumpadDelete)),
             Key::Unknown(code) => Ok(HotkeyKey::Unknown(code)),
             _ => Err(()),
         }
@@ -220,7 +590,8 @@ impl HotkeyRegistry {
         Self { hotkeys }
     }
 
-    /// Register a hotkey for a tool
+    /// Register a hotkey for a tool. A combination the OS usually keeps for
+    /// itself (see `is_system_reserved`) is logged but still registered.
     pub fn register(
         &mut self,
         tool_id: ToolId,
@@ -234,6 +605,9 @@ impl HotkeyRegistry {
                 existing: conflict.clone(),
             });
         }
+        if let Some(reason) = Self::is_system_reserved(&key, &modifiers) {
+            warn!("Hotkey for {} may not reach it: {}", tool_id.display_name(), reason);
+        }
 
         self.hotkeys.push(RegisteredHotkey {
             tool_id,
@@ -260,6 +634,66 @@ impl HotkeyRegistry {
         self.hotkeys.iter().find(|h| &h.key == key && h.modifiers == modifiers)
     }
 
+    /// Why a key combination probably never reaches a tool, if the OS or
+    /// desktop commonly handles it first. Advisory only: the user may have
+    /// turned the system shortcut off, so callers warn but accept the key.
+    pub fn is_system_reserved(
+        key: &HotkeyKey,
+        modifiers: &[HotkeyModifier],
+    ) -> Option<&'static str> {
+        use HotkeyModifier as Mod;
+        use NamedKey::*;
+
+        let HotkeyKey::Named(key) = *key else {
+            return None;
+        };
+        let held = |modifier| modifiers.contains(&modifier);
+
+        match key {
+            Tab if held(Mod::Alt) => return Some("Alt+Tab switches windows"),
+            F4 if held(Mod::Alt) => return Some("Alt+F4 closes the active window"),
+            Delete if held(Mod::Ctrl) && held(Mod::Alt) => {
+                return Some("Ctrl+Alt+Delete is handled by the system")
+            }
+            VolumeUp | VolumeDown | VolumeMute => {
+                return Some("Volume keys also change the system volume")
+            }
+            MediaPlayPause | MediaStop | MediaPrevious | MediaNext => {
+                return Some("Media keys also control the active media player")
+            }
+            _ => {}
+        }
+
+        if cfg!(windows) {
+            match key {
+                PrintScreen => Some("Windows opens the Snipping Tool on Print Screen"),
+                Escape if held(Mod::Ctrl) => {
+                    Some("Ctrl+Esc and Ctrl+Shift+Esc are handled by Windows")
+                }
+                _ if held(Mod::Meta) => Some("Most Windows key shortcuts are reserved by Windows"),
+                _ => None,
+            }
+        } else if cfg!(target_os = "macos") {
+            match key {
+                Space if held(Mod::Meta) => Some("Cmd+Space opens Spotlight"),
+                Tab if held(Mod::Meta) => Some("Cmd+Tab switches apps"),
+                _ if key.is_standard_function_key() && modifiers.is_empty() => Some(
+                    "F1-F12 act as brightness and media keys unless macOS is set to use \
+                     them as standard function keys",
+                ),
+                _ => None,
+            }
+        } else {
+            match key {
+                PrintScreen => Some("Most Linux desktops take a screenshot on Print Screen"),
+                _ if key.is_standard_function_key() && held(Mod::Ctrl) && held(Mod::Alt) => {
+                    Some("Ctrl+Alt+F1-F12 switch virtual terminals")
+                }
+                _ => None,
+            }
+        }
+    }
+
     /// Get all registered hotkeys
     pub fn all(&self) -> &[RegisteredHotkey] {
         &self.hotkeys
@@ -5,14 +5,23 @@
 //! - Shared OpenAI API key storage
 //! - Hotkey registry to avoid conflicts
 //! - Tool registry for managing enabled tools
+//! - Optional heartbeat protocol for detecting hung tools
 
+pub mod binary;
 pub mod config;
+pub mod heartbeat;
 pub mod hotkeys;
 pub mod tools;
 
-pub use config::{HubConfig, ToolConfig};
-pub use hotkeys::{HotkeyRegistry, RegisteredHotkey};
-pub use tools::{ToolId, ToolRegistry, ToolStatus};
+pub use binary::{locate_binary, SearchRoots};
+pub use config::{
+    app_dirs, ApiKeyError, ApiProvider, ConfigExport, ConfigRecovery, ConfigValidationError, HubConfig,
+    ThemeMode, ToolConfig, ToolConfigError,
+};
+pub use hotkeys::{HotkeyModifier, HotkeyRegistry, KeyCategory, NamedKey, RegisteredHotkey};
+pub use tools::{
+    ToolCategory, ToolDescriptor, ToolId, ToolRegistry, ToolStartError, ToolStatus, UnknownToolId,
+};
 
 /// Re-export rdev::Key for convenience
 pub use rdev::Key;
@@ -1,7 +1,8 @@
 //! Tool registry for managing the suite of productivity tools
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tracing::warn;
 
 /// Unique identifier for each tool in the suite
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -16,6 +17,23 @@ pub enum ToolId {
 }
 
 impl ToolId {
+    /// Get the stable kebab-case identifier (matches the serde representation)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ToolId::DeskTalk => "desk-talk",
+            ToolId::SpeakSelected => "speak-selected",
+            ToolId::QuickAssistant => "quick-assistant",
+            ToolId::FlattenString => "flatten-string",
+            ToolId::TypoFix => "typo-fix",
+            ToolId::OcrPaste => "ocr-paste",
+        }
+    }
+
+    /// Get the folder name of the tool's submodule under `tools/`
+    pub fn folder_name(&self) -> &'static str {
+        self.as_str()
+    }
+
     /// Get the display name for the tool
     pub fn display_name(&self) -> &'static str {
         match self {
@@ -52,6 +70,92 @@ impl ToolId {
         }
     }
 
+    /// Get the category used to group the tool in the dashboard
+    pub fn category(&self) -> ToolCategory {
+        match self {
+            ToolId::DeskTalk => ToolCategory::Voice,
+            ToolId::SpeakSelected => ToolCategory::Voice,
+            ToolId::QuickAssistant => ToolCategory::Voice,
+            ToolId::FlattenString => ToolCategory::Text,
+            ToolId::TypoFix => ToolCategory::Text,
+            ToolId::OcrPaste => ToolCategory::Vision,
+        }
+    }
+
+    /// Get the name of the icon the dashboard renders for the tool
+    pub fn icon_name(&self) -> &'static str {
+        match self {
+            ToolId::DeskTalk => "microphone",
+            ToolId::SpeakSelected => "speaker",
+            ToolId::QuickAssistant => "assistant",
+            ToolId::FlattenString => "flatten",
+            ToolId::TypoFix => "spellcheck",
+            ToolId::OcrPaste => "scan",
+        }
+    }
+
+    /// Get the CLI flag used to pass the configured hotkey (None for GUI tools,
+    /// which read their own config)
+    pub fn hotkey_arg(&self) -> Option<&'static str> {
+        match self {
+            ToolId::SpeakSelected => Some("--ptt-key"),
+            ToolId::QuickAssistant => Some("--ptt-key"),
+            ToolId::FlattenString => Some("--trigger-key"),
+            ToolId::OcrPaste => Some("--trigger-key"),
+            ToolId::DeskTalk | ToolId::TypoFix => None,
+        }
+    }
+
+    /// Get the CLI flag used to pass modifiers that must be held with the
+    /// hotkey (None for tools that only take a bare key)
+    pub fn modifiers_arg(&self) -> Option<&'static str> {
+        match self {
+            ToolId::SpeakSelected
+            | ToolId::QuickAssistant
+            | ToolId::FlattenString
+            | ToolId::OcrPaste => Some("--modifiers"),
+            ToolId::DeskTalk | ToolId::TypoFix => None,
+        }
+    }
+
+    /// Get the CLI flag used to pass the configured TTS voice (None for tools
+    /// that don't speak)
+    pub fn voice_arg(&self) -> Option<&'static str> {
+        match self {
+            ToolId::SpeakSelected => Some("--voice"),
+            ToolId::QuickAssistant => Some("--ai-voice"),
+            _ => None,
+        }
+    }
+
+    /// Get the CLI flag used to pass the configured speech speed (None for
+    /// tools that don't speak)
+    pub fn speech_speed_arg(&self) -> Option<&'static str> {
+        match self {
+            ToolId::SpeakSelected | ToolId::QuickAssistant => Some("--speech-speed"),
+            _ => None,
+        }
+    }
+
+    /// Get the descriptor for this built-in tool
+    pub fn descriptor(&self) -> ToolDescriptor {
+        ToolDescriptor {
+            id: self.as_str().to_string(),
+            display_name: self.display_name().to_string(),
+            description: self.description().to_string(),
+            binary_name: self.binary_name().to_string(),
+            requires_api_key: self.requires_api_key(),
+            hotkey_arg: self.hotkey_arg().map(str::to_string),
+            category: self.category(),
+        }
+    }
+
+    /// Check if this is a GUI tool that manages its own window and config file
+    /// (rather than taking its settings as CLI args)
+    pub fn is_gui(&self) -> bool {
+        matches!(self, ToolId::DeskTalk | ToolId::TypoFix)
+    }
+
     /// Check if this tool requires OpenAI API key
     pub fn requires_api_key(&self) -> bool {
         match self {
@@ -77,6 +181,171 @@ impl ToolId {
     }
 }
 
+impl ToolId {
+    /// Get all tool IDs grouped by category, in `ToolCategory::all()` order
+    pub fn all_by_category() -> Vec<(ToolCategory, Vec<ToolId>)> {
+        ToolCategory::all()
+            .iter()
+            .map(|category| {
+                let tools = ToolId::all()
+                    .iter()
+                    .filter(|id| id.category() == *category)
+                    .cloned()
+                    .collect();
+                (*category, tools)
+            })
+            .collect()
+    }
+}
+
+/// Category a tool belongs to (used for grouping in the dashboard)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ToolCategory {
+    /// Speech input/output tools
+    Voice,
+    /// Text transformation tools
+    Text,
+    /// Image/screen based tools
+    Vision,
+    /// Third-party tools that don't declare a category
+    #[default]
+    Other,
+}
+
+impl ToolCategory {
+    /// Get the display name for the category
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ToolCategory::Voice => "Voice",
+            ToolCategory::Text => "Text",
+            ToolCategory::Vision => "Vision",
+            ToolCategory::Other => "Other",
+        }
+    }
+
+    /// Get all categories in display order
+    pub fn all() -> &'static [ToolCategory] {
+        &[
+            ToolCategory::Voice,
+            ToolCategory::Text,
+            ToolCategory::Vision,
+            ToolCategory::Other,
+        ]
+    }
+}
+
+impl std::fmt::Display for ToolId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ToolId {
+    type Err = UnknownToolId;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ToolId::all()
+            .iter()
+            .find(|id| id.as_str() == s)
+            .cloned()
+            .ok_or_else(|| UnknownToolId(s.to_string()))
+    }
+}
+
+/// Describes a tool the hub can manage.
+///
+/// Built-in tools get theirs from `ToolId::descriptor`. Third-party tools are
+/// registered by dropping a JSON file (one descriptor per file) into the
+/// `tools.d` config directory and placing the binary where the hub searches
+/// for tools.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolDescriptor {
+    /// Unique kebab-case identifier
+    pub id: String,
+    /// Human-readable name
+    pub display_name: String,
+    /// Short description of the tool
+    #[serde(default)]
+    pub description: String,
+    /// Binary name without extension (".exe" is added on Windows)
+    pub binary_name: String,
+    /// Whether the tool needs the OpenAI API key (passed as `OPENAI_API_KEY`)
+    #[serde(default)]
+    pub requires_api_key: bool,
+    /// CLI flag used to pass the configured hotkey (e.g. "--trigger-key")
+    #[serde(default)]
+    pub hotkey_arg: Option<String>,
+    /// Category to group the tool under
+    #[serde(default)]
+    pub category: ToolCategory,
+}
+
+impl ToolDescriptor {
+    /// Get the descriptors of all built-in tools
+    pub fn builtins() -> Vec<ToolDescriptor> {
+        ToolId::all().iter().map(ToolId::descriptor).collect()
+    }
+
+    /// Load third-party descriptors from the `*.json` files in `dir`.
+    ///
+    /// Invalid files and IDs that clash with a built-in tool (or an earlier
+    /// plugin) are skipped with a warning.
+    pub fn load_plugins(dir: &Path) -> Vec<ToolDescriptor> {
+        let mut descriptors: Vec<ToolDescriptor> = Vec::new();
+
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return descriptors;
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let descriptor = match std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|contents| {
+                    serde_json::from_str::<ToolDescriptor>(&contents).map_err(|e| e.to_string())
+                }) {
+                Ok(descriptor) => descriptor,
+                Err(e) => {
+                    warn!("Skipping tool descriptor {:?}: {}", path, e);
+                    continue;
+                }
+            };
+
+            let clashes = descriptor.id.parse::<ToolId>().is_ok()
+                || descriptors.iter().any(|d| d.id == descriptor.id);
+            if descriptor.id.is_empty() || descriptor.binary_name.is_empty() || clashes {
+                warn!(
+                    "Skipping tool descriptor {:?}: missing or duplicate id/binary name",
+                    path
+                );
+                continue;
+            }
+
+            descriptors.push(descriptor);
+        }
+
+        descriptors
+    }
+}
+
+/// Error when parsing a string that isn't a known tool ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownToolId(pub String);
+
+impl std::fmt::Display for UnknownToolId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown tool '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownToolId {}
+
 /// Status of a running tool
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ToolStatus {
@@ -90,12 +359,74 @@ pub enum ToolStatus {
     Error(String),
 }
 
+/// `ToolStatus::Error` message for a running tool whose heartbeat went stale
+/// (see `heartbeat`)
+pub const NOT_RESPONDING: &str = "Not responding";
+
 impl Default for ToolStatus {
     fn default() -> Self {
         ToolStatus::Stopped
     }
 }
 
+/// Why a tool failed to start
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind")]
+pub enum ToolStartError {
+    /// The tool's binary couldn't be located; `searched` lists every path
+    /// checked, in search order
+    BinaryNotFound { tool: String, searched: Vec<PathBuf> },
+    /// The process started but exited during its startup window
+    ImmediateExit { code: Option<i32>, stderr: String },
+    /// The OS refused to spawn the process
+    SpawnFailed { message: String },
+    /// The tool needs an OpenAI API key and none is configured
+    MissingApiKey { tool: String },
+    /// The tool is disabled in its config
+    Disabled { tool: String },
+    /// There is no valid license or active trial; `message` says what the
+    /// user should do about it
+    NotAuthorized { tool: String, message: String },
+}
+
+impl std::fmt::Display for ToolStartError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ToolStartError::BinaryNotFound { tool, searched } => write!(
+                f,
+                "Could not find binary for {} (searched {} locations)",
+                tool,
+                searched.len()
+            ),
+            ToolStartError::ImmediateExit { code, stderr } => {
+                if stderr.is_empty() {
+                    write!(f, "Process exited with code {:?}", code)
+                } else {
+                    write!(f, "{}", stderr)
+                }
+            }
+            ToolStartError::SpawnFailed { message } => write!(f, "{}", message),
+            ToolStartError::MissingApiKey { tool } => write!(
+                f,
+                "{} requires an OpenAI API key. Please add one in Settings.",
+                tool
+            ),
+            ToolStartError::Disabled { tool } => {
+                write!(f, "{} is disabled. Enable it in Settings to start it.", tool)
+            }
+            ToolStartError::NotAuthorized { message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ToolStartError {}
+
+impl From<ToolStartError> for ToolStatus {
+    fn from(error: ToolStartError) -> Self {
+        ToolStatus::Error(error.to_string())
+    }
+}
+
 /// Information about a tool's runtime state
 #[derive(Debug, Clone)]
 pub struct ToolInfo {
@@ -179,29 +510,33 @@ impl ToolRegistry {
             }
         }
 
-        // Try to find the binary relative to the current executable
-        if let Ok(exe_path) = std::env::current_exe() {
-            if let Some(exe_dir) = exe_path.parent() {
-                let binary_name = if cfg!(windows) {
-                    format!("{}.exe", id.binary_name())
-                } else {
-                    id.binary_name().to_string()
-                };
+        crate::locate_binary(id.binary_name(), Some(id.folder_name()))
+    }
+}
 
-                // Check in same directory
-                let path = exe_dir.join(&binary_name);
-                if path.exists() {
-                    return Some(path);
-                }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                // Check in tools subdirectory
-                let path = exe_dir.join("tools").join(&binary_name);
-                if path.exists() {
-                    return Some(path);
-                }
-            }
+    #[test]
+    fn tool_ids_round_trip_through_strings() {
+        for tool_id in ToolId::all() {
+            assert_eq!(tool_id.as_str().parse::<ToolId>().as_ref(), Ok(tool_id));
+            assert_eq!(tool_id.to_string(), tool_id.as_str());
         }
+    }
+
+    #[test]
+    fn tool_id_strings_match_serde() {
+        for tool_id in ToolId::all() {
+            let json = serde_json::to_string(tool_id).unwrap();
+            assert_eq!(json, format!("\"{}\"", tool_id.as_str()));
+        }
+    }
 
-        None
+    #[test]
+    fn unknown_tool_id_is_rejected() {
+        assert_eq!("not-a-tool".parse::<ToolId>(), Err(UnknownToolId("not-a-tool".to_string())));
+        assert!("DeskTalk".parse::<ToolId>().is_err());
     }
 }
@@ -0,0 +1,86 @@
+//! Debounced config writer - coalesces bursts of saves into one disk write
+
+use anyhow::Result;
+use hub_common::HubConfig;
+use parking_lot::{Condvar, Mutex};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::error;
+
+/// How long to wait for further changes before writing
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+struct Shared {
+    /// Latest config waiting to be written
+    pending: Mutex<Option<HubConfig>>,
+    /// Signalled when a save is requested
+    wake: Condvar,
+    /// Held while taking and writing a snapshot, so an older snapshot can
+    /// never land on disk after a newer one
+    write: Mutex<()>,
+}
+
+impl Shared {
+    fn write_pending(&self) -> Result<()> {
+        let _write = self.write.lock();
+        let pending = self.pending.lock().take();
+        match pending {
+            Some(config) => config.save(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writes the hub config on a background thread, at most once per burst of
+/// changes
+pub struct ConfigWriter {
+    shared: Arc<Shared>,
+}
+
+impl ConfigWriter {
+    pub fn new() -> Self {
+        let shared = Arc::new(Shared::default());
+        let worker = Arc::clone(&shared);
+        std::thread::spawn(move || loop {
+            {
+                let mut pending = worker.pending.lock();
+                while pending.is_none() {
+                    worker.wake.wait(&mut pending);
+                }
+            }
+
+            // Let the burst settle, then write whatever is newest
+            std::thread::sleep(SAVE_DEBOUNCE);
+            if let Err(e) = worker.write_pending() {
+                error!("Failed to save config: {}", e);
+            }
+        });
+
+        Self { shared }
+    }
+
+    /// Queue the config to be written once changes settle
+    pub fn save(&self, config: &HubConfig) {
+        *self.shared.pending.lock() = Some(config.clone());
+        self.shared.wake.notify_one();
+    }
+
+    /// Write any queued config right away (e.g. on exit)
+    pub fn flush(&self) -> Result<()> {
+        self.shared.write_pending()
+    }
+
+    /// Write the config right away, replacing anything queued
+    pub fn save_now(&self, config: &HubConfig) -> Result<()> {
+        let _write = self.shared.write.lock();
+        self.shared.pending.lock().take();
+        config.save()
+    }
+}
+
+impl Default for ConfigWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
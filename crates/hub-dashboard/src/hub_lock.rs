@@ -0,0 +1,124 @@
+//! Lockfile naming the hub that manages tools
+//!
+//! The single-instance plugin only stops a second window of the same install.
+//! Hubs started from different install locations would each run their own
+//! `ProcessManager`, auto-starting tools twice and fighting over them. The
+//! first hub writes its PID and path to `hub.lock` in the config dir; later
+//! hubs leave tools alone while that hub is alive.
+
+use anyhow::{Context, Result};
+use hub_common::HubConfig;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::process_manager::{is_process_running, process_executable};
+
+const LOCK_FILE_NAME: &str = "hub.lock";
+
+/// The hub holding the lock
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockOwner {
+    pub pid: u32,
+    /// Executable of the owning hub
+    pub path: PathBuf,
+}
+
+impl LockOwner {
+    fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            path: std::env::current_exe().unwrap_or_default(),
+        }
+    }
+
+    /// Whether this hub is still running. A reused PID running some other
+    /// program doesn't count.
+    fn is_alive(&self) -> bool {
+        is_process_running(self.pid)
+            && process_executable(self.pid).is_some_and(|exe| same_file(&exe, &self.path))
+    }
+}
+
+/// Result of trying to take the lock
+pub enum LockOutcome {
+    Acquired(HubLock),
+    /// Another running hub manages the tools
+    HeldBy(LockOwner),
+}
+
+/// The held lock. The lockfile is removed when this is dropped.
+pub struct HubLock {
+    path: PathBuf,
+}
+
+impl HubLock {
+    /// Take the lock, replacing a lockfile left behind by a hub that is no
+    /// longer running
+    pub fn acquire() -> Result<LockOutcome> {
+        let path = HubConfig::config_dir()?.join(LOCK_FILE_NAME);
+        let owner = LockOwner::current();
+        let contents = serde_json::to_string(&owner)?;
+
+        // Write the contents beside the lockfile, then link them into place.
+        // Unlike `rename`, the link fails if a lockfile exists, and the
+        // lockfile is never seen empty or half-written.
+        let temp_path = path.with_extension(format!("lock.{}", owner.pid));
+        fs::write(&temp_path, contents)
+            .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+        let outcome = Self::link_into_place(&temp_path, path, &owner);
+        let _ = fs::remove_file(&temp_path);
+        outcome
+    }
+
+    /// Link the lockfile contents written to `temp_path` into place at
+    /// `path`, replacing a stale lockfile
+    fn link_into_place(temp_path: &Path, path: PathBuf, owner: &LockOwner) -> Result<LockOutcome> {
+        // The second attempt follows removing a stale lockfile
+        for _ in 0..2 {
+            match fs::hard_link(temp_path, &path) {
+                Ok(()) => return Ok(LockOutcome::Acquired(HubLock { path })),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => match read_owner(&path) {
+                    Some(holder) if holder.pid != owner.pid && holder.is_alive() => {
+                        return Ok(LockOutcome::HeldBy(holder));
+                    }
+                    stale => {
+                        info!("Replacing stale hub lock (owner: {:?})", stale);
+                        fs::remove_file(&path)
+                            .with_context(|| format!("Failed to remove {}", path.display()))?;
+                    }
+                },
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to create {}", path.display()))
+                }
+            }
+        }
+        anyhow::bail!("Another hub keeps recreating {}", path.display())
+    }
+}
+
+impl Drop for HubLock {
+    fn drop(&mut self) {
+        // Only remove the file if it's still ours
+        if read_owner(&self.path).is_some_and(|owner| owner.pid == std::process::id()) {
+            if let Err(e) = fs::remove_file(&self.path) {
+                warn!("Failed to remove hub lock {}: {}", self.path.display(), e);
+            }
+        }
+    }
+}
+
+/// Whether two paths name the same file, e.g. through a symlink
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn read_owner(path: &Path) -> Option<LockOwner> {
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
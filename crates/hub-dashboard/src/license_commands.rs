@@ -1,7 +1,14 @@
 //! Tauri commands for license management
 
-use hub_licensing::{lemonsqueezy, trial, AuthStatus, TrialInfo};
+use hub_licensing::{
+    audit, lemonsqueezy, offline, trial, AuditEntry, AuthDecision, AuthStatus, DeactivateAllResult,
+    LicenseConfig, LicenseError, LicenseInfo, LicensePlan, NetworkErrorRecord, TrialInfo,
+    TrialMilestone,
+};
 use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+use tracing::error;
 
 /// Get current authorization status
 #[tauri::command]
@@ -15,111 +22,286 @@ pub fn is_authorized() -> bool {
     hub_licensing::is_authorized()
 }
 
+/// Check authorization, including what to prompt for when it's refused
+#[tauri::command]
+pub fn check_authorization() -> AuthDecision {
+    hub_licensing::authorization_check()
+}
+
+/// Details for the license panel (the key itself is masked), or `None` when
+/// no license is stored
+#[tauri::command]
+pub fn get_license_details() -> Result<Option<LicenseDetails>, String> {
+    let config = LicenseConfig::load().map_err(|e| e.to_string())?;
+    let Some(ref key) = config.license_key else {
+        return Ok(None);
+    };
+
+    let relative = |timestamp: &Option<String>| {
+        timestamp.as_deref().and_then(hub_licensing::relative_time)
+    };
+    Ok(Some(LicenseDetails {
+        key_preview: hub_licensing::mask_secret(key),
+        plan: config.license_plan,
+        status: config.license_status.clone(),
+        customer_email: config.customer_email.clone(),
+        last_validated_relative: relative(&config.last_validated),
+        last_validated: config.last_validated.clone(),
+        expires_relative: relative(&config.license_expires_at),
+        expires_at: config.license_expires_at.clone(),
+        offline: config.offline_token.is_some(),
+    }))
+}
+
+/// Everything known about the stored license for a detailed license panel,
+/// or `None` when no license is stored. The license is validated first; if
+/// the server can't be reached the stored values are returned instead, with
+/// `license_info` unset.
+#[tauri::command]
+pub async fn get_full_license_info(app: AppHandle) -> Result<Option<FullLicenseInfo>, String> {
+    if LicenseConfig::load().map_err(|e| e.to_string())?.license_key.is_none() {
+        return Ok(None);
+    }
+
+    let (license_info, offline_reason) = match lemonsqueezy::validate_existing().await {
+        Ok(result) => (result.license_info, None),
+        Err(e) if e.is_retryable() => (None, Some(e.to_string())),
+        Err(e) => return Err(e.to_string()),
+    };
+    crate::refresh_tray_status(&app);
+
+    // Reloaded, since validating refreshes the stored status
+    let config = LicenseConfig::load().map_err(|e| e.to_string())?;
+    let Some(ref key) = config.license_key else {
+        return Ok(None);
+    };
+    let key_preview = hub_licensing::mask_secret(key);
+    Ok(Some(FullLicenseInfo {
+        license_info: license_info.map(|info| LicenseInfo { key: key_preview.clone(), ..info }),
+        key_preview,
+        plan: config.license_plan,
+        status: config.license_status.clone(),
+        customer_email: config.customer_email.clone(),
+        expires_at: config.license_expires_at.clone(),
+        last_validated: config.last_validated.clone(),
+        offline: config.offline_token.is_some(),
+        offline_reason,
+    }))
+}
+
+/// Why the license server has been unreachable since the last request that
+/// got through, or `None` if it answered last time
+#[tauri::command]
+pub fn get_last_license_error() -> Option<NetworkErrorRecord> {
+    lemonsqueezy::last_network_error()
+}
+
+/// Most recent license audit log entries (activations, validations,
+/// deactivations), oldest first; 50 unless `limit` is given
+#[tauri::command]
+pub fn get_license_audit(limit: Option<usize>) -> Result<Vec<AuditEntry>, String> {
+    audit::recent_entries(limit.unwrap_or(50)).map_err(|e| e.to_string())
+}
+
 /// Get trial information
 #[tauri::command]
 pub fn get_trial_info() -> Result<TrialInfo, String> {
     trial::get_trial_status().map_err(|e| e.to_string())
 }
 
+/// Trial time left as text, e.g. "3 days, 4 hours remaining" (see
+/// `trial::format_trial_remaining`)
+#[tauri::command]
+pub fn get_trial_remaining_text() -> Result<String, String> {
+    trial::get_trial_status()
+        .map(|info| trial::format_trial_remaining(&info))
+        .map_err(|e| e.to_string())
+}
+
+/// Seconds left in the trial (0 unless it's active), so the UI can tick a
+/// countdown without asking every second
+#[tauri::command]
+pub fn get_trial_seconds_remaining() -> Result<u64, String> {
+    trial::get_trial_status()
+        .map(|info| info.seconds_remaining)
+        .map_err(|e| e.to_string())
+}
+
 /// Start the 7-day trial
 #[tauri::command]
-pub fn start_trial() -> Result<TrialInfo, String> {
-    trial::start_trial().map_err(|e| e.to_string())
+pub async fn start_trial(app: AppHandle) -> Result<TrialInfo, String> {
+    let info = trial::start_trial().await.map_err(|e| e.to_string())?;
+    crate::refresh_tray_status(&app);
+    Ok(info)
 }
 
-/// Activate a license key
+/// Redeem a promo code to extend the active trial
 #[tauri::command]
-pub async fn activate_license(license_key: String) -> Result<ActivationResultResponse, String> {
-    let result = lemonsqueezy::activate_and_save(&license_key)
+pub async fn apply_trial_extension(app: AppHandle, code: String) -> Result<TrialInfo, String> {
+    let info = trial::redeem_extension_code(&code)
         .await
         .map_err(|e| e.to_string())?;
+    crate::refresh_tray_status(&app);
+    Ok(info)
+}
+
+/// Get the trial countdown notification to show, if a new milestone was
+/// reached (3 days left, 1 day left, expired). Each is returned only once.
+#[tauri::command]
+pub fn check_trial_notifications() -> Result<Option<TrialNotification>, String> {
+    trial::check_trial_notification()
+        .map(|milestone| milestone.map(TrialNotification::from))
+        .map_err(|e| e.to_string())
+}
+
+/// Activate a license key
+#[tauri::command]
+pub async fn activate_license(
+    app: AppHandle,
+    license_key: String,
+) -> Result<ActivationResultResponse, String> {
+    let result = match lemonsqueezy::activate_and_save(&license_key).await {
+        Ok(result) => result,
+        // Reported as a refusal the UI can recognize, to offer freeing a slot
+        Err(e @ LicenseError::ActivationLimitReached { limit }) => {
+            return Ok(ActivationResultResponse {
+                success: false,
+                error: Some(e.to_string()),
+                activation_limit_reached: true,
+                plan: None,
+                customer_email: None,
+                activations_used: None,
+                activations_limit: limit,
+                activations_unlimited: false,
+            })
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+    crate::refresh_tray_status(&app);
 
     Ok(ActivationResultResponse {
         success: result.activated,
         error: result.error,
+        activation_limit_reached: false,
         plan: result.meta.as_ref().map(|m| m.variant_name.clone()),
         customer_email: result.meta.as_ref().map(|m| m.customer_email.clone()),
+        activations_used: result.license_info.as_ref().map(|i| i.activation_usage),
+        activations_limit: result.license_info.as_ref().and_then(|i| i.activation_limit),
+        activations_unlimited: result
+            .license_info
+            .as_ref()
+            .is_some_and(|i| i.activation_limit.is_none()),
     })
 }
 
+/// Activate with a signed offline license token (air-gapped machines). An
+/// online activation it replaces is released in the background.
+#[tauri::command]
+pub fn activate_offline_token(app: AppHandle, token: String) -> Result<LicenseInfo, String> {
+    let info = offline::activate_offline_token(&token).map_err(|e| e.to_string())?;
+    crate::refresh_tray_status(&app);
+    crate::spawn_pending_deactivation(app);
+    Ok(info)
+}
+
 /// Validate existing license (refresh status from server)
 #[tauri::command]
-pub async fn validate_license() -> Result<ValidationResultResponse, String> {
+pub async fn validate_license(app: AppHandle) -> Result<ValidationResultResponse, String> {
     let result = lemonsqueezy::validate_existing()
         .await
         .map_err(|e| e.to_string())?;
+    crate::refresh_tray_status(&app);
 
     Ok(ValidationResultResponse {
+        rejection_reason: result.rejection_reason(),
         valid: result.valid,
         error: result.error,
         status: result.license_info.as_ref().map(|i| i.status.clone()),
     })
 }
 
-/// Deactivate license (remove from this machine)
+/// Deactivate license (remove from this machine). `force` removes it locally
+/// even when the license server can't be reached.
 #[tauri::command]
-pub async fn deactivate_license() -> Result<bool, String> {
-    lemonsqueezy::deactivate_and_clear()
+pub async fn deactivate_license(app: AppHandle, force: bool) -> Result<bool, String> {
+    let deactivated = lemonsqueezy::deactivate_and_clear(force)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    crate::refresh_tray_status(&app);
+    Ok(deactivated)
 }
 
-/// Get the LemonSqueezy checkout URL for purchasing
+/// Deactivate every known activation of a license key, this machine's
+/// included, to free slots held by machines the user no longer has. Every
+/// machine using the key loses its license; this one's is cleared locally.
 #[tauri::command]
-pub fn get_checkout_url(plan: String) -> String {
-    match plan.as_str() {
-        // Subscription (monthly/yearly variants are on the same checkout page)
-        "monthly" | "yearly" => {
-            "https://slking.lemonsqueezy.com/checkout/buy/e84ca54b-c009-4262-a434-2528592e4077"
-                .to_string()
-        }
-        // Lifetime product
-        "lifetime" => {
-            "https://slking.lemonsqueezy.com/checkout/buy/346b4776-f424-4c23-8980-227233e240cb"
-                .to_string()
-        }
-        // Fallback to store page
-        _ => "https://slking.lemonsqueezy.com".to_string(),
-    }
+pub async fn deactivate_all_license_instances(
+    app: AppHandle,
+    license_key: String,
+) -> Result<DeactivateAllResult, String> {
+    let result = lemonsqueezy::deactivate_all_instances(&license_key)
+        .await
+        .map_err(|e| e.to_string())?;
+    crate::refresh_tray_status(&app);
+    Ok(result)
 }
 
-/// Open a URL in the default browser
+/// Stop retrying a pending deactivation the hub couldn't complete, once the
+/// user has released the device from their LemonSqueezy account themselves
 #[tauri::command]
-pub fn open_checkout(plan: String) -> Result<(), String> {
+pub fn dismiss_pending_deactivation() -> Result<(), String> {
+    let mut config = LicenseConfig::load().map_err(|e| e.to_string())?;
+    config.pending_deactivation = None;
+    config.save().map_err(|e| e.to_string())
+}
+
+/// Generate a new machine ID, releasing this machine's activation. The
+/// license has to be activated again afterwards.
+#[tauri::command]
+pub async fn reset_machine_id(app: AppHandle) -> Result<String, String> {
+    let machine_id = lemonsqueezy::reset_machine_id()
+        .await
+        .map_err(|e| e.to_string())?;
+    crate::refresh_tray_status(&app);
+    Ok(machine_id)
+}
+
+/// Get the LemonSqueezy checkout URL for purchasing, optionally prefilled
+/// with the customer's email and a discount code
+#[tauri::command]
+pub fn get_checkout_url(plan: String, email: Option<String>, discount: Option<String>) -> String {
+    lemonsqueezy::checkout_url(&plan, email.as_deref(), discount.as_deref())
+}
+
+/// Open a URL in the default browser.
+///
+/// If no browser can be launched (e.g. headless Linux), the error carries the
+/// URL so the frontend can show a copyable link.
+#[tauri::command]
+pub fn open_checkout(app: AppHandle, plan: String) -> Result<(), String> {
     let url = match plan.as_str() {
-        // LemonSqueezy checkout URLs
-        "monthly" | "yearly" => {
-            "https://slking.lemonsqueezy.com/checkout/buy/e84ca54b-c009-4262-a434-2528592e4077"
-        }
-        "lifetime" => {
-            "https://slking.lemonsqueezy.com/checkout/buy/346b4776-f424-4c23-8980-227233e240cb"
-        }
         // OpenAI help links
-        "openai-keys" => "https://platform.openai.com/api-keys",
-        "openai-usage" => "https://platform.openai.com/usage",
-        "video-tutorial" => "https://youtu.be/SzPE_AE0eEo?si=WbJP-ABj0uG5s-XV",
-        // Fallback
-        _ => "https://slking.lemonsqueezy.com",
+        "openai-keys" => "https://platform.openai.com/api-keys".to_string(),
+        "openai-usage" => "https://platform.openai.com/usage".to_string(),
+        "video-tutorial" => "https://youtu.be/SzPE_AE0eEo?si=WbJP-ABj0uG5s-XV".to_string(),
+        // LemonSqueezy checkout URLs (storefront fallback)
+        _ => lemonsqueezy::checkout_url(&plan, None, None),
     };
 
-    // Use the Windows shell to open the URL
-    #[cfg(windows)]
-    {
-        std::process::Command::new("cmd")
-            .args(["/C", "start", "", url])
-            .spawn()
-            .map_err(|e| e.to_string())?;
-    }
+    let could_not_open = || format!("Could not open browser, visit: {}", url);
 
-    #[cfg(not(windows))]
+    // xdg-open "succeeds" without a display, so don't even try
+    #[cfg(target_os = "linux")]
     {
-        std::process::Command::new("xdg-open")
-            .arg(url)
-            .spawn()
-            .map_err(|e| e.to_string())?;
+        if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+            return Err(could_not_open());
+        }
     }
 
-    Ok(())
+    app.opener().open_url(&url, None::<&str>).map_err(|e| {
+        error!("Failed to open {}: {}", url, e);
+        could_not_open()
+    })
 }
 
 // Response types for frontend
@@ -128,8 +310,52 @@ pub fn open_checkout(plan: String) -> Result<(), String> {
 pub struct ActivationResultResponse {
     pub success: bool,
     pub error: Option<String>,
+    /// Refused because the key is activated on as many machines as it allows
+    pub activation_limit_reached: bool,
     pub plan: Option<String>,
     pub customer_email: Option<String>,
+    /// Machines this key is activated on, including this one
+    pub activations_used: Option<u32>,
+    /// Activation limit; `None` with `activations_unlimited` set means there's no limit
+    pub activations_limit: Option<u32>,
+    pub activations_unlimited: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LicenseDetails {
+    pub key_preview: String,
+    pub plan: Option<LicensePlan>,
+    pub status: Option<String>,
+    pub customer_email: Option<String>,
+    /// RFC3339 timestamp of the last successful check
+    pub last_validated: Option<String>,
+    /// e.g. "2 hours ago"
+    pub last_validated_relative: Option<String>,
+    /// RFC3339 expiry, `None` for licenses that never expire
+    pub expires_at: Option<String>,
+    /// e.g. "in 20 days"
+    pub expires_relative: Option<String>,
+    /// Activated with an offline token rather than online
+    pub offline: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FullLicenseInfo {
+    pub key_preview: String,
+    pub plan: Option<LicensePlan>,
+    pub status: Option<String>,
+    pub customer_email: Option<String>,
+    /// RFC3339 expiry, `None` for licenses that never expire
+    pub expires_at: Option<String>,
+    /// RFC3339 timestamp of the last successful check
+    pub last_validated: Option<String>,
+    /// Activated with an offline token rather than online
+    pub offline: bool,
+    /// The server's record (activation limit and usage, license ID), with the
+    /// key masked. `None` if the server couldn't be reached.
+    pub license_info: Option<LicenseInfo>,
+    /// Why the server couldn't be reached; the other fields are the stored ones
+    pub offline_reason: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -137,4 +363,23 @@ pub struct ValidationResultResponse {
     pub valid: bool,
     pub error: Option<String>,
     pub status: Option<String>,
+    /// Why the server rejected the license, for "your license was disabled: ..."
+    pub rejection_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrialNotification {
+    pub milestone: TrialMilestone,
+    pub title: String,
+    pub message: String,
+}
+
+impl From<TrialMilestone> for TrialNotification {
+    fn from(milestone: TrialMilestone) -> Self {
+        Self {
+            milestone,
+            title: milestone.title().to_string(),
+            message: milestone.message().to_string(),
+        }
+    }
 }
@@ -0,0 +1,89 @@
+//! Hub log output - console plus a daily-rotated file in the config directory
+
+use anyhow::{Context, Result};
+use hub_common::HubConfig;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{filter::LevelFilter, fmt, prelude::*};
+
+/// Log files are named `hub.<date>.log`
+const LOG_FILE_PREFIX: &str = "hub";
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// Daily log files kept before the oldest is deleted
+const MAX_LOG_FILES: usize = 7;
+
+/// Directory holding the hub's log files
+pub fn log_dir() -> Result<PathBuf> {
+    Ok(HubConfig::config_dir()?.join("logs"))
+}
+
+/// Install the global tracing subscriber.
+///
+/// Console output always works; file logging is skipped (with a warning) if
+/// the log directory can't be written.
+pub fn init() {
+    let appender = log_dir().and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix(LOG_FILE_SUFFIX)
+            .max_log_files(MAX_LOG_FILES)
+            .build(&dir)
+            .with_context(|| format!("Failed to open log file in {}", dir.display()))
+    });
+    let (file_layer, file_error) = match appender {
+        Ok(appender) => (Some(fmt::layer().with_ansi(false).with_writer(appender)), None),
+        Err(e) => (None, Some(e)),
+    };
+
+    tracing_subscriber::registry()
+        .with(LevelFilter::INFO)
+        .with(fmt::layer())
+        .with(file_layer)
+        .init();
+
+    if let Some(e) = file_error {
+        warn!("File logging disabled: {:#}", e);
+    }
+}
+
+fn is_log_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| {
+            name.starts_with(&format!("{}.", LOG_FILE_PREFIX))
+                && name.ends_with(&format!(".{}", LOG_FILE_SUFFIX))
+        })
+}
+
+/// The last `count` log lines, oldest first, reading back across rotated files
+/// as needed
+pub fn recent_lines(count: usize) -> Result<Vec<String>> {
+    let dir = log_dir()?;
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| is_log_file(path))
+        .collect();
+    // Dates in the file names sort chronologically
+    files.sort();
+
+    let mut lines = Vec::new();
+    for path in files.iter().rev() {
+        if lines.len() >= count {
+            break;
+        }
+        let contents =
+            fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut older: Vec<String> =
+            String::from_utf8_lossy(&contents).lines().map(str::to_string).collect();
+        older.append(&mut lines);
+        lines = older;
+    }
+
+    let skip = lines.len().saturating_sub(count);
+    Ok(lines.split_off(skip))
+}
@@ -1,43 +1,207 @@
 // Prevents additional console window on Windows in release mode
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod config_writer;
+mod hub_lock;
 mod license_commands;
+mod logging;
 mod process_manager;
 mod tauri_commands;
 
-use hub_common::{HubConfig, ToolId};
-use parking_lot::RwLock;
-use process_manager::ProcessManager;
+use hub_common::{ConfigRecovery, HubConfig, ToolDescriptor};
+use hub_licensing::{AuthStatus, LicenseConfig};
+use parking_lot::{Mutex, RwLock};
+use config_writer::ConfigWriter;
+use hub_lock::{HubLock, LockOutcome, LockOwner};
+use process_manager::{HubRunState, ProcessManager, StartupCheck};
+use std::time::Duration;
 use tauri::{
-    menu::{Menu, MenuItem},
+    image::Image,
+    menu::{Menu, MenuItem, PredefinedMenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Manager, Runtime,
+    AppHandle, Emitter, Manager, Runtime,
 };
+use tracing::{error, info, warn};
 
 /// Application state shared across the app
 pub struct AppState {
     pub config: RwLock<HubConfig>,
     pub process_manager: RwLock<ProcessManager>,
+    /// Third-party tools loaded from `tools.d` at startup
+    pub plugins: Vec<ToolDescriptor>,
+    /// Debounced writer for `config`
+    pub config_writer: ConfigWriter,
+    /// Marks this hub as the one managing tools (released on exit)
+    pub hub_lock: Mutex<Option<HubLock>>,
+    /// Another running hub that manages the tools. This hub then starts none.
+    pub other_hub: Option<LockOwner>,
+    /// Set when the config file couldn't be parsed and defaults are in use
+    pub config_recovery: Option<ConfigRecovery>,
 }
 
 impl AppState {
-    pub fn new(config: HubConfig) -> Self {
+    pub fn new(config: HubConfig, config_recovery: Option<ConfigRecovery>) -> Self {
         let mut pm = ProcessManager::new();
+        pm.set_tool_directory(config.tool_directory.clone());
+        pm.set_stop_on_exit(config.stop_tools_on_exit);
+        pm.set_api_endpoint(config.api_provider, config.api_base_url().map(str::to_string));
         // Detect already-running tools (done here so it's ready when UI loads)
         pm.init_detect_running();
+
+        let plugins = HubConfig::plugin_dir()
+            .map(|dir| ToolDescriptor::load_plugins(&dir))
+            .unwrap_or_default();
+        for plugin in &plugins {
+            info!("Loaded plugin tool: {} ({})", plugin.display_name, plugin.id);
+        }
+
+        let (hub_lock, other_hub) = match HubLock::acquire() {
+            Ok(LockOutcome::Acquired(lock)) => (Some(lock), None),
+            Ok(LockOutcome::HeldBy(owner)) => {
+                warn!(
+                    "Hub at {} (PID {}) is managing tools - this hub won't start any",
+                    owner.path.display(),
+                    owner.pid
+                );
+                (None, Some(owner))
+            }
+            Err(e) => {
+                warn!("Failed to take the hub lock, managing tools anyway: {:#}", e);
+                (None, None)
+            }
+        };
         
         Self {
             config: RwLock::new(config),
             process_manager: RwLock::new(pm),
+            plugins,
+            config_writer: ConfigWriter::new(),
+            hub_lock: Mutex::new(hub_lock),
+            other_hub,
+            config_recovery,
         }
     }
 }
 
-fn create_tray_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
+/// How often the tray's license/trial text is refreshed (trial hours tick down)
+const TRAY_STATUS_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+fn create_tray_menu<R: Runtime>(
+    app: &AppHandle<R>,
+    auth: &AuthStatus,
+    paused: bool,
+) -> tauri::Result<Menu<R>> {
+    // Disabled item - just shows the license/trial state
+    let license_item = MenuItem::with_id(app, "license", auth.summary(), false, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
     let show_dashboard = MenuItem::with_id(app, "show", "Show Dashboard", true, None::<&str>)?;
+    let pause_label = if paused { "Resume Tools" } else { "Pause and Stop All Tools" };
+    let pause_item = MenuItem::with_id(app, "toggle_pause", pause_label, true, None::<&str>)?;
     let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let quit_all_item =
+        MenuItem::with_id(app, "quit_all", "Quit and Stop All Tools", true, None::<&str>)?;
+
+    Menu::with_items(
+        app,
+        &[
+            &license_item,
+            &separator,
+            &show_dashboard,
+            &pause_item,
+            &quit_item,
+            &quit_all_item,
+        ],
+    )
+}
+
+fn tray_tooltip(auth: &AuthStatus, paused: bool) -> String {
+    let tooltip = match auth {
+        AuthStatus::Licensed { .. } => "Productivity Hub - Licensed".to_string(),
+        _ => format!("Productivity Hub - {}", auth.summary()),
+    };
+    if paused {
+        format!("{} (paused)", tooltip)
+    } else {
+        tooltip
+    }
+}
+
+/// Update the tray tooltip and menu after the license/trial or pause state changed
+pub fn refresh_tray_status<R: Runtime>(app: &AppHandle<R>) {
+    let Some(tray) = app.tray_by_id("main") else {
+        return;
+    };
+    let auth = hub_licensing::get_auth_status();
+    let paused = app.state::<AppState>().config.read().paused;
+
+    if let Ok(menu) = create_tray_menu(app, &auth, paused) {
+        let _ = tray.set_menu(Some(menu));
+    }
+    let _ = tray.set_tooltip(Some(tray_tooltip(&auth, paused)));
+}
 
-    Menu::with_items(app, &[&show_dashboard, &quit_item])
+/// Keep the tray's trial countdown current
+fn spawn_tray_status_timer<R: Runtime>(app: AppHandle<R>) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(TRAY_STATUS_INTERVAL);
+        refresh_tray_status(&app);
+    });
+}
+
+/// How often the tray icon is checked against the tools' state
+const TRAY_ICON_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Badge the tray icon green while tools run and red when one has an error
+fn spawn_tray_icon_timer<R: Runtime>(app: AppHandle<R>) {
+    std::thread::spawn(move || {
+        let mut shown = None;
+        loop {
+            let run_state = {
+                let state = app.state::<AppState>();
+                let mut pm = state.process_manager.write();
+                pm.refresh_statuses();
+                pm.aggregate_status()
+            };
+            if shown != Some(run_state) {
+                set_tray_icon(&app, run_state);
+                shown = Some(run_state);
+            }
+            std::thread::sleep(TRAY_ICON_INTERVAL);
+        }
+    });
+}
+
+fn set_tray_icon<R: Runtime>(app: &AppHandle<R>, run_state: HubRunState) {
+    let (Some(tray), Some(base)) = (app.tray_by_id("main"), app.default_window_icon()) else {
+        return;
+    };
+    // Same success/error colors as the dashboard
+    let icon = match run_state {
+        HubRunState::AllStopped => base.clone().to_owned(),
+        HubRunState::SomeRunning => with_badge(base, [0x3f, 0xb9, 0x50, 0xff]),
+        HubRunState::ErrorPresent => with_badge(base, [0xf8, 0x51, 0x49, 0xff]),
+    };
+    let _ = tray.set_icon(Some(icon));
+}
+
+/// Copy of `base` with a filled dot of `color` in the bottom-right corner
+fn with_badge(base: &Image<'_>, color: [u8; 4]) -> Image<'static> {
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    let radius = width.min(height) as f32 / 5.0;
+    let (center_x, center_y) = (width as f32 - radius, height as f32 - radius);
+
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+            if dx * dx + dy * dy <= radius * radius {
+                let i = ((y * width + x) * 4) as usize;
+                rgba[i..i + 4].copy_from_slice(&color);
+            }
+        }
+    }
+    Image::new_owned(rgba, width, height)
 }
 
 fn handle_tray_event<R: Runtime>(app: &AppHandle<R>, event: TrayIconEvent) {
@@ -62,9 +226,42 @@ fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, id: &str) {
                 let _ = window.set_focus();
             }
         }
+        "toggle_pause" => {
+            let state = app.state::<AppState>();
+            let paused = !state.config.read().paused;
+            tauri_commands::apply_paused(&state, paused, true);
+            refresh_tray_status(app);
+            let _ = app.emit("paused-changed", paused);
+        }
         "quit" => {
-            // Don't stop tools - let them keep running
-            app.exit(0);
+            // Tools keep running unless the user asked otherwise. Only tools
+            // the hub started are stopped; last_running is left as it was so
+            // restore_session brings them back.
+            let state = app.state::<AppState>();
+            if !state.config.read().stop_tools_on_exit {
+                app.exit(0);
+                return;
+            }
+            // Stopping waits for the tools to exit, keep the event loop free
+            let app = app.clone();
+            std::thread::spawn(move || {
+                let state = app.state::<AppState>();
+                tauri_commands::stop_all_unlocked(&state, false);
+                app.exit(0);
+            });
+        }
+        "quit_all" => {
+            let app = app.clone();
+            std::thread::spawn(move || {
+                let state = app.state::<AppState>();
+                for (tool_id, result) in tauri_commands::stop_all_unlocked(&state, true) {
+                    if let Err(e) = result {
+                        warn!("Failed to stop {}: {}", tool_id, e);
+                    }
+                }
+                tauri_commands::record_running_tools(&state);
+                app.exit(0);
+            });
         }
         _ => {}
     }
@@ -72,40 +269,176 @@ fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, id: &str) {
 
 fn auto_start_tools<R: Runtime>(app: &AppHandle<R>) {
     // Check if user is authorized (valid license or active trial)
-    if !hub_licensing::is_authorized() {
-        println!("Not authorized - skipping auto-start of tools");
+    if !process_manager::license_allows_start() {
+        info!("Not authorized - skipping auto-start of tools");
         return;
     }
-    
+
     let state = app.state::<AppState>();
+    if state.other_hub.is_some() {
+        info!("Another hub is managing tools - skipping auto-start");
+        return;
+    }
+    if state.config.read().paused {
+        info!("Tools are paused - skipping auto-start");
+        return;
+    }
     let config = state.config.read().clone();
-    let has_api_key = hub_common::config::has_api_key();
-    
-    for tool_id in ToolId::all() {
-        let tool_config = config.get_tool_config(tool_id);
-        
-        if tool_config.enabled && tool_config.auto_start {
-            // Skip if tool requires API key but we don't have one
-            if tool_id.requires_api_key() && !has_api_key {
+    let decisions =
+        tauri_commands::autostart_decisions(&config, true, hub_common::config::has_api_key());
+    let mut first = true;
+    for (tool_id, skip) in decisions {
+        if skip.is_some() {
+            continue;
+        }
+        let tool_config = config.get_tool_config(&tool_id);
+        // Spread out launches so heavy tools don't all start at once
+        if !first && tool_config.autostart_delay_ms > 0 {
+            std::thread::sleep(Duration::from_millis(tool_config.autostart_delay_ms.into()));
+        }
+        first = false;
+
+        // Only hold the lock for the spawn and each poll, like `launch_tool`
+        let spawned = state.process_manager.write().spawn_tool(&tool_id, &tool_config);
+        match spawned {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) => {
+                warn!("Failed to auto-start {}: {}", tool_id.display_name(), e);
                 continue;
             }
-            
-            let mut pm = state.process_manager.write();
-            let _ = pm.start_tool_with_config(tool_id, &tool_config);
         }
+        let check = process_manager::watch_startup(tool_config.startup_grace(), || {
+            state.process_manager.write().poll_startup(&tool_id)
+        });
+        match check {
+            StartupCheck::Pending => state.process_manager.write().finish_startup(&tool_id),
+            StartupCheck::Failed(e) => {
+                warn!("Failed to auto-start {}: {}", tool_id.display_name(), e);
+            }
+            StartupCheck::Cancelled => {}
+        }
+    }
+
+    tauri_commands::record_running_tools(&state);
+}
+
+/// How often to check whether a trial countdown notification is due
+const TRIAL_NOTIFICATION_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Check for trial countdown notifications once a day (the UI checks on load)
+fn spawn_trial_notification_timer<R: Runtime>(app: AppHandle<R>) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(TRIAL_NOTIFICATION_INTERVAL);
+        match license_commands::check_trial_notifications() {
+            Ok(Some(notification)) => {
+                info!("Trial notification: {}", notification.message);
+                refresh_tray_status(&app);
+                let _ = app.emit("trial-notification", notification);
+            }
+            Ok(None) => {}
+            Err(e) => error!("Failed to check trial notifications: {}", e),
+        }
+    });
+}
+
+/// How often to re-check the config while external auto-scan is off
+const EXTERNAL_SCAN_IDLE_POLL: Duration = Duration::from_secs(5);
+
+/// Scan for externally started tools on the configured interval, telling the
+/// UI about anything that appeared or went away
+fn spawn_external_scan_timer<R: Runtime>(app: AppHandle<R>) {
+    std::thread::spawn(move || loop {
+        let state = app.state::<AppState>();
+        let Some(interval) = state.config.read().external_scan_interval() else {
+            std::thread::sleep(EXTERNAL_SCAN_IDLE_POLL);
+            continue;
+        };
+
+        std::thread::sleep(interval);
+        let diff = state.process_manager.write().full_scan();
+        if !diff.is_empty() {
+            info!(
+                "External scan: detected {:?}, disappeared {:?}",
+                diff.detected, diff.disappeared
+            );
+            let _ = app.emit("external-scan", diff);
+        }
+    });
+}
+
+/// Finish releasing an activation that a forced deactivation left registered
+/// with the server. Tells the frontend once retries have run out.
+fn spawn_pending_deactivation<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        match hub_licensing::lemonsqueezy::complete_pending_deactivation().await {
+            Ok(Some(pending)) if pending.is_stuck() => {
+                warn!("Giving up on deactivating license instance {}", pending.instance_id);
+                let _ = app.emit("deactivation-stuck", pending.attempts);
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Could not complete pending deactivation: {:#}", e),
+        }
+    });
+}
+
+/// Re-check a stored license with the server in the background, so a license
+/// disabled server-side stops working without the user clicking "Validate"
+fn spawn_launch_license_check<R: Runtime>(app: AppHandle<R>) {
+    let has_license = LicenseConfig::load()
+        .map(|config| config.license_key.is_some() || config.offline_token.is_some())
+        .unwrap_or(false);
+    if !has_license {
+        return;
     }
+
+    tauri::async_runtime::spawn(async move {
+        match hub_licensing::lemonsqueezy::validate_existing().await {
+            Ok(result) => {
+                let status = hub_licensing::get_auth_status();
+                info!("License checked on launch (valid: {}): {}", result.valid, status);
+                refresh_tray_status(&app);
+                let _ = app.emit("license-status-changed", status);
+                if let Some(reason) = result.rejection_reason() {
+                    warn!("License rejected by server: {}", reason);
+                    let _ = app.emit("license-rejected", reason);
+                }
+            }
+            // Offline: keep running on the stored status
+            Err(e) => {
+                warn!("Could not validate license on launch: {:#}", e);
+                if let Some(record) = hub_licensing::lemonsqueezy::last_network_error() {
+                    let _ = app.emit("license-server-unreachable", record);
+                }
+            }
+        }
+    });
 }
 
 fn main() {
+    // Release builds have no console, so everything also goes to a log file
+    logging::init();
+
     // Load configuration
-    let config = HubConfig::load().unwrap_or_default();
+    match HubConfig::config_path() {
+        Ok(path) => info!("Config: {}", path.display()),
+        Err(e) => warn!("Config directory unavailable, settings won't be saved: {:#}", e),
+    }
+    match LicenseConfig::config_path() {
+        Ok(path) => info!("License config: {}", path.display()),
+        Err(e) => warn!("License config directory unavailable: {:#}", e),
+    }
+    let (config, config_recovery) = HubConfig::load_or_recover().unwrap_or_else(|e| {
+        warn!("Failed to load config, using defaults: {:#}", e);
+        (HubConfig::default(), None)
+    });
     let should_minimize = config.start_minimized;
-    let app_state = AppState::new(config);
+    let app_state = AppState::new(config, config_recovery);
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-            println!("Second instance detected - bringing existing window to front");
+            info!("Second instance detected - bringing existing window to front");
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.show();
                 let _ = window.set_focus();
@@ -117,27 +450,66 @@ fn main() {
             // Config commands
             tauri_commands::get_config,
             tauri_commands::save_config,
+            tauri_commands::set_tool_hotkey,
+            tauri_commands::save_config_now,
+            tauri_commands::export_config,
+            tauri_commands::import_config,
+            tauri_commands::get_tool_catalog,
+            tauri_commands::get_assignable_keys,
+            tauri_commands::preview_autostart,
             tauri_commands::has_api_key,
             tauri_commands::get_api_key_masked,
             tauri_commands::get_api_key,
             tauri_commands::save_api_key,
             tauri_commands::delete_api_key,
+            tauri_commands::has_secure_storage,
             tauri_commands::validate_api_key,
+            tauri_commands::test_api_key,
             tauri_commands::get_tool_statuses,
+            tauri_commands::get_tool_status,
+            tauri_commands::get_tool_status_reports,
+            tauri_commands::get_hub_log,
+            tauri_commands::open_config_dir,
+            tauri_commands::get_tool_instance_counts,
+            tauri_commands::get_hub_run_state,
+            tauri_commands::get_tool_last_exit,
+            tauri_commands::get_tool_versions,
             tauri_commands::scan_external_processes,
             tauri_commands::start_tool,
             tauri_commands::stop_tool,
+            tauri_commands::stop_all_tools,
+            tauri_commands::start_tools,
+            tauri_commands::stop_tools,
+            tauri_commands::is_paused,
+            tauri_commands::get_other_hub,
+            tauri_commands::get_config_recovery,
+            tauri_commands::set_paused,
+            tauri_commands::restart_tool,
+            tauri_commands::test_tool_launch,
             tauri_commands::open_tool_settings,
             tauri_commands::get_desktalk_parallel,
             tauri_commands::set_desktalk_parallel,
             // License commands
             license_commands::get_auth_status,
             license_commands::is_authorized,
+            license_commands::check_authorization,
             license_commands::get_trial_info,
+            license_commands::get_trial_remaining_text,
+            license_commands::get_trial_seconds_remaining,
+            license_commands::get_license_details,
             license_commands::start_trial,
+            license_commands::check_trial_notifications,
+            license_commands::apply_trial_extension,
             license_commands::activate_license,
+            license_commands::activate_offline_token,
             license_commands::validate_license,
+            license_commands::get_full_license_info,
+            license_commands::get_last_license_error,
+            license_commands::get_license_audit,
             license_commands::deactivate_license,
+            license_commands::deactivate_all_license_instances,
+            license_commands::dismiss_pending_deactivation,
+            license_commands::reset_machine_id,
             license_commands::get_checkout_url,
             license_commands::open_checkout,
         ])
@@ -149,12 +521,14 @@ fn main() {
             let handle_for_tray = app.handle().clone();
 
             // Create tray menu
-            let menu = create_tray_menu(&handle)?;
+            let auth = hub_licensing::get_auth_status();
+            let paused = handle.state::<AppState>().config.read().paused;
+            let menu = create_tray_menu(&handle, &auth, paused)?;
             let icon = app.default_window_icon().cloned();
 
             let mut builder = TrayIconBuilder::with_id("main")
                 .menu(&menu)
-                .tooltip("Productivity Hub");
+                .tooltip(tray_tooltip(&auth, paused));
             if let Some(icon) = icon {
                 builder = builder.icon(icon);
             }
@@ -177,17 +551,37 @@ fn main() {
 
                 if !should_minimize {
                     let _ = window.show();
-                    println!("Starting with window visible");
+                    info!("Starting with window visible");
                 } else {
-                    println!("Starting minimized to tray");
+                    info!("Starting minimized to tray");
                 }
             }
 
-            // Auto-start configured tools
-            auto_start_tools(&handle);
+            // Auto-start configured tools (in the background, since
+            // configured delays between launches can add up)
+            let autostart_handle = handle.clone();
+            std::thread::spawn(move || auto_start_tools(&autostart_handle));
+
+            spawn_trial_notification_timer(handle.clone());
+            spawn_tray_status_timer(handle.clone());
+            spawn_tray_icon_timer(handle.clone());
+            spawn_external_scan_timer(handle.clone());
+            spawn_launch_license_check(handle.clone());
+            spawn_pending_deactivation(handle.clone());
 
             Ok(())
         })
-        .run(tauri::generate_context!("tauri.conf.json"))
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!("tauri.conf.json"))
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                let state = app.state::<AppState>();
+                // Don't lose a debounced save that hasn't been written yet
+                if let Err(e) = state.config_writer.flush() {
+                    error!("Failed to save config on exit: {}", e);
+                }
+                // Let the next hub manage tools
+                state.hub_lock.lock().take();
+            }
+        });
 }
@@ -1,21 +1,379 @@
 //! Process Manager - Start, stop, and monitor tool processes
 
-use anyhow::{Context, Result};
-use hub_common::{config, ToolConfig, ToolId, ToolStatus};
-use std::collections::HashMap;
+use anyhow::Result;
+use hub_common::config::{
+    check_env_var, is_valid_extra_arg, MAX_SPEECH_SPEED, MIN_SPEECH_SPEED, TTS_VOICES,
+};
+use hub_common::hotkeys::HotkeyModifier;
+use hub_common::tools::NOT_RESPONDING;
+use hub_common::{
+    config, heartbeat, ApiProvider, SearchRoots, ToolConfig, ToolDescriptor, ToolId, ToolStartError,
+    ToolStatus,
+};
+use hub_licensing::AuthDecision;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
+use std::process::{Child, ChildStderr, Command, ExitStatus, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
+#[cfg(not(windows))]
+use std::os::unix::process::CommandExt;
+
+/// How often to poll a freshly spawned process during the startup window
+const STARTUP_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// How long a spawned tool gets to exit on its own before it's killed
+const GRACEFUL_STOP_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// How long to wait for a stopped tool to actually exit before giving up
+const STOP_CONFIRM_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait for an external tool to die once it's been force-killed
+const KILL_CONFIRM_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long a `--version` query may run before we give up on it
+const VERSION_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Lines of stderr kept for a tool's `ExitRecord`
+const STDERR_TAIL_LINES: usize = 5;
+
+/// What happens when a tool is started without a license or active trial
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum LicenseEnforcement {
+    /// Refuse to start it
+    Block,
+    /// Start it anyway and log a warning
+    Warn,
+}
+
+impl LicenseEnforcement {
+    /// `Warn` in builds with the `soft-license-check` feature, else `Block`
+    pub const fn for_build() -> Self {
+        if cfg!(feature = "soft-license-check") {
+            Self::Warn
+        } else {
+            Self::Block
+        }
+    }
+}
+
+/// Whether tools may be started, checking the license the way `check_license`
+/// does but without logging
+pub fn license_allows_start() -> bool {
+    LicenseEnforcement::for_build() == LicenseEnforcement::Warn || hub_licensing::is_authorized()
+}
+
+/// Check the license before starting `tool`. Returns the decision when the
+/// start must be refused.
+pub fn check_license(tool: &str) -> Result<(), AuthDecision> {
+    let decision = hub_licensing::authorization_check();
+    if decision.authorized {
+        return Ok(());
+    }
+    match LicenseEnforcement::for_build() {
+        LicenseEnforcement::Block => Err(decision),
+        LicenseEnforcement::Warn => {
+            warn!("Starting {} without a license ({})", tool, decision.status);
+            Ok(())
+        }
+    }
+}
+
+fn not_authorized(tool: &str) -> impl FnOnce(AuthDecision) -> ToolStartError + '_ {
+    move |decision| ToolStartError::NotAuthorized {
+        tool: tool.to_string(),
+        message: decision.message().to_string(),
+    }
+}
+
+/// Most recent stderr lines of a running tool, filled by a reader thread
+type StderrTail = Arc<Mutex<VecDeque<String>>>;
 
 /// Manages child processes for all tools
 #[derive(Debug)]
 pub struct ProcessManager {
     /// Processes we spawned ourselves
     spawned_processes: HashMap<ToolId, Child>,
-    /// External processes we detected (by PID)
-    external_pids: HashMap<ToolId, u32>,
+    /// External processes we detected (by PID; more than one if the tool was
+    /// started several times)
+    external_pids: HashMap<ToolId, Vec<u32>>,
+    /// Spawned tools still inside their startup health-check window
+    starting: HashSet<ToolId>,
+    /// Tools whose processes are being stopped (see `begin_stop`)
+    stopping: HashSet<ToolId>,
+    /// User-configured directory searched first for tool binaries
+    tool_directory: Option<PathBuf>,
+    /// Cached `--version` results (`None` = binary doesn't report a version)
+    versions: HashMap<ToolId, Option<ToolVersion>>,
+    /// Processes for third-party tools, keyed by descriptor ID
+    plugin_processes: HashMap<String, Child>,
+    /// Third-party tools still inside their startup health-check window
+    starting_plugins: HashSet<String>,
+    /// Third-party tools whose processes are being stopped
+    stopping_plugins: HashSet<String>,
+    /// Stderr of spawned tools past their startup window
+    stderr_tails: HashMap<ToolId, StderrTail>,
+    /// How each spawned tool last exited on its own
+    last_exits: HashMap<ToolId, ExitRecord>,
+    /// Job Objects holding spawned tools and everything they start
+    #[cfg(windows)]
+    jobs: HashMap<ToolId, ProcessJob>,
+    #[cfg(windows)]
+    plugin_jobs: HashMap<String, ProcessJob>,
+    /// Stop spawned tools when the manager is dropped (`stop_tools_on_exit`)
+    stop_on_exit: bool,
+    /// Where tools needing the API key send requests
+    api_provider: ApiProvider,
+    api_base_url: Option<String>,
+}
+
+/// A spawned tool exiting without the hub stopping it (a crash, or the tool
+/// quitting by itself)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExitRecord {
+    /// Exit code, `None` if the process was killed by a signal or its status
+    /// couldn't be read
+    pub code: Option<i32>,
+    /// When the exit was noticed (RFC3339)
+    pub exited_at: String,
+    /// Last lines the tool wrote to stderr (empty for GUI tools, whose stderr
+    /// isn't captured)
+    pub stderr: String,
+}
+
+impl ExitRecord {
+    fn now(code: Option<i32>, stderr: String) -> Self {
+        Self {
+            code,
+            exited_at: chrono::Utc::now().to_rfc3339(),
+            stderr,
+        }
+    }
+}
+
+/// Semantic version reported by a tool binary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ToolVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl ToolVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// Find the first `x.y.z` version in `--version` output (e.g. "desk-talk 1.2.3")
+    pub fn parse(output: &str) -> Option<Self> {
+        output.split_whitespace().find_map(|word| {
+            let word = word.trim_start_matches('v');
+            let mut parts = word.splitn(3, '.');
+            let major = parts.next()?.parse().ok()?;
+            let minor = parts.next()?.parse().ok()?;
+            // Allow pre-release/build suffixes like "3-beta.1"
+            let patch = parts.next()?;
+            let digits = patch.find(|c: char| !c.is_ascii_digit()).unwrap_or(patch.len());
+            let patch = patch[..digits].parse().ok()?;
+            Some(Self::new(major, minor, patch))
+        })
+    }
+
+    /// Minimum version known to understand the CLI args the hub passes
+    pub fn min_supported(tool_id: &ToolId) -> Option<Self> {
+        if tool_id.is_gui() {
+            // GUI tools read their own config files, so any version works
+            None
+        } else {
+            // CLI tools need --ptt-key/--trigger-key support
+            Some(Self::new(0, 1, 0))
+        }
+    }
+}
+
+impl std::fmt::Display for ToolVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Result of polling a tool that is still inside its startup window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupCheck {
+    /// Process is still alive
+    Pending,
+    /// Process exited during startup
+    Failed(ToolStartError),
+    /// The start was abandoned (tool was stopped or is no longer tracked)
+    Cancelled,
+}
+
+/// Overall state of the managed tools, e.g. for the tray icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum HubRunState {
+    /// Nothing is running
+    AllStopped,
+    /// At least one tool is running or starting, none has an error
+    SomeRunning,
+    /// At least one tool has an error (e.g. not responding)
+    ErrorPresent,
+}
+
+/// A stop started by `ProcessManager::begin_stop`: the tool's processes were
+/// asked to exit but may still be running
+#[derive(Debug)]
+pub struct PendingStop {
+    tool: ManagedTool,
+    spawned: Option<Child>,
+    #[cfg(windows)]
+    job: Option<ProcessJob>,
+    external: Vec<u32>,
+}
+
+/// What's left of a tool after `PendingStop::wait`
+#[derive(Debug)]
+pub struct StopOutcome {
+    tool: ManagedTool,
+    /// External instances that survived being killed
+    survivors: Vec<u32>,
+}
+
+impl PendingStop {
+    /// Wait for the tool's processes to exit, killing any that don't exit in
+    /// time. Doesn't touch the process manager, so no lock is needed.
+    pub fn wait(self) -> StopOutcome {
+        let name = self.tool.name();
+
+        if let Some(mut child) = self.spawned {
+            if wait_for_child(&mut child, GRACEFUL_STOP_TIMEOUT) {
+                info!("{} stopped gracefully", name);
+            } else {
+                // Its Job Object on Windows, its process group elsewhere
+                #[cfg(windows)]
+                if let Some(job) = self.job {
+                    job.terminate();
+                }
+                // Also covers tools that couldn't be put in a job
+                terminate_tree(child.id(), true);
+                let _ = child.kill();
+                info!("{} force killed", name);
+            }
+            let _ = child.wait();
+        }
+
+        // Spawned processes are reaped above; external ones are only signalled,
+        // so confirm they actually exited, killing any that ignore the request.
+        let mut survivors = Vec::new();
+        for pid in self.external {
+            let stopped = wait_for_exit(pid, STOP_CONFIRM_TIMEOUT) || {
+                warn!("{} (external, PID {}) didn't exit, killing it", name, pid);
+                terminate_external(pid, true);
+                wait_for_exit(pid, KILL_CONFIRM_TIMEOUT)
+            };
+            if stopped {
+                info!("{} (external, PID {}) stopped", name, pid);
+            } else {
+                warn!("{} (external, PID {}) survived being killed", name, pid);
+                survivors.push(pid);
+            }
+        }
+
+        StopOutcome { tool: self.tool, survivors }
+    }
+
+    /// `wait` for several stops at once, each on its own thread, so slow
+    /// tools don't add up
+    pub fn wait_all(stops: Vec<PendingStop>) -> Vec<StopOutcome> {
+        std::thread::scope(|scope| {
+            let waits: Vec<_> = stops
+                .into_iter()
+                .map(|stop| scope.spawn(move || stop.wait()))
+                .collect();
+            waits
+                .into_iter()
+                .map(|wait| wait.join().expect("stop wait panicked"))
+                .collect()
+        })
+    }
+}
+
+/// A tool the hub starts and stops, built-in or third-party (see
+/// `ProcessManager::poll_starting` and `ProcessManager::begin_stopping`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManagedTool {
+    Builtin(ToolId),
+    /// Third-party tool, by descriptor ID
+    Plugin(String),
+}
+
+impl ManagedTool {
+    /// ID the frontend knows the tool by
+    pub fn id(&self) -> &str {
+        match self {
+            ManagedTool::Builtin(tool_id) => tool_id.as_str(),
+            ManagedTool::Plugin(id) => id,
+        }
+    }
+
+    /// Name to log the tool under
+    pub fn name(&self) -> &str {
+        match self {
+            ManagedTool::Builtin(tool_id) => tool_id.display_name(),
+            ManagedTool::Plugin(id) => id,
+        }
+    }
+}
+
+/// A tool binary to start (see `ProcessManager::launch`)
+struct LaunchSpec<'a> {
+    display_name: &'a str,
+    /// ID the tool sends heartbeats under
+    heartbeat_id: &'a str,
+    binary_path: PathBuf,
+    api_key: Option<String>,
+    tool_config: &'a ToolConfig,
+    /// Long-lived GUI tool: keeps its console window and its stderr isn't
+    /// captured
+    gui: bool,
+}
+
+/// A process started by `ProcessManager::launch`
+struct Launched {
+    child: Child,
+    #[cfg(windows)]
+    job: Option<ProcessJob>,
+}
+
+/// Who started a running tool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ToolOrigin {
+    /// Started by this hub, which manages it
+    Spawned,
+    /// Started outside the hub (by the user or another hub) and detected by a
+    /// scan; stopping it ends a process the user may have started themselves
+    External,
+}
+
+/// Changes found by a full scan for externally started tools
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ScanDiff {
+    /// Tools newly found running outside the hub
+    pub detected: Vec<ToolId>,
+    /// Externally started tools that are no longer running
+    pub disappeared: Vec<ToolId>,
+}
+
+impl ScanDiff {
+    pub fn is_empty(&self) -> bool {
+        self.detected.is_empty() && self.disappeared.is_empty()
+    }
 }
 
 impl ProcessManager {
@@ -23,6 +381,55 @@ impl ProcessManager {
         Self {
             spawned_processes: HashMap::new(),
             external_pids: HashMap::new(),
+            starting: HashSet::new(),
+            stopping: HashSet::new(),
+            tool_directory: None,
+            versions: HashMap::new(),
+            plugin_processes: HashMap::new(),
+            starting_plugins: HashSet::new(),
+            stopping_plugins: HashSet::new(),
+            stderr_tails: HashMap::new(),
+            last_exits: HashMap::new(),
+            #[cfg(windows)]
+            jobs: HashMap::new(),
+            #[cfg(windows)]
+            plugin_jobs: HashMap::new(),
+            stop_on_exit: false,
+            api_provider: ApiProvider::OpenAi,
+            api_base_url: None,
+        }
+    }
+
+    /// Set the user-configured directory to search first for tool binaries
+    pub fn set_tool_directory(&mut self, dir: Option<PathBuf>) {
+        if self.tool_directory != dir {
+            // Binaries may resolve differently now
+            self.versions.clear();
+        }
+        self.tool_directory = dir;
+    }
+    
+    /// Whether spawned tools are stopped along with the hub
+    pub fn set_stop_on_exit(&mut self, stop: bool) {
+        self.stop_on_exit = stop;
+    }
+    
+    /// Set the API service passed to tools that need the API key. Applies to
+    /// tools started from now on.
+    pub fn set_api_endpoint(&mut self, provider: ApiProvider, base_url: Option<String>) {
+        self.api_provider = provider;
+        self.api_base_url = base_url;
+    }
+
+    /// Pass the API key, and the endpoint if one is set, via environment
+    /// variables
+    fn add_api_env(&self, cmd: &mut Command, api_key: String) {
+        if self.api_provider == ApiProvider::Azure {
+            cmd.env("AZURE_OPENAI_API_KEY", &api_key);
+        }
+        cmd.env("OPENAI_API_KEY", api_key);
+        if let Some(ref url) = self.api_base_url {
+            cmd.env(self.api_provider.base_url_env(), url);
         }
     }
     
@@ -32,72 +439,162 @@ impl ProcessManager {
     }
 
     /// Start a tool process with optional configuration
-    pub fn start_tool(&mut self, tool_id: &ToolId) -> Result<()> {
+    pub fn start_tool(&mut self, tool_id: &ToolId) -> Result<(), ToolStartError> {
         self.start_tool_with_config(tool_id, &ToolConfig::default())
     }
 
-    /// Start a tool process with specific configuration
-    pub fn start_tool_with_config(&mut self, tool_id: &ToolId, tool_config: &ToolConfig) -> Result<()> {
+    /// Start a tool process with specific configuration, blocking until the
+    /// startup health check completes
+    pub fn start_tool_with_config(
+        &mut self,
+        tool_id: &ToolId,
+        tool_config: &ToolConfig,
+    ) -> Result<(), ToolStartError> {
+        if !self.spawn_tool(tool_id, tool_config)? {
+            return Ok(());
+        }
+
+        match watch_startup(tool_config.startup_grace(), || self.poll_startup(tool_id)) {
+            StartupCheck::Pending => {
+                self.finish_startup(tool_id);
+                Ok(())
+            }
+            StartupCheck::Failed(error) => Err(error),
+            StartupCheck::Cancelled => Ok(()),
+        }
+    }
+
+    /// Spawn a tool process without waiting for the startup health check.
+    ///
+    /// Returns `Ok(false)` if the tool is already running. Otherwise the tool is
+    /// left in the `Starting` state until `poll_startup` reports a failure or
+    /// `finish_startup` is called once the grace window has passed.
+    pub fn spawn_tool(
+        &mut self,
+        tool_id: &ToolId,
+        tool_config: &ToolConfig,
+    ) -> Result<bool, ToolStartError> {
+        if !tool_config.enabled {
+            return Err(ToolStartError::Disabled {
+                tool: tool_id.display_name().to_string(),
+            });
+        }
+
+        // The old process may still be exiting
+        if self.stopping.contains(tool_id) {
+            return Err(ToolStartError::SpawnFailed {
+                message: format!(
+                    "{} is still stopping, try again in a moment",
+                    tool_id.display_name()
+                ),
+            });
+        }
+
         // Check if already running (spawned by us)
         if let Some(child) = self.spawned_processes.get_mut(tool_id) {
             match child.try_wait() {
                 Ok(Some(_)) => {
                     // Process exited, we can restart
                     self.spawned_processes.remove(tool_id);
+                    self.starting.remove(tool_id);
                 }
                 Ok(None) => {
                     // Still running
-                    return Ok(());
+                    return Ok(false);
                 }
                 Err(_) => {
                     // Error checking, remove and try to restart
                     self.spawned_processes.remove(tool_id);
+                    self.starting.remove(tool_id);
                 }
             }
         }
         
         // Check if running externally
-        if let Some(pid) = self.external_pids.get(tool_id) {
-            if is_process_running(*pid) {
-                return Ok(()); // Already running externally
-            } else {
-                self.external_pids.remove(tool_id);
+        if let Some(pids) = self.external_pids.get_mut(tool_id) {
+            pids.retain(|&pid| is_process_running(pid));
+            if !pids.is_empty() {
+                return Ok(false); // Already running externally
             }
+            self.external_pids.remove(tool_id);
         }
 
+        // Checked only now, so tools already running (including ones started
+        // outside the hub) are left alone
+        check_license(tool_id.display_name()).map_err(not_authorized(tool_id.display_name()))?;
+
+        // Tools that need the OpenAI key just crash with a cryptic error without it,
+        // so fail fast with a typed error the UI can turn into the API-key dialog
+        let api_key = if tool_id.requires_api_key() {
+            let key = config::load_api_key().map_err(|_| ToolStartError::MissingApiKey {
+                tool: tool_id.display_name().to_string(),
+            })?;
+            Some(key)
+        } else {
+            None
+        };
+
         // Find the binary
-        let binary_path = self
-            .find_binary(tool_id)
-            .context(format!("Could not find binary for {}", tool_id.display_name()))?;
+        let binary_path = self.find_binary(tool_id).map_err(|searched| {
+            warn!("{} not found, searched: {:#?}", tool_id.display_name(), searched);
+            ToolStartError::BinaryNotFound {
+                tool: tool_id.display_name().to_string(),
+                searched,
+            }
+        })?;
+
+        let launch = LaunchSpec {
+            display_name: tool_id.display_name(),
+            heartbeat_id: tool_id.as_str(),
+            binary_path,
+            api_key,
+            tool_config,
+            gui: tool_id.is_gui(),
+        };
+        // Add command-line arguments based on tool type (hotkey, voice, etc.)
+        let launched = self.launch(launch, |cmd| self.add_tool_args(cmd, tool_id, tool_config))?;
 
-        println!("Starting {} from {:?}", tool_id.display_name(), binary_path);
+        self.last_exits.remove(tool_id);
+        #[cfg(windows)]
+        if let Some(job) = launched.job {
+            self.jobs.insert(tool_id.clone(), job);
+        }
+        self.spawned_processes.insert(tool_id.clone(), launched.child);
+        self.starting.insert(tool_id.clone());
+
+        Ok(true)
+    }
+
+    /// Spawn a tool binary with the API key and the tool's `env`, its args
+    /// (`add_args`, then `extra_args`), and its own process group (a Job
+    /// Object on Windows) so stopping it reaches any helpers it starts.
+    /// Shared by built-in and third-party tools.
+    fn launch(
+        &self,
+        spec: LaunchSpec<'_>,
+        add_args: impl FnOnce(&mut Command),
+    ) -> Result<Launched, ToolStartError> {
+        info!("Starting {} from {:?}", spec.display_name, spec.binary_path);
 
         // Set up the command
-        let mut cmd = Command::new(&binary_path);
+        let mut cmd = Command::new(&spec.binary_path);
 
-        // Pass the API key via environment variable if available
-        if tool_id.requires_api_key() {
-            if let Ok(api_key) = config::load_api_key() {
-                cmd.env("OPENAI_API_KEY", api_key);
-            }
+        // Pass the API key via environment variable
+        if let Some(api_key) = spec.api_key {
+            self.add_api_env(&mut cmd, api_key);
         }
+        add_tool_env(&mut cmd, spec.display_name, spec.tool_config);
 
-        // Add command-line arguments based on tool type (hotkey, voice, etc.)
-        self.add_tool_args(&mut cmd, tool_id, tool_config);
+        add_args(&mut cmd);
+        add_extra_args(&mut cmd, spec.display_name, spec.tool_config);
 
-        // Hide console window for CLI tools on Windows
+        // Hide console window for CLI tools on Windows (GUI tools manage their
+        // own windows)
         #[cfg(windows)]
-        {
+        if !spec.gui {
             // CREATE_NO_WINDOW = 0x08000000
             const CREATE_NO_WINDOW: u32 = 0x08000000;
-            
-            // Only hide window for CLI tools (GUI tools manage their own windows)
-            match tool_id {
-                ToolId::SpeakSelected | ToolId::QuickAssistant | ToolId::FlattenString | ToolId::OcrPaste => {
-                    cmd.creation_flags(CREATE_NO_WINDOW);
-                }
-                _ => {}
-            }
+            cmd.creation_flags(CREATE_NO_WINDOW);
         }
 
         cmd.stdin(Stdio::null());
@@ -105,52 +602,82 @@ impl ProcessManager {
         // GUI tools (DeskTalk, TypoFix) live long; piping stderr then dropping
         // the read end creates a broken pipe that panics on any eprintln!().
         // Only pipe stderr for CLI tools where we want early-exit error messages.
-        match tool_id {
-            ToolId::DeskTalk | ToolId::TypoFix => {
-                cmd.stderr(Stdio::null());
-            }
-            _ => {
-                cmd.stderr(Stdio::piped());
-            }
+        if spec.gui {
+            cmd.stderr(Stdio::null());
+        } else {
+            cmd.stderr(Stdio::piped());
         }
 
+        // Own process group, so stopping the tool reaches any helpers it starts
+        #[cfg(not(windows))]
+        cmd.process_group(0);
+
         // Start the process
-        let mut child = cmd
-            .spawn()
-            .context(format!("Failed to spawn {}", tool_id.display_name()))?;
+        let child = cmd.spawn().map_err(|e| ToolStartError::SpawnFailed {
+            message: format!("Failed to spawn {}: {}", spec.display_name, e),
+        })?;
 
-        // Wait briefly to check if the process exits immediately with an error
-        std::thread::sleep(std::time::Duration::from_millis(500));
-        
-        match child.try_wait() {
-            Ok(Some(exit_status)) => {
-                // Process exited immediately - this is likely an error
-                let mut stderr_output = String::new();
-                if let Some(mut stderr) = child.stderr.take() {
-                    use std::io::Read;
-                    let _ = stderr.read_to_string(&mut stderr_output);
-                }
-                
-                let error_msg = if !stderr_output.is_empty() {
-                    stderr_output.lines().take(5).collect::<Vec<_>>().join("\n")
-                } else {
-                    format!("Process exited with code {:?}", exit_status.code())
-                };
-                
-                return Err(anyhow::anyhow!("{}", error_msg));
-            }
-            Ok(None) => {
-                // Process is still running - good! Drop stderr handle so it doesn't block
-                drop(child.stderr.take());
-            }
-            Err(e) => {
-                return Err(anyhow::anyhow!("Failed to check process status: {}", e));
-            }
+        heartbeat::clear(spec.heartbeat_id);
+        #[cfg(windows)]
+        let job = ProcessJob::assign(&child);
+        #[cfg(windows)]
+        if job.is_none() {
+            warn!("Could not put {} in a job object", spec.display_name);
         }
 
-        self.spawned_processes.insert(tool_id.clone(), child);
+        Ok(Launched {
+            child,
+            #[cfg(windows)]
+            job,
+        })
+    }
+
+    /// Check whether a starting tool has exited early.
+    ///
+    /// A tool that exited is removed from tracking and its captured stderr is
+    /// returned as the failure message.
+    pub fn poll_startup(&mut self, tool_id: &ToolId) -> StartupCheck {
+        if !self.starting.contains(tool_id) {
+            return StartupCheck::Cancelled;
+        }
 
-        Ok(())
+        let exit = match self.spawned_processes.get_mut(tool_id) {
+            Some(child) => match child.try_wait() {
+                Ok(None) => return StartupCheck::Pending,
+                Ok(Some(exit_status)) => Ok(exit_status),
+                Err(e) => Err(e),
+            },
+            None => {
+                self.starting.remove(tool_id);
+                return StartupCheck::Cancelled;
+            }
+        };
+
+        self.starting.remove(tool_id);
+        #[cfg(windows)]
+        self.jobs.remove(tool_id);
+        let Some(mut child) = self.spawned_processes.remove(tool_id) else {
+            return StartupCheck::Cancelled;
+        };
+
+        let error = startup_failure(&mut child, exit);
+        if let ToolStartError::ImmediateExit { code, ref stderr } = error {
+            self.last_exits.insert(tool_id.clone(), ExitRecord::now(code, stderr.clone()));
+        }
+        StartupCheck::Failed(error)
+    }
+
+    /// Mark a starting tool as healthy once its grace window has passed
+    pub fn finish_startup(&mut self, tool_id: &ToolId) {
+        if self.starting.remove(tool_id) {
+            if let Some(child) = self.spawned_processes.get_mut(tool_id) {
+                // Process is still running - good! Keep draining stderr so it
+                // never blocks on a full pipe, remembering the tail for a crash
+                if let Some(stderr) = child.stderr.take() {
+                    self.stderr_tails.insert(tool_id.clone(), drain_stderr(stderr));
+                }
+            }
+        }
     }
 
     /// Add command-line arguments based on tool type
@@ -160,7 +687,7 @@ impl ProcessManager {
             if let Ok(parallel) = crate::tauri_commands::get_desktalk_parallel_value() {
                 if parallel > 1 {
                     cmd.arg("--parallel").arg(parallel.to_string());
-                    println!("  Passing --parallel {} to DeskTalk", parallel);
+                    info!("Passing --parallel {} to DeskTalk", parallel);
                 }
             }
             return;
@@ -172,182 +699,297 @@ impl ProcessManager {
         }
 
         // For CLI tools, pass the hotkey as an argument
+        let special_key_arg = match tool_id {
+            ToolId::SpeakSelected | ToolId::QuickAssistant => Some("--special-ptt-key"),
+            // flatten-string and ocr-paste don't support special keys
+            _ => None,
+        };
         if let Some(ref hotkey) = tool_config.hotkey {
-            let arg_name = match tool_id {
-                ToolId::SpeakSelected => "--ptt-key",
-                ToolId::QuickAssistant => "--ptt-key",
-                ToolId::FlattenString => "--trigger-key",
-                ToolId::OcrPaste => "--trigger-key",
-                _ => return,
-            };
-            cmd.arg(arg_name).arg(hotkey);
-            println!("  Passing hotkey: {} {}", arg_name, hotkey);
+            if let Some(arg_name) = tool_id.hotkey_arg() {
+                cmd.arg(arg_name).arg(hotkey);
+                info!("Passing hotkey: {} {}", arg_name, hotkey);
+            }
+            add_modifier_args(cmd, tool_id, &tool_config.modifiers);
         } else if let Some(special_key) = tool_config.special_hotkey {
-            let arg_name = match tool_id {
-                ToolId::SpeakSelected => "--special-ptt-key",
-                ToolId::QuickAssistant => "--special-ptt-key",
-                // flatten-string and ocr-paste don't support special keys
-                _ => return,
-            };
-            cmd.arg(arg_name).arg(special_key.to_string());
-            println!("  Passing special hotkey: {} {}", arg_name, special_key);
+            if let Some(arg_name) = special_key_arg {
+                cmd.arg(arg_name).arg(special_key.to_string());
+                info!("Passing special hotkey: {} {}", arg_name, special_key);
+            }
         } else {
-            println!("  Warning: No hotkey configured for {}", tool_id.display_name());
+            warn!("No hotkey configured for {}", tool_id.display_name());
         }
 
-        // Pass voice for TTS tools
-        if let Some(ref voice) = tool_config.voice {
-            let arg_name = match tool_id {
-                ToolId::SpeakSelected => "--voice",
-                ToolId::QuickAssistant => "--ai-voice",
-                _ => return,
-            };
-            cmd.arg(arg_name).arg(voice);
-            println!("  Passing voice: {} {}", arg_name, voice);
+        // Pass voice and speech speed for TTS tools. Configs saved before these
+        // were validated may hold bad values; the tool's default beats a crash.
+        if let (Some(voice), Some(arg_name)) = (&tool_config.voice, tool_id.voice_arg()) {
+            if TTS_VOICES.contains(&voice.as_str()) {
+                cmd.arg(arg_name).arg(voice);
+                info!("Passing voice: {} {}", arg_name, voice);
+            } else {
+                warn!("Not passing unknown voice {:?} to {}", voice, tool_id.display_name());
+            }
         }
 
-        // Pass speech speed for TTS tools
-        if let Some(speed) = tool_config.speech_speed {
-            match tool_id {
-                ToolId::SpeakSelected | ToolId::QuickAssistant => {
-                    cmd.arg("--speech-speed").arg(speed.to_string());
-                    println!("  Passing speech speed: {}", speed);
-                }
-                _ => {}
+        let speed_arg = tool_id.speech_speed_arg();
+        if let (Some(speed), Some(arg_name)) = (tool_config.speech_speed, speed_arg) {
+            if (MIN_SPEECH_SPEED..=MAX_SPEECH_SPEED).contains(&speed) {
+                cmd.arg(arg_name).arg(speed.to_string());
+                info!("Passing speech speed: {} {}", arg_name, speed);
+            } else {
+                warn!(
+                    "Not passing out-of-range speech speed {} to {}",
+                    speed,
+                    tool_id.display_name()
+                );
             }
         }
     }
 
-    /// Stop a tool process (whether spawned by us or running externally)
-    pub fn stop_tool(&mut self, tool_id: &ToolId) -> Result<()> {
-        println!("Stopping {}...", tool_id.display_name());
+    /// A tool's cached version (see `record_version`): `None` if it hasn't been
+    /// queried yet, `Some(None)` if its binary doesn't report one
+    pub fn cached_version(&self, tool_id: &ToolId) -> Option<Option<ToolVersion>> {
+        self.versions.get(tool_id).copied()
+    }
 
-        // First try to stop a process we spawned
-        if let Some(mut child) = self.spawned_processes.remove(tool_id) {
-            let pid = child.id();
-            
-            // Try graceful termination first
-            #[cfg(windows)]
-            {
-                let _ = Command::new("taskkill")
-                    .args(["/PID", &pid.to_string()])
-                    .creation_flags(0x08000000) // CREATE_NO_WINDOW
-                    .output();
-            }
+    /// Where a tool's binary is, if it can be found
+    pub fn binary_path(&self, tool_id: &ToolId) -> Option<PathBuf> {
+        self.find_binary(tool_id).ok()
+    }
 
-            std::thread::sleep(std::time::Duration::from_millis(500));
+    /// Cache a version read with `read_binary_version`
+    pub fn record_version(&mut self, tool_id: &ToolId, version: Option<ToolVersion>) {
+        self.versions.insert(tool_id.clone(), version);
+    }
 
-            match child.try_wait() {
-                Ok(Some(_)) => {
-                    println!("{} stopped gracefully", tool_id.display_name());
-                }
-                _ => {
-                    let _ = child.kill();
-                    let _ = child.wait();
-                    println!("{} force killed", tool_id.display_name());
-                }
-            }
-            return Ok(());
+    /// Stop a tool process (whether spawned by us or running externally) and
+    /// confirm it's gone. Fails if an external instance survives being killed
+    /// (e.g. one running elevated); it then stays tracked as running.
+    ///
+    /// Waits for the processes to exit; callers sharing the manager behind a
+    /// lock should use `begin_stop`, `PendingStop::wait` and `finish_stop` so
+    /// the lock isn't held meanwhile.
+    pub fn stop_tool(&mut self, tool_id: &ToolId) -> Result<()> {
+        let outcome = self.begin_stop(tool_id).wait();
+        self.finish_stop(outcome)
+    }
+
+    /// Ask a tool's processes to exit and stop tracking them as running. The
+    /// returned stop is completed with `PendingStop::wait`, then reported back
+    /// with `finish_stop`; the tool can't be started until then.
+    pub fn begin_stop(&mut self, tool_id: &ToolId) -> PendingStop {
+        info!("Stopping {}...", tool_id.display_name());
+        self.starting.remove(tool_id);
+        self.stderr_tails.remove(tool_id);
+        self.stopping.insert(tool_id.clone());
+
+        // Try graceful termination first, on the whole tree so helper
+        // processes the tool started don't outlive it
+        let spawned = self.spawned_processes.remove(tool_id);
+        if let Some(ref child) = spawned {
+            terminate_tree(child.id(), false);
+        }
+        let external = self.external_pids.remove(tool_id).unwrap_or_default();
+        for &pid in &external {
+            terminate_external(pid, false);
         }
 
-        // Try to stop an externally-started process
-        if let Some(pid) = self.external_pids.remove(tool_id) {
+        PendingStop {
+            tool: ManagedTool::Builtin(tool_id.clone()),
+            spawned,
             #[cfg(windows)]
-            {
-                let _ = Command::new("taskkill")
-                    .args(["/PID", &pid.to_string(), "/F"])
-                    .creation_flags(0x08000000) // CREATE_NO_WINDOW
-                    .output();
+            job: self.jobs.remove(tool_id),
+            external,
+        }
+    }
+
+    /// Record the outcome of a stop started with `begin_stop` (or
+    /// `begin_plugin_stop`). External instances that survived stay tracked as
+    /// running, and are reported as the error.
+    pub fn finish_stop(&mut self, outcome: StopOutcome) -> Result<()> {
+        let StopOutcome { tool, survivors } = outcome;
+        let tool_id = match tool {
+            ManagedTool::Builtin(tool_id) => tool_id,
+            ManagedTool::Plugin(id) => {
+                self.stopping_plugins.remove(&id);
+                return Ok(());
             }
-            println!("{} (external, PID {}) stopped", tool_id.display_name(), pid);
+        };
+        self.stopping.remove(&tool_id);
+        if survivors.is_empty() {
             return Ok(());
         }
 
-        // Not running
-        Ok(())
+        let pids = survivors.iter().map(u32::to_string).collect::<Vec<_>>().join(", ");
+        self.external_pids.entry(tool_id.clone()).or_default().extend(survivors);
+        anyhow::bail!(
+            "{} (PID {}) is still running after being stopped. It may be running as \
+             administrator; stop it from there.",
+            tool_id.display_name(),
+            pids
+        );
     }
 
     /// Get the status of a tool
     pub fn get_status(&self, tool_id: &ToolId) -> ToolStatus {
-        // Check spawned processes
-        if self.spawned_processes.contains_key(tool_id) {
-            return ToolStatus::Running;
+        if self.starting.contains(tool_id) {
+            return ToolStatus::Starting;
         }
-        
-        // Check external processes
-        if self.external_pids.contains_key(tool_id) {
-            return ToolStatus::Running;
+
+        // Check spawned, then external processes
+        if self.spawned_processes.contains_key(tool_id)
+            || self.external_pids.contains_key(tool_id)
+        {
+            return running_status(tool_id.as_str());
         }
         
         ToolStatus::Stopped
     }
 
+    /// Who started the tool, if it's running. A tool the hub started that was
+    /// also started externally counts as `Spawned`.
+    pub fn origin(&self, tool_id: &ToolId) -> Option<ToolOrigin> {
+        if self.spawned_processes.contains_key(tool_id) {
+            Some(ToolOrigin::Spawned)
+        } else if self.external_pids.contains_key(tool_id) {
+            Some(ToolOrigin::External)
+        } else {
+            None
+        }
+    }
+
+    /// How a tool last exited on its own since it was last started, if it did
+    pub fn last_exit(&self, tool_id: &ToolId) -> Option<&ExitRecord> {
+        self.last_exits.get(tool_id)
+    }
+
+    /// Number of known running instances of a tool (spawned plus external).
+    ///
+    /// More than one means the tool was started again outside the hub and
+    /// both copies are reacting to the same hotkey.
+    pub fn instance_count(&self, tool_id: &ToolId) -> usize {
+        let spawned = usize::from(self.spawned_processes.contains_key(tool_id));
+        let external = self.external_pids.get(tool_id).map_or(0, Vec::len);
+        spawned + external
+    }
+
+    /// Get the tools the hub started that are currently running or starting.
+    /// Externally started ones are left out: restoring those would start a
+    /// duplicate next to the instance already running.
+    pub fn running_tools(&self) -> Vec<ToolId> {
+        ToolId::all()
+            .iter()
+            .filter(|id| self.origin(id) == Some(ToolOrigin::Spawned))
+            .filter(|id| self.get_status(id) != ToolStatus::Stopped)
+            .cloned()
+            .collect()
+    }
+
+    /// Combine every tool's status (third-party tools included). Any error
+    /// wins; otherwise anything running or starting counts as running.
+    pub fn aggregate_status(&self) -> HubRunState {
+        let statuses = ToolId::all()
+            .iter()
+            .map(|id| self.get_status(id))
+            .chain(self.plugin_processes.keys().map(|id| self.plugin_status(id)));
+
+        let mut run_state = HubRunState::AllStopped;
+        for status in statuses {
+            match status {
+                ToolStatus::Error(_) => return HubRunState::ErrorPresent,
+                ToolStatus::Running | ToolStatus::Starting => run_state = HubRunState::SomeRunning,
+                ToolStatus::Stopped => {}
+            }
+        }
+        run_state
+    }
+
     /// Update statuses by checking if processes are still running
     /// This is called frequently, so it must be FAST - no system calls for external processes
     pub fn refresh_statuses(&mut self) {
         // Check spawned processes - this is fast (just try_wait)
-        let mut exited_spawned = Vec::new();
-        for (tool_id, child) in self.spawned_processes.iter_mut() {
-            match child.try_wait() {
-                Ok(Some(_)) => {
-                    exited_spawned.push(tool_id.clone());
-                }
-                Ok(None) => {
-                    // Still running
-                }
-                Err(_) => {
-                    exited_spawned.push(tool_id.clone());
-                }
-            }
-        }
-        for tool_id in exited_spawned {
-            self.spawned_processes.remove(&tool_id);
+        let spawned: Vec<ToolId> = self.spawned_processes.keys().cloned().collect();
+        for tool_id in &spawned {
+            self.refresh_status(tool_id);
         }
+        // Startup failures are reported by whoever is watching the start
+        let starting = &self.starting_plugins;
+        self.plugin_processes
+            .retain(|id, child| starting.contains(id) || matches!(child.try_wait(), Ok(None)));
+        #[cfg(windows)]
+        self.plugin_jobs.retain(|id, _| self.plugin_processes.contains_key(id));
         
         // For external processes, we just trust they're still running
         // They'll be removed when we try to stop them or on next full scan
         // This avoids expensive tasklist calls every 2 seconds
     }
-    
-    /// Full scan for external processes (expensive - only call occasionally)
-    pub fn full_scan(&mut self) {
-        let running = get_all_running_processes();
-        
-        // Check external processes
-        let mut exited_external = Vec::new();
-        for (tool_id, pid) in self.external_pids.iter() {
-            let exe_name = if cfg!(windows) {
-                format!("{}.exe", tool_id.binary_name())
-            } else {
-                tool_id.binary_name().to_string()
-            };
-            
-            let still_running = running.get(&exe_name.to_lowercase())
-                .map(|&p| p == *pid)
-                .unwrap_or(false);
-            
-            if !still_running {
-                exited_external.push(tool_id.clone());
-            }
+
+    /// `refresh_statuses` for a single built-in tool
+    pub fn refresh_status(&mut self, tool_id: &ToolId) {
+        // Startup failures are reported by whoever is watching the start
+        if self.starting.contains(tool_id) {
+            return;
+        }
+        let Some(child) = self.spawned_processes.get_mut(tool_id) else {
+            return;
+        };
+        let code = match child.try_wait() {
+            Ok(Some(exit_status)) => exit_status.code(),
+            // Still running
+            Ok(None) => return,
+            Err(_) => None,
+        };
+
+        self.spawned_processes.remove(tool_id);
+        #[cfg(windows)]
+        self.jobs.remove(tool_id);
+        let stderr = self
+            .stderr_tails
+            .remove(tool_id)
+            .map(|tail| Vec::from(tail.lock().clone()).join("\n"))
+            .unwrap_or_default();
+        warn!("{} exited (code {:?})", tool_id.display_name(), code);
+        self.last_exits.insert(tool_id.clone(), ExitRecord::now(code, stderr));
+    }
+
+    /// `refresh_statuses` for a single third-party tool
+    pub fn refresh_plugin_status(&mut self, id: &str) {
+        if self.starting_plugins.contains(id) {
+            return;
         }
-        for tool_id in exited_external {
-            self.external_pids.remove(&tool_id);
+        let exited = self
+            .plugin_processes
+            .get_mut(id)
+            .is_some_and(|child| !matches!(child.try_wait(), Ok(None)));
+        if exited {
+            self.plugin_processes.remove(id);
+            #[cfg(windows)]
+            self.plugin_jobs.remove(id);
         }
+    }
+    
+    /// Full scan for external processes (expensive - only call occasionally).
+    ///
+    /// Returns the tools whose external instances appeared or went away.
+    pub fn full_scan(&mut self) -> ScanDiff {
+        let running = get_all_running_processes();
+        let mut diff = ScanDiff::default();
 
-        // Detect newly-started external processes
         for tool_id in ToolId::all() {
-            if self.spawned_processes.contains_key(tool_id) || self.external_pids.contains_key(tool_id) {
-                continue;
-            }
-            let exe_name = if cfg!(windows) {
-                format!("{}.exe", tool_id.binary_name())
+            let pids = self.external_instances(&running, tool_id);
+            let was_running = if pids.is_empty() {
+                self.external_pids.remove(tool_id).is_some()
             } else {
-                tool_id.binary_name().to_string()
+                self.external_pids.insert(tool_id.clone(), pids).is_some()
             };
-            if let Some(&pid) = running.get(&exe_name.to_lowercase()) {
-                self.external_pids.insert(tool_id.clone(), pid);
+
+            match (was_running, self.external_pids.contains_key(tool_id)) {
+                (false, true) => diff.detected.push(tool_id.clone()),
+                (true, false) => diff.disappeared.push(tool_id.clone()),
+                _ => {}
             }
         }
+
+        diff
     }
     
     /// Detect tools that are already running (started outside the hub)
@@ -357,88 +999,271 @@ impl ProcessManager {
         
         for tool_id in ToolId::all() {
             // Skip if we already know about this tool
-            if self.spawned_processes.contains_key(tool_id) || self.external_pids.contains_key(tool_id) {
+            if self.external_pids.contains_key(tool_id) {
                 continue;
             }
-            
-            // Check if this tool is running
-            let exe_name = if cfg!(windows) {
-                format!("{}.exe", tool_id.binary_name())
-            } else {
-                tool_id.binary_name().to_string()
-            };
-            
-            if let Some(&pid) = running.get(&exe_name.to_lowercase()) {
-                println!("Detected already-running {}: PID {}", tool_id.display_name(), pid);
-                self.external_pids.insert(tool_id.clone(), pid);
+
+            let pids = self.external_instances(&running, tool_id);
+            if !pids.is_empty() {
+                info!("Detected already-running {}: PID(s) {:?}", tool_id.display_name(), pids);
+                self.external_pids.insert(tool_id.clone(), pids);
             }
         }
     }
 
-    /// Find the binary path for a tool
-    fn find_binary(&self, tool_id: &ToolId) -> Option<PathBuf> {
-        let binary_name = if cfg!(windows) {
+    /// PIDs of a tool's processes that the hub didn't spawn itself
+    fn external_instances(
+        &self,
+        running: &HashMap<String, Vec<u32>>,
+        tool_id: &ToolId,
+    ) -> Vec<u32> {
+        let exe_name = if cfg!(windows) {
             format!("{}.exe", tool_id.binary_name())
         } else {
             tool_id.binary_name().to_string()
         };
+        let spawned_pid = self.spawned_processes.get(tool_id).map(Child::id);
 
-        // Try to find relative to current executable (production layout)
-        if let Ok(exe_path) = std::env::current_exe() {
-            if let Some(exe_dir) = exe_path.parent() {
-                // Same directory as hub (for portable/dev installs)
-                let path = exe_dir.join(&binary_name);
-                if path.exists() {
-                    return Some(path);
-                }
+        running
+            .get(&exe_name.to_lowercase())
+            .map(|pids| {
+                pids.iter()
+                    .copied()
+                    .filter(|&pid| Some(pid) != spawned_pid)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 
-                // In a 'tools' subdirectory (bundled install)
-                let path = exe_dir.join("tools").join(&binary_name);
-                if path.exists() {
-                    return Some(path);
-                }
+    /// Find the binary path for a tool
+    fn find_binary(&self, tool_id: &ToolId) -> Result<PathBuf, Vec<PathBuf>> {
+        self.search_binary(tool_id.binary_name(), Some(tool_id.folder_name()))
+    }
 
-                // Check Tauri resource path
-                // In bundled apps, resources are in: <exe_dir>/resources/tools/
-                let path = exe_dir.join("resources").join("tools").join(&binary_name);
-                if path.exists() {
-                    return Some(path);
-                }
+    /// Search the tool directory, install layout and dev build directories for
+    /// a binary. `folder` is the tool's submodule folder under `tools/`, if any.
+    /// On failure, returns the paths that were checked.
+    fn search_binary(
+        &self,
+        binary_name: &str,
+        folder: Option<&str>,
+    ) -> Result<PathBuf, Vec<PathBuf>> {
+        SearchRoots::current(self.tool_directory.clone()).find(binary_name, folder)
+    }
+
+    // === Third-party tools ===
+
+    /// Spawn a third-party tool described by a `tools.d` descriptor without
+    /// waiting for the startup health check, the same way as `spawn_tool`
+    /// (see `poll_plugin_startup` and `finish_plugin_startup`).
+    ///
+    /// Returns `Ok(false)` if the tool is already running.
+    pub fn spawn_plugin(
+        &mut self,
+        descriptor: &ToolDescriptor,
+        tool_config: &ToolConfig,
+    ) -> Result<bool, ToolStartError> {
+        if !tool_config.enabled {
+            return Err(ToolStartError::Disabled {
+                tool: descriptor.display_name.clone(),
+            });
+        }
+        // Alive, even if not responding - starting again would make a duplicate
+        if self.plugin_processes.contains_key(&descriptor.id) {
+            return Ok(false);
+        }
+        // The old process may still be exiting
+        if self.stopping_plugins.contains(&descriptor.id) {
+            return Err(ToolStartError::SpawnFailed {
+                message: format!(
+                    "{} is still stopping, try again in a moment",
+                    descriptor.display_name
+                ),
+            });
+        }
+        check_license(&descriptor.display_name).map_err(not_authorized(&descriptor.display_name))?;
+
+        let api_key = if descriptor.requires_api_key {
+            let key = config::load_api_key().map_err(|_| ToolStartError::MissingApiKey {
+                tool: descriptor.display_name.clone(),
+            })?;
+            Some(key)
+        } else {
+            None
+        };
+
+        // Plugins aren't in `tools/`, only the tool directory and install
+        // layout are searched
+        let binary_path = self.search_binary(&descriptor.binary_name, None).map_err(|searched| {
+            warn!("{} not found, searched: {:#?}", descriptor.display_name, searched);
+            ToolStartError::BinaryNotFound {
+                tool: descriptor.display_name.clone(),
+                searched,
+            }
+        })?;
+
+        let launch = LaunchSpec {
+            display_name: &descriptor.display_name,
+            heartbeat_id: &descriptor.id,
+            binary_path,
+            api_key,
+            tool_config,
+            gui: false,
+        };
+        let launched = self.launch(launch, |cmd| {
+            if let (Some(arg_name), Some(hotkey)) = (&descriptor.hotkey_arg, &tool_config.hotkey) {
+                cmd.arg(arg_name).arg(hotkey);
+                info!("Passing hotkey: {} {}", arg_name, hotkey);
+            }
+        })?;
+
+        #[cfg(windows)]
+        if let Some(job) = launched.job {
+            self.plugin_jobs.insert(descriptor.id.clone(), job);
+        }
+        self.plugin_processes.insert(descriptor.id.clone(), launched.child);
+        self.starting_plugins.insert(descriptor.id.clone());
+
+        Ok(true)
+    }
+
+    /// `poll_startup` for a third-party tool
+    pub fn poll_plugin_startup(&mut self, id: &str) -> StartupCheck {
+        if !self.starting_plugins.contains(id) {
+            return StartupCheck::Cancelled;
+        }
+
+        let exit = match self.plugin_processes.get_mut(id) {
+            Some(child) => match child.try_wait() {
+                Ok(None) => return StartupCheck::Pending,
+                Ok(Some(exit_status)) => Ok(exit_status),
+                Err(e) => Err(e),
+            },
+            None => {
+                self.starting_plugins.remove(id);
+                return StartupCheck::Cancelled;
             }
+        };
+
+        self.starting_plugins.remove(id);
+        #[cfg(windows)]
+        self.plugin_jobs.remove(id);
+        match self.plugin_processes.remove(id) {
+            Some(mut child) => StartupCheck::Failed(startup_failure(&mut child, exit)),
+            None => StartupCheck::Cancelled,
         }
+    }
 
-        // Try workspace target directories (for development)
-        if let Ok(cwd) = std::env::current_dir() {
-            let workspace_paths = [
-                // Workspace target directory (cargo builds all workspace members here)
-                cwd.join("target").join("release").join(&binary_name),
-                cwd.join("target").join("debug").join(&binary_name),
-                // From workspace root - submodule's own target
-                cwd.join("tools").join(tool_id_to_folder(tool_id)).join("target").join("release").join(&binary_name),
-                cwd.join("tools").join(tool_id_to_folder(tool_id)).join("target").join("debug").join(&binary_name),
-                // From crates/hub-dashboard (when running with cargo run)
-                cwd.join("..").join("..").join("target").join("release").join(&binary_name),
-                cwd.join("..").join("..").join("target").join("debug").join(&binary_name),
-                cwd.join("..").join("..").join("tools").join(tool_id_to_folder(tool_id)).join("target").join("release").join(&binary_name),
-                cwd.join("..").join("..").join("tools").join(tool_id_to_folder(tool_id)).join("target").join("debug").join(&binary_name),
-            ];
-
-            for path in &workspace_paths {
-                if path.exists() {
-                    return Some(path.canonicalize().unwrap_or(path.clone()));
+    /// `finish_startup` for a third-party tool
+    pub fn finish_plugin_startup(&mut self, id: &str) {
+        if self.starting_plugins.remove(id) {
+            if let Some(child) = self.plugin_processes.get_mut(id) {
+                // Keep draining stderr so it never blocks on a full pipe
+                if let Some(stderr) = child.stderr.take() {
+                    drain_stderr(stderr);
                 }
             }
         }
+    }
+
+    /// Poll a starting tool, built-in or third-party
+    pub fn poll_starting(&mut self, tool: &ManagedTool) -> StartupCheck {
+        match tool {
+            ManagedTool::Builtin(tool_id) => self.poll_startup(tool_id),
+            ManagedTool::Plugin(id) => self.poll_plugin_startup(id),
+        }
+    }
+
+    /// Finish the startup of a tool, built-in or third-party
+    pub fn finish_starting(&mut self, tool: &ManagedTool) {
+        match tool {
+            ManagedTool::Builtin(tool_id) => self.finish_startup(tool_id),
+            ManagedTool::Plugin(id) => self.finish_plugin_startup(id),
+        }
+    }
 
-        None
+    /// Stop a third-party tool we spawned, gracefully if it exits in time.
+    /// Like `stop_tool`, this waits for the process to exit.
+    pub fn stop_plugin(&mut self, id: &str) {
+        let outcome = self.begin_plugin_stop(id).wait();
+        let _ = self.finish_stop(outcome);
+    }
+
+    /// `begin_stop` for a third-party tool
+    pub fn begin_plugin_stop(&mut self, id: &str) -> PendingStop {
+        self.starting_plugins.remove(id);
+        let spawned = self.plugin_processes.remove(id);
+        if let Some(ref child) = spawned {
+            info!("Stopping plugin tool {}...", id);
+            self.stopping_plugins.insert(id.to_string());
+            terminate_tree(child.id(), false);
+        }
+
+        PendingStop {
+            tool: ManagedTool::Plugin(id.to_string()),
+            spawned,
+            #[cfg(windows)]
+            job: self.plugin_jobs.remove(id),
+            external: Vec::new(),
+        }
+    }
+
+    /// Begin stopping a tool, built-in or third-party
+    pub fn begin_stopping(&mut self, tool: &ManagedTool) -> PendingStop {
+        match tool {
+            ManagedTool::Builtin(tool_id) => self.begin_stop(tool_id),
+            ManagedTool::Plugin(id) => self.begin_plugin_stop(id),
+        }
+    }
+
+    /// Get the status of a third-party tool
+    pub fn plugin_status(&self, id: &str) -> ToolStatus {
+        if self.starting_plugins.contains(id) {
+            ToolStatus::Starting
+        } else if self.plugin_processes.contains_key(id) {
+            running_status(id)
+        } else {
+            ToolStatus::Stopped
+        }
+    }
+
+    /// Begin stopping every running tool, including externally started ones
+    /// if `include_external` is set (see `begin_stop`). Complete the stops
+    /// with `PendingStop::wait_all` and `finish_stops`.
+    pub fn begin_stop_all(&mut self, include_external: bool) -> Vec<PendingStop> {
+        let mut tool_ids: Vec<ToolId> = self.spawned_processes.keys().cloned().collect();
+        if include_external {
+            tool_ids.extend(
+                self.external_pids
+                    .keys()
+                    .filter(|id| !self.spawned_processes.contains_key(*id))
+                    .cloned(),
+            );
+        }
+        let plugin_ids: Vec<_> = self.plugin_processes.keys().cloned().collect();
+
+        let mut stops: Vec<_> = tool_ids.iter().map(|id| self.begin_stop(id)).collect();
+        stops.extend(plugin_ids.iter().map(|id| self.begin_plugin_stop(id)));
+        stops
+    }
+
+    /// `finish_stop` for each of a batch of stops. Returns the outcome per
+    /// tool ID; tools that already exited count as stopped.
+    pub fn finish_stops(&mut self, outcomes: Vec<StopOutcome>) -> Vec<(String, Result<()>)> {
+        outcomes
+            .into_iter()
+            .map(|outcome| {
+                let id = outcome.tool.id().to_string();
+                (id, self.finish_stop(outcome))
+            })
+            .collect()
     }
 
     /// Stop all running tools (only those we spawned, not external ones)
     pub fn stop_all(&mut self) {
-        let tool_ids: Vec<_> = self.spawned_processes.keys().cloned().collect();
-        for tool_id in tool_ids {
-            let _ = self.stop_tool(&tool_id);
-        }
+        let stops = self.begin_stop_all(false);
+        let outcomes = PendingStop::wait_all(stops);
+        self.finish_stops(outcomes);
         // Note: We don't stop external processes on hub close
     }
 }
@@ -451,25 +1276,166 @@ impl Default for ProcessManager {
 
 impl Drop for ProcessManager {
     fn drop(&mut self) {
-        // Don't stop tools when hub exits - let them keep running
-        // The user can stop them manually or they'll be detected on next hub launch
+        // By default tools keep running when the hub exits; the user can stop
+        // them manually or they'll be detected on next hub launch
+        if self.stop_on_exit {
+            self.stop_all();
+        }
     }
 }
 
-fn tool_id_to_folder(tool_id: &ToolId) -> &'static str {
-    match tool_id {
-        ToolId::DeskTalk => "desk-talk",
-        ToolId::SpeakSelected => "speak-selected",
-        ToolId::QuickAssistant => "quick-assistant",
-        ToolId::FlattenString => "flatten-string",
-        ToolId::TypoFix => "typo-fix",
-        ToolId::OcrPaste => "ocr-paste",
+/// Pass the modifiers to hold with the hotkey, as `--modifiers ctrl,shift`.
+/// Nothing is added without modifiers, so tools that don't know the flag
+/// still start.
+fn add_modifier_args(cmd: &mut Command, tool_id: &ToolId, modifiers: &[HotkeyModifier]) {
+    if modifiers.is_empty() {
+        return;
     }
+    let Some(arg_name) = tool_id.modifiers_arg() else {
+        warn!("{} doesn't support hotkey modifiers, ignoring them", tool_id.display_name());
+        return;
+    };
+    let value = modifiers.iter().map(HotkeyModifier::as_arg).collect::<Vec<_>>().join(",");
+    info!("Passing modifiers: {} {}", arg_name, value);
+    cmd.arg(arg_name).arg(value);
+}
+
+/// The error for a tool that exited (or couldn't be checked) during its
+/// startup window, with the first lines of its stderr
+fn startup_failure(child: &mut Child, exit: std::io::Result<ExitStatus>) -> ToolStartError {
+    match exit {
+        Ok(exit_status) => {
+            // Process exited immediately - this is likely an error
+            let mut stderr_output = String::new();
+            if let Some(mut stderr) = child.stderr.take() {
+                use std::io::Read;
+                let _ = stderr.read_to_string(&mut stderr_output);
+            }
+            ToolStartError::ImmediateExit {
+                code: exit_status.code(),
+                stderr: stderr_output.lines().take(5).collect::<Vec<_>>().join("\n"),
+            }
+        }
+        Err(e) => {
+            let _ = child.kill();
+            ToolStartError::SpawnFailed {
+                message: format!("Failed to check process status: {}", e),
+            }
+        }
+    }
+}
+
+/// Read a tool's stderr on a background thread until it exits, keeping the
+/// last `STDERR_TAIL_LINES` lines
+fn drain_stderr(stderr: ChildStderr) -> StderrTail {
+    let tail = StderrTail::default();
+    let lines = Arc::clone(&tail);
+    std::thread::spawn(move || {
+        for line in BufReader::new(stderr).lines().map_while(|line| line.ok()) {
+            let mut lines = lines.lock();
+            if lines.len() == STDERR_TAIL_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    });
+    tail
 }
 
-/// Get all running processes as a map of name -> PID (efficient single call)
+/// Run `<binary> --version` and parse the result.
+///
+/// Binaries that don't understand `--version` may just start up normally, so the
+/// process is killed if it hasn't exited within `VERSION_QUERY_TIMEOUT`. Can
+/// take that long, so don't call it with the process manager locked.
+pub fn read_binary_version(binary_path: &std::path::Path) -> Option<ToolVersion> {
+    let mut cmd = Command::new(binary_path);
+    cmd.arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    let mut child = cmd.spawn().ok()?;
+
+    let deadline = Instant::now() + VERSION_QUERY_TIMEOUT;
+    while child.try_wait().ok()?.is_none() {
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    let output = child.wait_with_output().ok()?;
+    ToolVersion::parse(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Status of a tool whose process is alive: running, unless it opted in to
+/// heartbeats and stopped sending them
+fn running_status(tool_id: &str) -> ToolStatus {
+    if heartbeat::is_stale(tool_id) {
+        ToolStatus::Error(NOT_RESPONDING.to_string())
+    } else {
+        ToolStatus::Running
+    }
+}
+
+/// Append a tool's user-configured `extra_args`, each as a separate argument.
+/// Invalid entries (see `is_valid_extra_arg`) are skipped rather than
+/// failing the launch.
+fn add_extra_args(cmd: &mut Command, display_name: &str, tool_config: &ToolConfig) {
+    for arg in &tool_config.extra_args {
+        if is_valid_extra_arg(arg) {
+            cmd.arg(arg);
+        } else {
+            warn!("Not passing invalid extra argument {:?} to {}", arg, display_name);
+        }
+    }
+    if !tool_config.extra_args.is_empty() {
+        info!("Extra args for {}: {:?}", display_name, tool_config.extra_args);
+    }
+}
+
+/// Set a tool's user-configured `env`, after the API key so it can override
+/// it. Invalid or protected entries (see `check_env_var`) are skipped rather
+/// than failing the launch. Values aren't logged, they may be secrets.
+fn add_tool_env(cmd: &mut Command, display_name: &str, tool_config: &ToolConfig) {
+    for (name, value) in &tool_config.env {
+        match check_env_var(name, value) {
+            Ok(()) => {
+                cmd.env(name, value);
+            }
+            Err(e) => warn!("Not passing environment to {}: {}", display_name, e),
+        }
+    }
+    if !tool_config.env.is_empty() {
+        let names: Vec<&String> = tool_config.env.keys().collect();
+        info!("Environment for {}: {:?}", display_name, names);
+    }
+}
+
+/// Poll a starting tool until it fails, is cancelled, or the grace window elapses.
+///
+/// `poll` is called repeatedly (typically `ProcessManager::poll_startup`), so
+/// callers can take the process manager lock only for the duration of each poll.
+pub fn watch_startup(grace: Duration, mut poll: impl FnMut() -> StartupCheck) -> StartupCheck {
+    let deadline = Instant::now() + grace;
+    loop {
+        let check = poll();
+        let now = Instant::now();
+        if check != StartupCheck::Pending || now >= deadline {
+            return check;
+        }
+        std::thread::sleep(STARTUP_POLL_INTERVAL.min(deadline - now));
+    }
+}
+
+/// Get all running processes as a map of name -> PIDs (efficient single call)
 #[cfg(windows)]
-fn get_all_running_processes() -> HashMap<String, u32> {
+fn get_all_running_processes() -> HashMap<String, Vec<u32>> {
     let mut result = HashMap::new();
     
     // Use tasklist to get all processes in one call
@@ -491,7 +1457,7 @@ fn get_all_running_processes() -> HashMap<String, u32> {
             let name = parts[0].trim_matches('"').to_lowercase();
             let pid_str = parts[1].trim_matches('"');
             if let Ok(pid) = pid_str.parse::<u32>() {
-                result.insert(name, pid);
+                result.entry(name).or_insert_with(Vec::new).push(pid);
             }
         }
     }
@@ -500,7 +1466,7 @@ fn get_all_running_processes() -> HashMap<String, u32> {
 }
 
 #[cfg(not(windows))]
-fn get_all_running_processes() -> HashMap<String, u32> {
+fn get_all_running_processes() -> HashMap<String, Vec<u32>> {
     let mut result = HashMap::new();
     
     // Use ps on Unix-like systems
@@ -518,7 +1484,7 @@ fn get_all_running_processes() -> HashMap<String, u32> {
         if parts.len() >= 2 {
             let name = parts[0].to_lowercase();
             if let Ok(pid) = parts[1].parse::<u32>() {
-                result.insert(name, pid);
+                result.entry(name).or_insert_with(Vec::new).push(pid);
             }
         }
     }
@@ -527,9 +1493,143 @@ fn get_all_running_processes() -> HashMap<String, u32> {
 }
 
 
+/// Windows Job Object a spawned tool is assigned to. Processes the tool
+/// starts join the job too, so terminating the job stops all of them. The job
+/// doesn't kill on close, so tools keep running when the hub exits.
+#[cfg(windows)]
+#[derive(Debug)]
+struct ProcessJob(windows_sys::Win32::Foundation::HANDLE);
+
+// The handle is only used for job calls, which are thread-safe
+#[cfg(windows)]
+unsafe impl Send for ProcessJob {}
+#[cfg(windows)]
+unsafe impl Sync for ProcessJob {}
+
+#[cfg(windows)]
+impl ProcessJob {
+    /// Create a job holding `child`. Children the tool started before this
+    /// (within microseconds of spawning) aren't included.
+    fn assign(child: &Child) -> Option<Self> {
+        use std::os::windows::io::AsRawHandle;
+        use windows_sys::Win32::Foundation::CloseHandle;
+        use windows_sys::Win32::System::JobObjects::{AssignProcessToJobObject, CreateJobObjectW};
+
+        // SAFETY: a fresh unnamed job with default security; the process
+        // handle is owned by `child` and valid for the call
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job.is_null() {
+                return None;
+            }
+            if AssignProcessToJobObject(job, child.as_raw_handle()) == 0 {
+                CloseHandle(job);
+                return None;
+            }
+            Some(Self(job))
+        }
+    }
+
+    /// Kill every process in the job
+    fn terminate(&self) {
+        // SAFETY: `self.0` is a job handle we own
+        unsafe {
+            windows_sys::Win32::System::JobObjects::TerminateJobObject(self.0, 1);
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for ProcessJob {
+    fn drop(&mut self) {
+        // SAFETY: `self.0` is a job handle we own and close exactly once
+        unsafe {
+            windows_sys::Win32::Foundation::CloseHandle(self.0);
+        }
+    }
+}
+
+/// Signal a process we spawned and every process it started. `force` kills
+/// instead of asking it to exit.
+#[cfg(windows)]
+fn terminate_tree(pid: u32, force: bool) {
+    let pid = pid.to_string();
+    let mut args = vec!["/PID", pid.as_str(), "/T"];
+    if force {
+        args.push("/F");
+    }
+    let _ = Command::new("taskkill")
+        .args(args)
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output();
+}
+
+/// Signal a process we spawned and every process it started. `force` kills
+/// instead of asking it to exit.
+#[cfg(not(windows))]
+fn terminate_tree(pid: u32, force: bool) {
+    // Spawned tools lead their own process group (see `spawn_tool`), so the
+    // negative PID reaches the tool and its helpers
+    let signal = if force { "-KILL" } else { "-TERM" };
+    let _ = Command::new("kill")
+        .args([signal, "--", &format!("-{}", pid)])
+        .output();
+}
+
+/// Stop a tool the hub didn't start, with its child processes. `force` kills
+/// instead of asking it to exit (SIGKILL rather than SIGTERM).
+///
+/// On Windows `/T` covers the whole tree, and the tree is always killed. On
+/// Unix such a process usually shares a process group with whatever launched
+/// it (e.g. a shell), so the group can't be signalled; its direct children
+/// are stopped first, then the process itself. Grandchildren of an external
+/// tool are not reached there.
+#[cfg(windows)]
+fn terminate_external(pid: u32, _force: bool) {
+    let _ = Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/F", "/T"])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output();
+}
+
+#[cfg(not(windows))]
+fn terminate_external(pid: u32, force: bool) {
+    let signal = if force { "-KILL" } else { "-TERM" };
+    let pid = pid.to_string();
+    let _ = Command::new("pkill").args([signal, "-P", &pid]).output();
+    let _ = Command::new("kill").args([signal, &pid]).output();
+}
+
+/// Wait up to `timeout` for a process we spawned to exit. Returns whether it
+/// did (an unreadable status counts as not exited).
+fn wait_for_child(child: &mut Child, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => return true,
+            Ok(None) if Instant::now() < deadline => {
+                std::thread::sleep(Duration::from_millis(25));
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// Wait up to `timeout` for a process to exit. Returns whether it did.
+fn wait_for_exit(pid: u32, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while is_process_running(pid) {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    true
+}
+
 /// Check if a process with the given PID is still running
 #[cfg(windows)]
-fn is_process_running(pid: u32) -> bool {
+pub fn is_process_running(pid: u32) -> bool {
     let output = Command::new("tasklist")
         .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
         .creation_flags(0x08000000) // CREATE_NO_WINDOW
@@ -545,10 +1645,112 @@ fn is_process_running(pid: u32) -> bool {
 }
 
 #[cfg(not(windows))]
-fn is_process_running(pid: u32) -> bool {
+pub fn is_process_running(pid: u32) -> bool {
     Command::new("kill")
         .args(["-0", &pid.to_string()])
         .output()
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
+
+/// Executable a running process was started from, if it can be read
+#[cfg(windows)]
+pub fn process_executable(pid: u32) -> Option<PathBuf> {
+    use std::os::windows::ffi::OsStringExt;
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let mut buffer = [0u16; 1024];
+    let mut len = buffer.len() as u32;
+    // SAFETY: the handle is checked before use and closed once; `len` holds
+    // the buffer size in u16s as the call expects
+    let ok = unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return None;
+        }
+        let ok =
+            QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, buffer.as_mut_ptr(), &mut len);
+        CloseHandle(process);
+        ok
+    };
+    (ok != 0).then(|| PathBuf::from(std::ffi::OsString::from_wide(&buffer[..len as usize])))
+}
+
+#[cfg(target_os = "linux")]
+pub fn process_executable(pid: u32) -> Option<PathBuf> {
+    let exe = std::fs::read_link(format!("/proc/{}/exe", pid)).ok()?;
+    // An executable replaced since (e.g. by an update) reads as "<path> (deleted)"
+    match exe.to_str().and_then(|path| path.strip_suffix(" (deleted)")) {
+        Some(path) => Some(PathBuf::from(path)),
+        None => Some(exe),
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn process_executable(pid: u32) -> Option<PathBuf> {
+    // `comm` is the full executable path on macOS
+    let output = Command::new("ps")
+        .args(["-o", "comm=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !path.is_empty()).then(|| PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_tool_is_not_started_manually() {
+        let mut pm = ProcessManager::new();
+        let tool_config = ToolConfig { enabled: false, ..ToolConfig::default() };
+
+        let result = pm.spawn_tool(&ToolId::FlattenString, &tool_config);
+
+        assert!(matches!(result, Err(ToolStartError::Disabled { .. })));
+        assert_eq!(pm.get_status(&ToolId::FlattenString), ToolStatus::Stopped);
+    }
+
+    fn tool_args(tool_id: &ToolId, tool_config: &ToolConfig) -> Vec<String> {
+        let mut cmd = Command::new("tool");
+        ProcessManager::new().add_tool_args(&mut cmd, tool_id, tool_config);
+        cmd.get_args().map(|arg| arg.to_string_lossy().into_owned()).collect()
+    }
+
+    fn speech_config() -> ToolConfig {
+        ToolConfig {
+            hotkey: Some("F9".to_string()),
+            voice: Some("nova".to_string()),
+            speech_speed: Some(1.5),
+            ..ToolConfig::default()
+        }
+    }
+
+    #[test]
+    fn voice_and_speed_are_passed_to_tts_tools() {
+        let args = tool_args(&ToolId::SpeakSelected, &speech_config());
+        assert!(args.windows(2).any(|pair| pair == ["--voice", "nova"]), "{:?}", args);
+        assert!(args.windows(2).any(|pair| pair == ["--speech-speed", "1.5"]), "{:?}", args);
+
+        let args = tool_args(&ToolId::QuickAssistant, &speech_config());
+        assert!(args.windows(2).any(|pair| pair == ["--ai-voice", "nova"]), "{:?}", args);
+    }
+
+    #[test]
+    fn voice_and_speed_are_not_passed_to_other_tools() {
+        let args = tool_args(&ToolId::FlattenString, &speech_config());
+        assert_eq!(args, ["--trigger-key", "F9"]);
+    }
+
+    #[test]
+    fn unset_voice_and_speed_are_omitted() {
+        let tool_config = ToolConfig { voice: None, speech_speed: None, ..speech_config() };
+        let args = tool_args(&ToolId::SpeakSelected, &tool_config);
+        assert_eq!(args, ["--ptt-key", "F9"]);
+    }
+}
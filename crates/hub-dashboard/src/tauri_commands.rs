@@ -1,111 +1,226 @@
 //! Tauri commands for the Hub Dashboard
 
+use crate::process_manager::{
+    self, ExitRecord, HubRunState, ManagedTool, PendingStop, ScanDiff, StartupCheck, ToolOrigin,
+    ToolVersion,
+};
+use crate::hub_lock::LockOwner;
 use crate::AppState;
-use hub_common::{config, HubConfig, ToolConfig, ToolId, ToolStatus};
+use hub_common::{
+    config, ApiProvider, ConfigRecovery, HotkeyModifier, HubConfig, Key, KeyCategory, NamedKey,
+    SearchRoots, ToolCategory, ToolConfig, ToolDescriptor, ToolId, ThemeMode, ToolStartError,
+    ToolStatus,
+};
+use hub_licensing::AuthPrompt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tauri::State;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tracing::{info, warn};
 
 /// Frontend-friendly config representation
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FrontendConfig {
     pub auto_start: bool,
     pub start_minimized: bool,
-    pub dark_mode: bool,
+    #[serde(default)]
+    pub theme: ThemeMode,
+    #[serde(default)]
+    pub restore_session: bool,
+    #[serde(default)]
+    pub stop_tools_on_exit: bool,
+    #[serde(default)]
+    pub tool_directory: Option<String>,
+    #[serde(default)]
+    pub external_scan_interval_secs: Option<u64>,
+    #[serde(default)]
+    pub api_provider: ApiProvider,
+    #[serde(default)]
+    pub api_base_url: Option<String>,
     pub tools: HashMap<String, FrontendToolConfig>,
 }
 
+/// A tool's settings as the frontend sees them. When saving, a field left
+/// out keeps the stored value, while an explicit `null` clears it.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FrontendToolConfig {
-    pub enabled: bool,
-    pub auto_start: bool,
-    #[serde(default)]
-    pub hotkey: Option<String>,
-    #[serde(default)]
-    pub special_hotkey: Option<u32>,
-    #[serde(default)]
-    pub voice: Option<String>,
-    #[serde(default)]
-    pub speech_speed: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_start: Option<bool>,
+    #[serde(default, deserialize_with = "present")]
+    pub hotkey: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub special_hotkey: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "present")]
+    pub voice: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub speech_speed: Option<Option<f32>>,
+    /// Extra environment variables for the tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<HashMap<String, String>>,
+}
+
+impl FrontendToolConfig {
+    /// The stored config with the fields the frontend sent replaced. Fields
+    /// the UI doesn't show (extra args, settings, ...) are always kept.
+    fn merge_into(self, stored: ToolConfig) -> ToolConfig {
+        ToolConfig {
+            enabled: self.enabled.unwrap_or(stored.enabled),
+            auto_start: self.auto_start.unwrap_or(stored.auto_start),
+            hotkey: self.hotkey.unwrap_or(stored.hotkey),
+            special_hotkey: self.special_hotkey.unwrap_or(stored.special_hotkey),
+            voice: self.voice.unwrap_or(stored.voice),
+            speech_speed: self.speech_speed.unwrap_or(stored.speech_speed),
+            env: self.env.unwrap_or(stored.env),
+            ..stored
+        }
+    }
+}
+
+impl From<ToolConfig> for FrontendToolConfig {
+    fn from(tc: ToolConfig) -> Self {
+        FrontendToolConfig {
+            enabled: Some(tc.enabled),
+            auto_start: Some(tc.auto_start),
+            hotkey: Some(tc.hotkey),
+            special_hotkey: Some(tc.special_hotkey),
+            voice: Some(tc.voice),
+            speech_speed: Some(tc.speech_speed),
+            env: Some(tc.env),
+        }
+    }
+}
+
+/// Tell a field sent as `null` (`Some(None)`) from one left out (`None`)
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
 impl From<HubConfig> for FrontendConfig {
     fn from(config: HubConfig) -> Self {
-        let tools = config
-            .tools
-            .into_iter()
-            .map(|(id, tc)| {
-                let key = match id {
-                    ToolId::DeskTalk => "desk-talk",
-                    ToolId::SpeakSelected => "speak-selected",
-                    ToolId::QuickAssistant => "quick-assistant",
-                    ToolId::FlattenString => "flatten-string",
-                    ToolId::TypoFix => "typo-fix",
-                    ToolId::OcrPaste => "ocr-paste",
-                };
-                (
-                    key.to_string(),
-                    FrontendToolConfig {
-                        enabled: tc.enabled,
-                        auto_start: tc.auto_start,
-                        hotkey: tc.hotkey,
-                        special_hotkey: tc.special_hotkey,
-                        voice: tc.voice,
-                        speech_speed: tc.speech_speed,
-                    },
-                )
-            })
+        // Every built-in tool, defaults included, keyed by its frontend ID
+        let mut tools: HashMap<String, FrontendToolConfig> = ToolId::all()
+            .iter()
+            .map(|id| (id.as_str().to_string(), config.get_tool_config(id).into()))
             .collect();
+        tools.extend(config.plugin_tools.into_iter().map(|(id, tc)| (id, tc.into())));
 
         FrontendConfig {
             auto_start: config.auto_start,
             start_minimized: config.start_minimized,
-            dark_mode: config.dark_mode,
+            theme: config.theme,
+            restore_session: config.restore_session,
+            stop_tools_on_exit: config.stop_tools_on_exit,
+            tool_directory: config
+                .tool_directory
+                .map(|dir| dir.to_string_lossy().to_string()),
+            external_scan_interval_secs: config.external_scan_interval_secs,
+            api_provider: config.api_provider,
+            api_base_url: config.api_base_url,
             tools,
         }
     }
 }
 
-fn string_to_tool_id(s: &str) -> Option<ToolId> {
-    match s {
-        "desk-talk" => Some(ToolId::DeskTalk),
-        "speak-selected" => Some(ToolId::SpeakSelected),
-        "quick-assistant" => Some(ToolId::QuickAssistant),
-        "flatten-string" => Some(ToolId::FlattenString),
-        "typo-fix" => Some(ToolId::TypoFix),
-        "ocr-paste" => Some(ToolId::OcrPaste),
-        _ => None,
-    }
-}
-
 #[tauri::command]
 pub fn get_config(state: State<AppState>) -> FrontendConfig {
     state.config.read().clone().into()
 }
 
+/// Write the current config to disk immediately (saves are otherwise
+/// debounced), reporting any write error
+#[tauri::command]
+pub fn save_config_now(state: State<AppState>) -> Result<(), String> {
+    let config = state.config.read();
+    state.config_writer.save_now(&config).map_err(|e| e.to_string())
+}
+
+/// Outcome of `save_config`
+#[derive(Debug, Serialize)]
+pub struct SaveConfigResult {
+    /// Running tools whose launch args (hotkey, voice, speed) changed and so
+    /// need a restart to apply them
+    pub needs_restart: Vec<String>,
+    /// Non-fatal problems with the saved values, e.g. a newly chosen hotkey
+    /// the OS usually keeps for itself
+    pub warnings: Vec<String>,
+}
+
+/// Save the config.
+///
+/// The write itself is debounced, so bursts of changes hit the disk once.
 #[tauri::command]
-pub fn save_config(state: State<AppState>, config: FrontendConfig) -> Result<(), String> {
+pub fn save_config(
+    state: State<AppState>,
+    config: FrontendConfig,
+) -> Result<SaveConfigResult, String> {
+    let tool_directory = match config.tool_directory.as_deref().map(str::trim) {
+        Some(dir) if !dir.is_empty() => {
+            let dir = std::path::PathBuf::from(dir);
+            if !dir.is_dir() {
+                return Err(format!("Tool directory does not exist: {}", dir.display()));
+            }
+            Some(dir)
+        }
+        _ => None,
+    };
+
+    // Reject bad voice/speed/env values before touching the stored config
+    for (key, tc) in &config.tools {
+        let launch_values = ToolConfig {
+            voice: tc.voice.clone().flatten(),
+            speech_speed: tc.speech_speed.flatten(),
+            env: tc.env.clone().unwrap_or_default(),
+            ..ToolConfig::default()
+        };
+        if let Err(e) = launch_values.validate() {
+            return Err(format!("{} {}: {}", key, e.field(), e));
+        }
+    }
+
     let mut hub_config = state.config.write();
 
     hub_config.auto_start = config.auto_start;
     hub_config.start_minimized = config.start_minimized;
-    hub_config.dark_mode = config.dark_mode;
+    hub_config.theme = config.theme;
+    hub_config.restore_session = config.restore_session;
+    hub_config.stop_tools_on_exit = config.stop_tools_on_exit;
+    hub_config.tool_directory = tool_directory.clone();
+    hub_config.external_scan_interval_secs = config.external_scan_interval_secs;
+    hub_config.api_provider = config.api_provider;
+    hub_config.api_base_url = config.api_base_url;
 
     // Update tool configs
+    let mut changed = Vec::new();
+    let mut warnings = Vec::new();
     for (key, tc) in config.tools {
-        if let Some(tool_id) = string_to_tool_id(&key) {
-            hub_config.set_tool_config(
-                tool_id,
-                ToolConfig {
-                    enabled: tc.enabled,
-                    auto_start: tc.auto_start,
-                    hotkey: tc.hotkey,
-                    special_hotkey: tc.special_hotkey,
-                    voice: tc.voice,
-                    speech_speed: tc.speech_speed,
-                    settings: serde_json::Value::Null,
-                },
-            );
+        let previous = match key.parse::<ToolId>() {
+            Ok(ref tool_id) => hub_config.get_tool_config(tool_id),
+            Err(_) => hub_config.plugin_tools.get(&key).cloned().unwrap_or_default(),
+        };
+        let tool_config = tc.merge_into(previous.clone());
+
+        // Only warn about a hotkey when it's picked, not on every save
+        if tool_config.hotkey != previous.hotkey {
+            if let Some(reason) = tool_config.reserved_hotkey_reason() {
+                let name = key.parse::<ToolId>().map_or(key.clone(), |id| id.display_name().into());
+                warnings.push(format!("{}: {}", name, reason));
+            }
+        }
+
+        if let Ok(tool_id) = key.parse::<ToolId>() {
+            // GUI tools read their own config files, so args don't matter
+            if !tool_id.is_gui() && tool_config.launch_args_differ(&previous) {
+                changed.push(tool_id.clone());
+            }
+            hub_config.set_tool_config(tool_id, tool_config);
+        } else if state.plugins.iter().any(|p| p.id == key) {
+            hub_config.plugin_tools.insert(key, tool_config);
         }
     }
 
@@ -119,25 +234,311 @@ pub fn save_config(state: State<AppState>, config: FrontendConfig) -> Result<(),
         }
     }
 
-    hub_config.save().map_err(|e| e.to_string())
+    let api_base_url = hub_config.api_base_url().map(str::to_string);
+    state.config_writer.save(&hub_config);
+    drop(hub_config);
+
+    let mut pm = state.process_manager.write();
+    pm.set_tool_directory(tool_directory);
+    pm.set_stop_on_exit(config.stop_tools_on_exit);
+    pm.set_api_endpoint(config.api_provider, api_base_url);
+    let needs_restart = changed
+        .into_iter()
+        .filter(|id| matches!(pm.get_status(id), ToolStatus::Running | ToolStatus::Error(_)))
+        .map(|id| id.to_string())
+        .collect();
+    Ok(SaveConfigResult { needs_restart, warnings })
+}
+
+/// Outcome of `set_tool_hotkey`
+#[derive(Debug, Serialize)]
+pub struct SetHotkeyResult {
+    /// The tool is running with the old hotkey and must be restarted
+    pub needs_restart: bool,
+    /// The OS usually keeps the new hotkey for itself
+    pub warning: Option<String>,
 }
 
+/// Change one tool's hotkey without resending the whole config.
+///
+/// Fails if another tool already uses the combination. `modifiers: None`
+/// keeps the tool's current modifiers.
 #[tauri::command]
-pub fn has_api_key() -> bool {
-    config::has_api_key()
+pub fn set_tool_hotkey(
+    state: State<AppState>,
+    tool_id: String,
+    hotkey: Option<String>,
+    modifiers: Option<Vec<HotkeyModifier>>,
+) -> Result<SetHotkeyResult, String> {
+    let tool_id = tool_id.parse::<ToolId>().map_err(|e| e.to_string())?;
+    let hotkey = hotkey.filter(|name| !name.trim().is_empty());
+    if let Some(name) = &hotkey {
+        if NamedKey::from_config_name(name).is_none() {
+            return Err(format!("Unknown key: {}", name));
+        }
+    }
+
+    let mut hub_config = state.config.write();
+    let previous = hub_config.get_tool_config(&tool_id);
+    let tool_config = ToolConfig {
+        hotkey,
+        modifiers: modifiers.unwrap_or_else(|| previous.modifiers.clone()),
+        ..previous.clone()
+    };
+
+    if let Some(key) = tool_config.hotkey_key() {
+        let mut registry = hub_config.tool_hotkey_registry();
+        registry.unregister_tool(&tool_id);
+        let action = format!("{} hotkey", tool_id.display_name());
+        registry
+            .register(tool_id.clone(), action, key, tool_config.modifiers.clone())
+            .map_err(|conflict| conflict.to_string())?;
+    }
+
+    let warning = if tool_config.hotkey != previous.hotkey {
+        tool_config.reserved_hotkey_reason().map(str::to_string)
+    } else {
+        None
+    };
+    let args_changed = !tool_id.is_gui() && tool_config.launch_args_differ(&previous);
+    hub_config.set_tool_config(tool_id.clone(), tool_config);
+    state.config_writer.save(&hub_config);
+    drop(hub_config);
+
+    let needs_restart = args_changed
+        && matches!(
+            state.process_manager.read().get_status(&tool_id),
+            ToolStatus::Running | ToolStatus::Error(_)
+        );
+    Ok(SetHotkeyResult { needs_restart, warning })
 }
 
+/// Export settings to a portable JSON file (no secrets)
 #[tauri::command]
-pub fn get_api_key_masked() -> Option<String> {
-    config::load_api_key().ok().map(|key| {
-        if key.len() > 8 {
-            format!("{}...{}", &key[..4], &key[key.len()-4..])
+pub fn export_config(state: State<AppState>, path: String) -> Result<(), String> {
+    state
+        .config
+        .read()
+        .export_to(std::path::Path::new(&path))
+        .map_err(|e| e.to_string())
+}
+
+/// Result of importing a config file
+#[derive(Debug, Serialize)]
+pub struct ConfigImportResult {
+    pub config: FrontendConfig,
+    /// Whether the exporting machine had an API key configured
+    pub exported_with_api_key: bool,
+    /// Whether this machine has an API key configured
+    pub has_api_key: bool,
+}
+
+/// Replace the settings with ones exported from another machine.
+///
+/// This machine's tool directory and session state are kept.
+#[tauri::command]
+pub fn import_config(state: State<AppState>, path: String) -> Result<ConfigImportResult, String> {
+    let export = HubConfig::import_from(std::path::Path::new(&path)).map_err(|e| e.to_string())?;
+
+    let mut hub_config = state.config.write();
+    let imported = HubConfig {
+        tool_directory: hub_config.tool_directory.clone(),
+        last_running: hub_config.last_running.clone(),
+        ..export.config
+    };
+    state.config_writer.save_now(&imported).map_err(|e| e.to_string())?;
+
+    #[cfg(windows)]
+    {
+        if imported.auto_start {
+            let _ = hub_common::config::enable_autostart();
         } else {
-            "••••••••".to_string()
+            let _ = hub_common::config::disable_autostart();
         }
+    }
+    {
+        let mut pm = state.process_manager.write();
+        pm.set_stop_on_exit(imported.stop_tools_on_exit);
+        pm.set_api_endpoint(imported.api_provider, imported.api_base_url().map(str::to_string));
+    }
+    *hub_config = imported;
+
+    Ok(ConfigImportResult {
+        config: hub_config.clone().into(),
+        exported_with_api_key: export.has_api_key,
+        has_api_key: config::has_api_key(),
     })
 }
 
+/// Static metadata for one tool
+#[derive(Debug, Serialize)]
+pub struct ToolMetadata {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub icon: String,
+    pub requires_api_key: bool,
+}
+
+/// Tools grouped under one category
+#[derive(Debug, Serialize)]
+pub struct ToolCategoryGroup {
+    pub category: ToolCategory,
+    pub name: String,
+    pub tools: Vec<ToolMetadata>,
+}
+
+/// Get tool metadata (built-in and plugin tools) grouped by category, so the
+/// UI doesn't hardcode it
+#[tauri::command]
+pub fn get_tool_catalog(state: State<AppState>) -> Vec<ToolCategoryGroup> {
+    let builtins = ToolId::all().iter().map(|id| (id.descriptor(), id.icon_name()));
+    // Plugins don't ship an icon; the UI falls back to a generic one
+    let plugins = state.plugins.iter().map(|d| (d.clone(), "plugin"));
+    let tools: Vec<_> = builtins.chain(plugins).collect();
+
+    ToolCategory::all()
+        .iter()
+        .map(|&category| ToolCategoryGroup {
+            category,
+            name: category.display_name().to_string(),
+            tools: tools
+                .iter()
+                .filter(|(d, _)| d.category == category)
+                .map(|(d, icon)| ToolMetadata {
+                    id: d.id.clone(),
+                    name: d.display_name.clone(),
+                    description: d.description.clone(),
+                    icon: icon.to_string(),
+                    requires_api_key: d.requires_api_key,
+                })
+                .collect(),
+        })
+        .filter(|group| !group.tools.is_empty())
+        .collect()
+}
+
+/// A key the hotkey picker offers
+#[derive(Debug, Serialize)]
+pub struct KeyDescriptor {
+    /// Value for `ToolConfig::hotkey`; `None` for keys only usable as a special hotkey
+    pub hotkey: Option<String>,
+    /// Value for `ToolConfig::special_hotkey`, for keys rdev only knows by code
+    pub special_hotkey: Option<u32>,
+    pub label: String,
+    pub category: KeyCategory,
+}
+
+/// Get every key that can be assigned as a hotkey, in picker order, so the UI
+/// doesn't hardcode the list
+#[tauri::command]
+pub fn get_assignable_keys() -> Vec<KeyDescriptor> {
+    NamedKey::ALL
+        .iter()
+        .map(|&key| {
+            let hotkey = key.config_name();
+            let special_hotkey = match Key::from(key) {
+                Key::Unknown(code) if hotkey.is_none() => Some(code),
+                _ => None,
+            };
+            KeyDescriptor {
+                hotkey: hotkey.map(str::to_string),
+                special_hotkey,
+                label: key.label().to_string(),
+                category: key.category(),
+            }
+        })
+        .collect()
+}
+
+/// Why a tool won't be started on hub startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum AutostartSkipReason {
+    /// No valid license or active trial
+    NotAuthorized,
+    /// All tools are paused
+    Paused,
+    /// Tool is disabled
+    Disabled,
+    /// Auto-start is off (and it isn't restored from the last session)
+    AutoStartOff,
+    /// Tool needs the OpenAI API key and none is configured
+    MissingApiKey,
+}
+
+/// Whether one tool would be started on hub startup
+#[derive(Debug, Clone, Serialize)]
+pub struct AutostartDecision {
+    pub tool_id: String,
+    pub will_start: bool,
+    /// Set when `will_start` is false
+    pub reason: Option<AutostartSkipReason>,
+}
+
+/// Decide which tools to start on hub startup, in start order (shared by
+/// `auto_start_tools` and `preview_autostart`)
+pub fn autostart_decisions(
+    config: &HubConfig,
+    authorized: bool,
+    has_api_key: bool,
+) -> Vec<(ToolId, Option<AutostartSkipReason>)> {
+    let mut tools = ToolId::all().to_vec();
+    tools.sort_by_key(|tool_id| config.get_tool_config(tool_id).autostart_order);
+
+    tools
+        .iter()
+        .map(|tool_id| {
+            let tool_config = config.get_tool_config(tool_id);
+            let restore = config.restore_session && config.last_running.contains(tool_id);
+
+            let skip = if !authorized {
+                Some(AutostartSkipReason::NotAuthorized)
+            } else if config.paused {
+                Some(AutostartSkipReason::Paused)
+            } else if !tool_config.enabled {
+                Some(AutostartSkipReason::Disabled)
+            } else if !(tool_config.auto_start || restore) {
+                Some(AutostartSkipReason::AutoStartOff)
+            } else if tool_id.requires_api_key() && !has_api_key {
+                Some(AutostartSkipReason::MissingApiKey)
+            } else {
+                None
+            };
+            (tool_id.clone(), skip)
+        })
+        .collect()
+}
+
+/// Show which tools would start on hub startup, and why the others wouldn't,
+/// without starting anything
+#[tauri::command]
+pub fn preview_autostart(state: State<AppState>) -> Vec<AutostartDecision> {
+    let config = state.config.read();
+    autostart_decisions(&config, process_manager::license_allows_start(), config::has_api_key())
+        .into_iter()
+        .map(|(tool_id, reason)| AutostartDecision {
+            tool_id: tool_id.to_string(),
+            will_start: reason.is_none(),
+            reason,
+        })
+        .collect()
+}
+
+/// Look up a plugin tool loaded from `tools.d`
+fn find_plugin<'a>(state: &'a AppState, id: &str) -> Option<&'a ToolDescriptor> {
+    state.plugins.iter().find(|d| d.id == id)
+}
+
+#[tauri::command]
+pub fn has_api_key() -> bool {
+    config::has_api_key()
+}
+
+#[tauri::command]
+pub fn get_api_key_masked() -> Option<String> {
+    config::load_api_key().ok().map(|key| hub_licensing::mask_secret(&key))
+}
+
 #[tauri::command]
 pub fn get_api_key() -> Result<String, String> {
     config::load_api_key().map_err(|e| e.to_string())
@@ -148,6 +549,13 @@ pub fn save_api_key(api_key: String) -> Result<(), String> {
     config::save_api_key(&api_key).map_err(|e| e.to_string())
 }
 
+/// Whether the API key is kept in the OS keyring (false means it's stored in
+/// a plain `.env` file in the config directory)
+#[tauri::command]
+pub fn has_secure_storage() -> bool {
+    config::has_secure_storage()
+}
+
 #[tauri::command]
 pub fn delete_api_key() -> Result<(), String> {
     config::delete_api_key().map_err(|e| e.to_string())
@@ -160,7 +568,12 @@ pub struct ApiKeyValidation {
 }
 
 #[tauri::command]
-pub async fn validate_api_key() -> ApiKeyValidation {
+pub fn validate_api_key(state: State<AppState>) -> ApiKeyValidation {
+    let (provider, has_base_url) = {
+        let config = state.config.read();
+        (config.api_provider, config.api_base_url().is_some())
+    };
+
     // Load the API key
     let api_key = match config::load_api_key() {
         Ok(key) => key,
@@ -172,6 +585,15 @@ pub async fn validate_api_key() -> ApiKeyValidation {
         }
     };
 
+    // Other providers have their own key formats (a local server may accept
+    // anything), but need to know where to send requests
+    if !provider.uses_openai_keys() {
+        return ApiKeyValidation {
+            valid: has_base_url,
+            error: (!has_base_url).then(|| "Set the API base URL for this provider".to_string()),
+        };
+    }
+
     // Basic validation - check format
     if !api_key.starts_with("sk-") {
         return ApiKeyValidation {
@@ -195,6 +617,97 @@ pub async fn validate_api_key() -> ApiKeyValidation {
     }
 }
 
+/// How long `test_api_key` waits for the provider
+const API_KEY_TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of `test_api_key`
+#[derive(Debug, Serialize)]
+pub struct ApiKeyTestResult {
+    /// The provider accepted the key and the account can make requests
+    pub usable: bool,
+    /// Round trip of the test request, if the provider answered
+    pub latency_ms: Option<u64>,
+    /// Why the key can't be used, phrased for the user
+    pub error: Option<String>,
+}
+
+impl ApiKeyTestResult {
+    fn failed(latency_ms: Option<u64>, error: impl Into<String>) -> Self {
+        Self { usable: false, latency_ms, error: Some(error.into()) }
+    }
+}
+
+/// Make an authenticated request (listing models) to the configured provider,
+/// unlike `validate_api_key` which only checks the format. Tests `key` if
+/// given, else the stored key; a key passed in is never saved.
+#[tauri::command]
+pub async fn test_api_key(
+    state: State<'_, AppState>,
+    key: Option<String>,
+) -> Result<ApiKeyTestResult, String> {
+    let (provider, base_url) = {
+        let config = state.config.read();
+        (config.api_provider, config.api_base_url().map(str::to_string))
+    };
+    let api_key = match key.as_deref().map(str::trim).filter(|key| !key.is_empty()) {
+        Some(key) => key.to_string(),
+        None => config::load_api_key().map_err(|_| "No API key configured".to_string())?,
+    };
+
+    let request = match (provider, base_url) {
+        (ApiProvider::OpenAi, base_url) => {
+            let base_url = base_url.unwrap_or_else(|| "https://api.openai.com/v1".to_string());
+            reqwest::Client::new()
+                .get(format!("{}/models", base_url.trim_end_matches('/')))
+                .bearer_auth(&api_key)
+        }
+        (ApiProvider::Compatible, Some(base_url)) => reqwest::Client::new()
+            .get(format!("{}/models", base_url.trim_end_matches('/')))
+            .bearer_auth(&api_key),
+        (ApiProvider::Azure, Some(endpoint)) => reqwest::Client::new()
+            .get(format!(
+                "{}/openai/models?api-version=2024-10-21",
+                endpoint.trim_end_matches('/')
+            ))
+            .header("api-key", &api_key),
+        (_, None) => return Err("Set the API base URL for this provider".to_string()),
+    };
+
+    let started = Instant::now();
+    let response = match request.timeout(API_KEY_TEST_TIMEOUT).send().await {
+        Ok(response) => response,
+        Err(e) if e.is_timeout() => {
+            return Ok(ApiKeyTestResult::failed(
+                None,
+                format!("No answer within {} seconds", API_KEY_TEST_TIMEOUT.as_secs()),
+            ))
+        }
+        Err(e) => {
+            return Ok(ApiKeyTestResult::failed(None, format!("Could not connect: {}", e)))
+        }
+    };
+    let latency_ms = Some(started.elapsed().as_millis() as u64);
+
+    let status = response.status();
+    if status.is_success() {
+        return Ok(ApiKeyTestResult { usable: true, latency_ms, error: None });
+    }
+    let body = response.text().await.unwrap_or_default();
+    let error = match status.as_u16() {
+        401 => "The provider rejected the API key".to_string(),
+        403 => "The API key isn't allowed to use this endpoint".to_string(),
+        // OpenAI answers 429 both when throttling and when the account is out of credit
+        429 if body.contains("insufficient_quota") => {
+            "The account has no credit left - check its billing".to_string()
+        }
+        429 => "Rate limited by the provider, try again in a moment".to_string(),
+        code => format!("The provider answered HTTP {}", code),
+    };
+    Ok(ApiKeyTestResult::failed(latency_ms, error))
+}
+
+/// Status label per tool: "Stopped", "Starting", "Running", or the error
+/// message (e.g. "Not responding" for a tool whose heartbeat went stale)
 #[tauri::command]
 pub fn get_tool_statuses(state: State<AppState>) -> HashMap<String, String> {
     // Quick refresh - only checks spawned processes (fast)
@@ -207,63 +720,704 @@ pub fn get_tool_statuses(state: State<AppState>) -> HashMap<String, String> {
     let mut statuses = HashMap::new();
 
     for tool_id in ToolId::all() {
-        let status = pm.get_status(tool_id);
-        let key = match tool_id {
-            ToolId::DeskTalk => "desk-talk",
-            ToolId::SpeakSelected => "speak-selected",
-            ToolId::QuickAssistant => "quick-assistant",
-            ToolId::FlattenString => "flatten-string",
-            ToolId::TypoFix => "typo-fix",
-            ToolId::OcrPaste => "ocr-paste",
-        };
-        let status_str = match status {
-            ToolStatus::Stopped => "Stopped",
-            ToolStatus::Starting => "Starting",
-            ToolStatus::Running => "Running",
-            ToolStatus::Error(_) => "Error",
-        };
-        statuses.insert(key.to_string(), status_str.to_string());
+        statuses.insert(tool_id.to_string(), status_label(pm.get_status(tool_id)));
+    }
+
+    for plugin in &state.plugins {
+        statuses.insert(plugin.id.clone(), status_label(pm.plugin_status(&plugin.id)));
     }
 
     statuses
 }
 
+/// A tool's status along with who started it
+#[derive(Debug, Serialize)]
+pub struct ToolStatusReport {
+    /// Same label as `get_tool_statuses`
+    pub status: String,
+    /// `None` while the tool isn't running
+    pub origin: Option<ToolOrigin>,
+}
+
+/// `get_tool_statuses` plus whether each running tool was started by the hub
+/// or detected running outside it
+#[tauri::command]
+pub fn get_tool_status_reports(state: State<AppState>) -> HashMap<String, ToolStatusReport> {
+    let mut pm = state.process_manager.write();
+    pm.refresh_statuses();
+
+    let mut reports: HashMap<String, ToolStatusReport> = ToolId::all()
+        .iter()
+        .map(|tool_id| {
+            let report = ToolStatusReport {
+                status: status_label(pm.get_status(tool_id)),
+                origin: pm.origin(tool_id),
+            };
+            (tool_id.to_string(), report)
+        })
+        .collect();
+
+    // Third-party tools are only tracked while the hub runs them
+    for plugin in &state.plugins {
+        let status = pm.plugin_status(&plugin.id);
+        let origin = (status != ToolStatus::Stopped).then_some(ToolOrigin::Spawned);
+        let report = ToolStatusReport { status: status_label(status), origin };
+        reports.insert(plugin.id.clone(), report);
+    }
+
+    reports
+}
+
+/// One tool's status label (see `get_tool_statuses`), refreshing only that
+/// tool, for polling right after acting on it
+#[tauri::command]
+pub fn get_tool_status(state: State<AppState>, tool_id: String) -> Result<String, String> {
+    let mut pm = state.process_manager.write();
+    if let Ok(id) = tool_id.parse::<ToolId>() {
+        pm.refresh_status(&id);
+        return Ok(status_label(pm.get_status(&id)));
+    }
+    if state.plugins.iter().any(|p| p.id == tool_id) {
+        pm.refresh_plugin_status(&tool_id);
+        return Ok(status_label(pm.plugin_status(&tool_id)));
+    }
+    Err(format!("Unknown tool: {}", tool_id))
+}
+
+fn status_label(status: ToolStatus) -> String {
+    match status {
+        ToolStatus::Stopped => "Stopped".to_string(),
+        ToolStatus::Starting => "Starting".to_string(),
+        ToolStatus::Running => "Running".to_string(),
+        ToolStatus::Error(message) => message,
+    }
+}
+
+
+/// Overall tool state: all stopped, some running, or an error present
+#[tauri::command]
+pub fn get_hub_run_state(state: State<AppState>) -> HubRunState {
+    let mut pm = state.process_manager.write();
+    pm.refresh_statuses();
+    pm.aggregate_status()
+}
+
+/// A tool's last exit, for "crashed 2 minutes ago with: ..."
+#[derive(Debug, Serialize)]
+pub struct LastExitReport {
+    #[serde(flatten)]
+    pub record: ExitRecord,
+    /// e.g. "2 minutes ago"
+    pub exited_relative: Option<String>,
+}
+
+/// How a tool last exited on its own, `None` if it hasn't since it was last
+/// started
+#[tauri::command]
+pub fn get_tool_last_exit(
+    state: State<AppState>,
+    tool_id: String,
+) -> Result<Option<LastExitReport>, String> {
+    let tool = tool_id.parse::<ToolId>().map_err(|e| e.to_string())?;
+    let mut pm = state.process_manager.write();
+    pm.refresh_statuses();
+    Ok(pm.last_exit(&tool).map(|record| LastExitReport {
+        exited_relative: hub_licensing::relative_time(&record.exited_at),
+        record: record.clone(),
+    }))
+}
+
+/// Number of running instances per tool, for tools running more than once.
+///
+/// Relies on the last full scan, so duplicates started outside the hub show
+/// up within one scan interval.
 #[tauri::command]
-pub fn scan_external_processes(state: State<AppState>) {
+pub fn get_tool_instance_counts(state: State<AppState>) -> HashMap<String, usize> {
+    let pm = state.process_manager.read();
+    ToolId::all()
+        .iter()
+        .map(|tool_id| (tool_id.to_string(), pm.instance_count(tool_id)))
+        .filter(|(_, count)| *count > 1)
+        .collect()
+}
+
+/// Version info for one tool binary
+#[derive(Debug, Serialize)]
+pub struct ToolVersionReport {
+    /// Reported version, or "Unknown" if the binary doesn't support `--version`
+    pub version: String,
+    /// Minimum version the hub's CLI args require, if any
+    pub min_version: Option<String>,
+    /// Whether the binary is known to be older than `min_version`
+    pub outdated: bool,
+}
+
+#[tauri::command]
+pub fn get_tool_versions(state: State<AppState>) -> HashMap<String, ToolVersionReport> {
+    let mut versions = HashMap::new();
+
+    for tool_id in ToolId::all() {
+        // `--version` runs with no lock held, so status polling isn't blocked
+        let cached = state.process_manager.read().cached_version(tool_id);
+        let version = cached.unwrap_or_else(|| {
+            // A missing binary isn't cached, it may be installed later
+            let binary_path = state.process_manager.read().binary_path(tool_id)?;
+            let version = process_manager::read_binary_version(&binary_path);
+            state.process_manager.write().record_version(tool_id, version);
+            version
+        });
+        let min_version = ToolVersion::min_supported(tool_id);
+        let outdated = match (version, min_version) {
+            (Some(v), Some(min)) if v < min => {
+                warn!(
+                    "{} {} is older than the minimum supported {}",
+                    tool_id.display_name(),
+                    v,
+                    min
+                );
+                true
+            }
+            _ => false,
+        };
+
+        versions.insert(
+            tool_id.to_string(),
+            ToolVersionReport {
+                version: version
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "Unknown".to_string()),
+                min_version: min_version.map(|v| v.to_string()),
+                outdated,
+            },
+        );
+    }
+
+    versions
+}
+
+/// Scan for tools started outside the hub, reporting what changed since the
+/// last scan
+#[tauri::command]
+pub fn scan_external_processes(state: State<AppState>) -> ScanDiff {
     // Full scan for external processes - expensive, call sparingly
     let mut pm = state.process_manager.write();
-    pm.full_scan();
+    pm.full_scan()
+}
+
+/// Payload for the `tool-started` event
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolStartEvent {
+    pub tool_id: String,
+}
+
+/// A failed tool start, as reported to the frontend (command error and
+/// `tool-start-failed` event payload)
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolStartFailure {
+    pub tool_id: String,
+    /// Human-readable message (the text a `ToolStatus::Error` would carry)
+    pub message: String,
+    /// Structured cause, so the UI can offer the right fix (e.g. API-key
+    /// dialog vs. "download the tool"); `None` for failures outside the
+    /// process manager such as licensing
+    pub error: Option<ToolStartError>,
+    /// Set when the start was refused for licensing, so the UI can offer
+    /// the matching fix (start trial, purchase, renew)
+    pub auth_prompt: Option<AuthPrompt>,
 }
 
+impl ToolStartFailure {
+    fn new(tool_id: &str, error: ToolStartError) -> Self {
+        // The process manager refuses unlicensed starts on its own; look up
+        // the matching fix for the UI
+        let auth_prompt = match error {
+            ToolStartError::NotAuthorized { .. } => hub_licensing::authorization_check().prompt,
+            _ => None,
+        };
+        Self {
+            tool_id: tool_id.to_string(),
+            message: error.to_string(),
+            error: Some(error),
+            auth_prompt,
+        }
+    }
+
+    fn other(tool_id: &str, message: impl Into<String>) -> Self {
+        Self {
+            tool_id: tool_id.to_string(),
+            message: message.into(),
+            error: None,
+            auth_prompt: None,
+        }
+    }
+}
+
+/// Start a tool without blocking on its startup health check.
+///
+/// Returns the tool's status right away ("Starting", or "Running" if it was
+/// already up). The outcome of the health check is reported later via a
+/// `tool-started` or `tool-start-failed` event.
 #[tauri::command]
-pub fn start_tool(state: State<AppState>, tool_id: String) -> Result<(), String> {
-    // Check if user is authorized (valid license or active trial)
-    if !hub_licensing::is_authorized() {
-        return Err("License required. Please activate a license or start a free trial.".to_string());
+pub fn start_tool(
+    app: AppHandle,
+    state: State<AppState>,
+    tool_id: String,
+) -> Result<String, ToolStartFailure> {
+    check_can_start(&state, &tool_id)?;
+
+    let tool = match tool_id.parse::<ToolId>() {
+        Ok(tool) => tool,
+        Err(e) => {
+            let Some(plugin) = find_plugin(&state, &tool_id) else {
+                return Err(ToolStartFailure::other(&tool_id, e.to_string()));
+            };
+            return launch_plugin(app, &state, plugin);
+        }
+    };
+    launch_tool(app, &state, tool)
+}
+
+/// Refuse to start tools while paused or while another hub manages them. The
+/// license is checked when the tool is spawned (see `ProcessManager::spawn_tool`).
+fn check_can_start(state: &AppState, tool_id: &str) -> Result<(), ToolStartFailure> {
+    if state.config.read().paused {
+        return Err(ToolStartFailure::other(
+            tool_id,
+            "Tools are paused. Resume them to start a tool.",
+        ));
     }
-    
-    let tool = string_to_tool_id(&tool_id).ok_or("Unknown tool")?;
-    
+    if let Some(ref owner) = state.other_hub {
+        return Err(ToolStartFailure::other(
+            tool_id,
+            format!("Another hub ({}) is managing tools.", owner.path.display()),
+        ));
+    }
+    Ok(())
+}
+
+/// Spawn a plugin tool with its stored config and watch its startup in the
+/// background, like `launch_tool`
+fn launch_plugin(
+    app: AppHandle,
+    state: &AppState,
+    plugin: &ToolDescriptor,
+) -> Result<String, ToolStartFailure> {
+    let tool_config = state
+        .config
+        .read()
+        .plugin_tools
+        .get(&plugin.id)
+        .cloned()
+        .unwrap_or_default();
+
+    let spawned = state
+        .process_manager
+        .write()
+        .spawn_plugin(plugin, &tool_config)
+        .map_err(|e| ToolStartFailure::new(&plugin.id, e))?;
+    if !spawned {
+        return Ok("Running".to_string());
+    }
+
+    let grace = tool_config.startup_grace();
+    spawn_startup_watch(app, ManagedTool::Plugin(plugin.id.clone()), grace);
+    Ok("Starting".to_string())
+}
+
+/// Outcome of starting one tool in a batch
+#[derive(Debug, Serialize)]
+pub struct ToolStartResult {
+    /// "Starting" or "Running" on success (see `start_tool`)
+    pub status: Option<String>,
+    pub failure: Option<ToolStartFailure>,
+}
+
+impl From<Result<String, ToolStartFailure>> for ToolStartResult {
+    fn from(result: Result<String, ToolStartFailure>) -> Self {
+        match result {
+            Ok(status) => Self { status: Some(status), failure: None },
+            Err(failure) => Self { status: None, failure: Some(failure) },
+        }
+    }
+}
+
+/// Start several tools with one process manager lock (e.g. an "enable all"
+/// button). A tool that fails doesn't stop the rest; returns the outcome
+/// per tool ID. Startup health checks are reported via events as for
+/// `start_tool`.
+#[tauri::command]
+pub fn start_tools(
+    app: AppHandle,
+    state: State<AppState>,
+    tool_ids: Vec<String>,
+) -> HashMap<String, ToolStartResult> {
+    let mut results = HashMap::new();
+    let config = state.config.read().clone();
+    let mut watches = Vec::new();
+
+    {
+        let mut pm = state.process_manager.write();
+        for tool_id in tool_ids {
+            let result = check_can_start(&state, &tool_id).and_then(|()| {
+                match tool_id.parse::<ToolId>() {
+                    Ok(tool) => {
+                        let tool_config = config.get_tool_config(&tool);
+                        let spawned = pm
+                            .spawn_tool(&tool, &tool_config)
+                            .map_err(|e| ToolStartFailure::new(&tool_id, e))?;
+                        if !spawned {
+                            return Ok("Running".to_string());
+                        }
+                        watches.push((ManagedTool::Builtin(tool), tool_config.startup_grace()));
+                        Ok("Starting".to_string())
+                    }
+                    Err(e) => {
+                        let Some(plugin) = find_plugin(&state, &tool_id) else {
+                            return Err(ToolStartFailure::other(&tool_id, e.to_string()));
+                        };
+                        let tool_config =
+                            config.plugin_tools.get(&plugin.id).cloned().unwrap_or_default();
+                        let spawned = pm
+                            .spawn_plugin(plugin, &tool_config)
+                            .map_err(|e| ToolStartFailure::new(&plugin.id, e))?;
+                        if !spawned {
+                            return Ok("Running".to_string());
+                        }
+                        let grace = tool_config.startup_grace();
+                        watches.push((ManagedTool::Plugin(plugin.id.clone()), grace));
+                        Ok("Starting".to_string())
+                    }
+                }
+            });
+            results.insert(tool_id, result.into());
+        }
+    }
+
+    for (tool, grace) in watches {
+        spawn_startup_watch(app.clone(), tool, grace);
+    }
+    results
+}
+
+/// Restart a tool so config changes (hotkey, voice, ...) take effect.
+///
+/// Waits until the old process is confirmed gone (without holding the process
+/// manager lock), then starts the tool the same way as `start_tool` (returns
+/// "Starting" and reports the outcome via events).
+#[tauri::command]
+pub fn restart_tool(
+    app: AppHandle,
+    state: State<AppState>,
+    tool_id: String,
+) -> Result<String, ToolStartFailure> {
+    check_can_start(&state, &tool_id)?;
+
+    let tool = tool_id
+        .parse::<ToolId>()
+        .map_err(|e| ToolStartFailure::other(&tool_id, e.to_string()))?;
+    // Don't stop a disabled tool only to then refuse to start it again
+    if !state.config.read().get_tool_config(&tool).enabled {
+        let error = ToolStartError::Disabled {
+            tool: tool.display_name().to_string(),
+        };
+        return Err(ToolStartFailure::new(&tool_id, error));
+    }
+    stop_tool_unlocked(&state, &ManagedTool::Builtin(tool.clone()))
+        .map_err(|e| ToolStartFailure::other(&tool_id, e.to_string()))?;
+
+    launch_tool(app, &state, tool)
+}
+
+/// Stop a tool, holding the process manager lock only to signal it and to
+/// record the outcome, not while waiting for it to exit
+fn stop_tool_unlocked(state: &AppState, tool: &ManagedTool) -> anyhow::Result<()> {
+    let pending = state.process_manager.write().begin_stopping(tool);
+    let outcome = pending.wait();
+    state.process_manager.write().finish_stop(outcome)
+}
+
+/// Check that a tool launches: start it with its stored config, wait out the
+/// startup health check, then stop it again. Fails with the captured stderr
+/// if the tool exits during startup. A tool that is already running counts
+/// as a success and is left alone.
+#[tauri::command]
+pub async fn test_tool_launch(app: AppHandle, tool_id: String) -> Result<(), ToolStartFailure> {
+    // Watching the startup blocks, so keep it off the async runtime
+    let id = tool_id.clone();
+    tauri::async_runtime::spawn_blocking(move || test_launch(&app.state::<AppState>(), &tool_id))
+        .await
+        .map_err(|e| ToolStartFailure::other(&id, e.to_string()))?
+}
+
+/// Blocking body of `test_tool_launch`
+fn test_launch(state: &AppState, tool_id: &str) -> Result<(), ToolStartFailure> {
+    check_can_start(state, tool_id)?;
+
+    let tool = tool_id
+        .parse::<ToolId>()
+        .map_err(|e| ToolStartFailure::other(tool_id, e.to_string()))?;
+    let tool_config = state.config.read().get_tool_config(&tool);
+
+    let spawned = state
+        .process_manager
+        .write()
+        .spawn_tool(&tool, &tool_config)
+        .map_err(|e| ToolStartFailure::new(tool_id, e))?;
+    if !spawned {
+        return Ok(());
+    }
+
+    let check = process_manager::watch_startup(tool_config.startup_grace(), || {
+        state.process_manager.write().poll_startup(&tool)
+    });
+    match check {
+        StartupCheck::Pending => {
+            info!("Test launch of {} succeeded, stopping it", tool.display_name());
+            stop_tool_unlocked(state, &ManagedTool::Builtin(tool))
+                .map_err(|e| ToolStartFailure::other(tool_id, e.to_string()))
+        }
+        StartupCheck::Failed(error) => Err(ToolStartFailure::new(tool_id, error)),
+        // Stopped by someone else meanwhile - it did launch
+        StartupCheck::Cancelled => Ok(()),
+    }
+}
+
+/// Spawn a tool with its stored config and watch its startup in the background
+fn launch_tool(app: AppHandle, state: &AppState, tool: ToolId) -> Result<String, ToolStartFailure> {
     // Get the tool's configuration (including hotkey)
     let tool_config = {
         let config = state.config.read();
         config.get_tool_config(&tool)
     };
     
-    let mut pm = state.process_manager.write();
-    pm.start_tool_with_config(&tool, &tool_config).map_err(|e| e.to_string())
+    // Only hold the lock for the spawn itself, not the health check
+    let spawned = {
+        let mut pm = state.process_manager.write();
+        pm.spawn_tool(&tool, &tool_config)
+            .map_err(|e| ToolStartFailure::new(tool.as_str(), e))?
+    };
+    if !spawned {
+        return Ok("Running".to_string());
+    }
+
+    spawn_startup_watch(app, ManagedTool::Builtin(tool), tool_config.startup_grace());
+    Ok("Starting".to_string())
+}
+
+/// Watch a starting tool (built-in or plugin) on a background thread and emit
+/// the outcome
+fn spawn_startup_watch(app: AppHandle, tool: ManagedTool, grace: Duration) {
+    std::thread::spawn(move || {
+        let state = app.state::<AppState>();
+        let check = process_manager::watch_startup(grace, || {
+            state.process_manager.write().poll_starting(&tool)
+        });
+
+        match check {
+            StartupCheck::Pending => {
+                state.process_manager.write().finish_starting(&tool);
+                record_running_tools(&state);
+                let _ = app.emit(
+                    "tool-started",
+                    ToolStartEvent {
+                        tool_id: tool.id().to_string(),
+                    },
+                );
+            }
+            StartupCheck::Failed(error) => {
+                record_running_tools(&state);
+                let _ = app.emit("tool-start-failed", ToolStartFailure::new(tool.id(), error));
+            }
+            StartupCheck::Cancelled => {}
+        }
+    });
+}
+
+/// Persist which tools the hub is running so `restore_session` can bring them
+/// back (tools started outside the hub aren't recorded).
+///
+/// Only called after user-driven starts/stops (not when an exit is merely
+/// observed), so tools killed during an OS shutdown still count as running.
+pub fn record_running_tools(state: &AppState) {
+    let running = state.process_manager.read().running_tools();
+    let mut config = state.config.write();
+    if config.last_running != running {
+        config.last_running = running;
+        state.config_writer.save(&config);
+    }
 }
 
 #[tauri::command]
 pub fn stop_tool(state: State<AppState>, tool_id: String) -> Result<(), String> {
-    let tool = string_to_tool_id(&tool_id).ok_or("Unknown tool")?;
-    let mut pm = state.process_manager.write();
-    pm.stop_tool(&tool).map_err(|e| e.to_string())
+    let tool = if find_plugin(&state, &tool_id).is_some() {
+        ManagedTool::Plugin(tool_id)
+    } else {
+        ManagedTool::Builtin(tool_id.parse::<ToolId>().map_err(|e| e.to_string())?)
+    };
+    stop_tool_unlocked(&state, &tool).map_err(|e| e.to_string())?;
+    record_running_tools(&state);
+    Ok(())
+}
+
+/// Outcome of stopping one tool
+#[derive(Debug, Serialize)]
+pub struct ToolStopResult {
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Stop all running tools, optionally including ones the hub didn't start.
+/// Returns the outcome per tool ID.
+#[tauri::command]
+pub async fn stop_all_tools(
+    app: AppHandle,
+    include_external: bool,
+) -> Result<HashMap<String, ToolStopResult>, String> {
+    // Waiting for the tools to exit blocks, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let results = stop_all_unlocked(&state, include_external);
+        record_running_tools(&state);
+
+        results
+            .into_iter()
+            .map(|(tool_id, result)| {
+                let outcome = ToolStopResult {
+                    success: result.is_ok(),
+                    error: result.err().map(|e| e.to_string()),
+                };
+                (tool_id, outcome)
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Stop every running tool (see `ProcessManager::begin_stop_all`), holding
+/// the process manager lock only to signal them and to record the outcomes,
+/// like `stop_tool_unlocked`
+pub fn stop_all_unlocked(
+    state: &AppState,
+    include_external: bool,
+) -> Vec<(String, anyhow::Result<()>)> {
+    let stops = state.process_manager.write().begin_stop_all(include_external);
+    let outcomes = PendingStop::wait_all(stops);
+    state.process_manager.write().finish_stops(outcomes)
+}
+
+/// Stop several tools, signalling them all with one process manager lock and
+/// then waiting for them together without it. A tool that fails to stop
+/// doesn't stop the rest; returns the outcome per tool ID.
+#[tauri::command]
+pub async fn stop_tools(
+    app: AppHandle,
+    tool_ids: Vec<String>,
+) -> Result<HashMap<String, ToolStopResult>, String> {
+    // Waiting for the tools to exit blocks, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let mut results: Vec<(String, Result<(), String>)> = Vec::new();
+        let mut stopping = Vec::new();
+        let mut stops = Vec::new();
+        {
+            let mut pm = state.process_manager.write();
+            for tool_id in tool_ids {
+                let tool = if find_plugin(&state, &tool_id).is_some() {
+                    ManagedTool::Plugin(tool_id.clone())
+                } else {
+                    match tool_id.parse::<ToolId>() {
+                        Ok(tool) => ManagedTool::Builtin(tool),
+                        Err(e) => {
+                            results.push((tool_id, Err(e.to_string())));
+                            continue;
+                        }
+                    }
+                };
+                stops.push(pm.begin_stopping(&tool));
+                stopping.push(tool_id);
+            }
+        }
+
+        let outcomes = PendingStop::wait_all(stops);
+        {
+            let mut pm = state.process_manager.write();
+            for (tool_id, outcome) in stopping.into_iter().zip(outcomes) {
+                results.push((tool_id, pm.finish_stop(outcome).map_err(|e| e.to_string())));
+            }
+        }
+        record_running_tools(&state);
+
+        results
+            .into_iter()
+            .map(|(tool_id, result)| {
+                let outcome = ToolStopResult {
+                    success: result.is_ok(),
+                    error: result.err(),
+                };
+                (tool_id, outcome)
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Pause or resume all tools, stopping running ones (including external
+/// instances) when pausing with `stop_running`
+pub fn apply_paused(state: &AppState, paused: bool, stop_running: bool) {
+    {
+        let mut config = state.config.write();
+        if config.paused != paused {
+            config.paused = paused;
+            state.config_writer.save(&config);
+        }
+    }
+    info!("Tools {}", if paused { "paused" } else { "resumed" });
+
+    // last_running is left alone so the session can be restored after resuming
+    if paused && stop_running {
+        for (tool_id, result) in stop_all_unlocked(state, true) {
+            if let Err(e) = result {
+                warn!("Failed to stop {}: {}", tool_id, e);
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub fn is_paused(state: State<AppState>) -> bool {
+    state.config.read().paused
+}
+
+/// Pause or resume starting tools (the tray's pause toggle does the same)
+#[tauri::command]
+pub fn set_paused(app: AppHandle, state: State<AppState>, paused: bool, stop_running: bool) {
+    apply_paused(&state, paused, stop_running);
+    crate::refresh_tray_status(&app);
+    let _ = app.emit("paused-changed", paused);
+}
+
+/// The other running hub that manages tools, if this hub isn't the one
+#[tauri::command]
+pub fn get_other_hub(state: State<AppState>) -> Option<LockOwner> {
+    state.other_hub.clone()
+}
+
+/// Where an unreadable config file was moved on startup, if settings were
+/// reset to defaults because of it
+#[tauri::command]
+pub fn get_config_recovery(state: State<AppState>) -> Option<ConfigRecovery> {
+    state.config_recovery.clone()
 }
 
 #[tauri::command]
-pub fn open_tool_settings(state: State<AppState>, tool_id: String) -> Result<(), String> {
-    let tool = string_to_tool_id(&tool_id).ok_or("Unknown tool")?;
+pub fn open_tool_settings(
+    app: AppHandle,
+    state: State<AppState>,
+    tool_id: String,
+) -> Result<(), String> {
+    let tool = tool_id.parse::<ToolId>().map_err(|e| e.to_string())?;
     
     // Only GUI tools (desk-talk, typo-fix) have settings windows
     match tool {
@@ -271,46 +1425,71 @@ pub fn open_tool_settings(state: State<AppState>, tool_id: String) -> Result<(),
         _ => return Err(format!("{} doesn't have a settings window", tool_id)),
     }
     
-    // Get the process manager to find the binary
-    let pm = state.process_manager.read();
-    
     // Find the binary path using the same logic as starting
-    let binary_name = if cfg!(windows) {
-        format!("{}.exe", tool.binary_name())
-    } else {
-        tool.binary_name().to_string()
-    };
-    
-    // Try to find the binary relative to current executable
-    let binary_path = find_tool_binary(&binary_name)
-        .ok_or_else(|| format!("Could not find {} binary", tool_id))?;
+    let tool_directory = state.config.read().tool_directory.clone();
+    let binary_path = SearchRoots::current(tool_directory)
+        .locate(tool.binary_name(), Some(tool.folder_name()))
+        .ok_or_else(|| {
+            format!("{} is not installed ({} not found)", tool.display_name(), tool.binary_name())
+        })?;
     
-    // Launch the binary - if already running, single-instance will bring up the window
-    // If not running, it will start fresh
+    // Launch the binary. If it's already running, the new process hands its
+    // arguments to the running one through the single-instance plugin and
+    // exits; the show-settings flag asks that instance to bring its window
+    // to the front. If not running, it starts fresh with its window open.
+    let already_running = matches!(
+        state.process_manager.read().get_status(&tool),
+        ToolStatus::Running | ToolStatus::Error(_)
+    );
+    let mut command = std::process::Command::new(&binary_path);
+    if already_running {
+        command.arg(SHOW_SETTINGS_ARG);
+    }
+
     #[cfg(windows)]
     {
         use std::os::windows::process::CommandExt;
         const DETACHED_PROCESS: u32 = 0x00000008;
-        
-        std::process::Command::new(&binary_path)
-            .creation_flags(DETACHED_PROCESS)
-            .spawn()
-            .map_err(|e| format!("Failed to launch {}: {}", tool_id, e))?;
+        command.creation_flags(DETACHED_PROCESS);
     }
-    
-    #[cfg(not(windows))]
-    {
-        std::process::Command::new(&binary_path)
-            .spawn()
-            .map_err(|e| format!("Failed to launch {}: {}", tool_id, e))?;
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to launch {}: {}", tool.display_name(), e))?;
+
+    // A launch that fails right away (bad install, missing DLL, ...) would
+    // otherwise look like a click that did nothing
+    let deadline = Instant::now() + SETTINGS_LAUNCH_CHECK;
+    while Instant::now() < deadline {
+        match child.try_wait() {
+            Ok(Some(status)) if !status.success() => {
+                return Err(format!(
+                    "{} exited without opening its settings ({})",
+                    tool.display_name(),
+                    status
+                ));
+            }
+            // Handed off to the running instance
+            Ok(Some(_)) => break,
+            Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            Err(e) => {
+                warn!("Could not check {} settings launch: {}", tool_id, e);
+                break;
+            }
+        }
     }
-    
-    // Drop the lock before returning
-    drop(pm);
-    
+
+    let _ = app.emit("tool-settings-opened", tool.as_str());
     Ok(())
 }
 
+/// Argument that asks an already running GUI tool (via its single-instance
+/// handler) to bring its settings window to the front
+const SHOW_SETTINGS_ARG: &str = "--show-settings";
+
+/// How long `open_tool_settings` watches the launched process for an early failure
+const SETTINGS_LAUNCH_CHECK: Duration = Duration::from_millis(500);
+
 // === DeskTalk parallel config ===
 
 fn desktalk_config_path() -> Result<std::path::PathBuf, String> {
@@ -358,46 +1537,107 @@ pub fn set_desktalk_parallel(value: usize) -> Result<(), String> {
     Ok(())
 }
 
-/// Find a tool binary by name
-fn find_tool_binary(binary_name: &str) -> Option<std::path::PathBuf> {
-    // Try to find relative to current executable (production layout)
-    if let Ok(exe_path) = std::env::current_exe() {
-        if let Some(exe_dir) = exe_path.parent() {
-            // Same directory as hub
-            let path = exe_dir.join(binary_name);
-            if path.exists() {
-                return Some(path);
-            }
+/// Lines returned by `get_hub_log` when the UI doesn't ask for a count
+const DEFAULT_LOG_LINES: usize = 200;
 
-            // In a 'tools' subdirectory (bundled install)
-            let path = exe_dir.join("tools").join(binary_name);
-            if path.exists() {
-                return Some(path);
-            }
+/// The most recent hub log lines (oldest first), for diagnostics in builds
+/// without a console
+#[tauri::command]
+pub fn get_hub_log(lines: Option<usize>) -> Result<Vec<String>, String> {
+    crate::logging::recent_lines(lines.unwrap_or(DEFAULT_LOG_LINES)).map_err(|e| format!("{:#}", e))
+}
 
-            // Check Tauri resource path
-            let path = exe_dir.join("resources").join("tools").join(binary_name);
-            if path.exists() {
-                return Some(path);
-            }
+/// Reveal the hub config folder (config.json, license.json, logs) in the file
+/// manager, creating it if needed. Returns the folder's path.
+#[tauri::command]
+pub fn open_config_dir(app: AppHandle) -> Result<String, String> {
+    use tauri_plugin_opener::OpenerExt;
+
+    let dir = HubConfig::config_dir().map_err(|e| format!("{:#}", e))?;
+    let path = dir.display().to_string();
+    app.opener().open_path(path.clone(), None::<&str>).map_err(|e| {
+        warn!("Failed to open config folder {}: {}", path, e);
+        format!("Could not open the config folder, it is at: {}", path)
+    })?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_tool_is_not_auto_started() {
+        let mut config = HubConfig::default();
+        let enabled = ToolConfig { auto_start: true, ..ToolConfig::default() };
+        let disabled = ToolConfig { enabled: false, ..enabled.clone() };
+        config.set_tool_config(ToolId::FlattenString, enabled);
+        config.set_tool_config(ToolId::OcrPaste, disabled);
+
+        let decisions: HashMap<_, _> =
+            autostart_decisions(&config, true, true).into_iter().collect();
+
+        assert_eq!(decisions[&ToolId::FlattenString], None);
+        assert_eq!(decisions[&ToolId::OcrPaste], Some(AutostartSkipReason::Disabled));
+    }
+
+    fn stored_tool_config() -> ToolConfig {
+        ToolConfig {
+            hotkey: Some("F9".to_string()),
+            voice: Some("nova".to_string()),
+            extra_args: vec!["--verbose".to_string()],
+            settings: serde_json::json!({ "threshold": 0.5 }),
+            ..ToolConfig::default()
         }
     }
 
-    // Try workspace target directories (for development)
-    if let Ok(cwd) = std::env::current_dir() {
-        let paths = [
-            cwd.join("target").join("release").join(binary_name),
-            cwd.join("target").join("debug").join(binary_name),
-            cwd.join("..").join("..").join("target").join("release").join(binary_name),
-            cwd.join("..").join("..").join("target").join("debug").join(binary_name),
-        ];
+    #[test]
+    fn partial_update_keeps_fields_it_leaves_out() {
+        let update: FrontendToolConfig = serde_json::from_str(r#"{"enabled": false}"#).unwrap();
+        let merged = update.merge_into(stored_tool_config());
 
-        for path in &paths {
-            if path.exists() {
-                return Some(path.canonicalize().unwrap_or(path.clone()));
-            }
-        }
+        assert!(!merged.enabled);
+        assert_eq!(merged.hotkey.as_deref(), Some("F9"));
+        assert_eq!(merged.voice.as_deref(), Some("nova"));
+        assert_eq!(merged.extra_args, ["--verbose"]);
+        assert_eq!(merged.settings, serde_json::json!({ "threshold": 0.5 }));
+    }
+
+    #[test]
+    fn explicit_null_clears_a_field() {
+        let update: FrontendToolConfig = serde_json::from_str(r#"{"voice": null}"#).unwrap();
+        let merged = update.merge_into(stored_tool_config());
+
+        assert_eq!(merged.voice, None);
+        assert_eq!(merged.hotkey.as_deref(), Some("F9"));
+        assert_eq!(merged.settings, serde_json::json!({ "threshold": 0.5 }));
     }
 
-    None
+    #[test]
+    fn frontend_config_round_trips_all_tools() {
+        let mut config = HubConfig::default();
+        for (index, tool_id) in ToolId::all().iter().enumerate() {
+            let tool_config = ToolConfig {
+                auto_start: index % 2 == 0,
+                hotkey: Some(format!("F{}", index + 1)),
+                ..ToolConfig::default()
+            };
+            config.set_tool_config(tool_id.clone(), tool_config);
+        }
+
+        let json = serde_json::to_string(&FrontendConfig::from(config.clone())).unwrap();
+        let frontend: FrontendConfig = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(frontend.tools.len(), ToolId::all().len());
+        for (key, tool_config) in frontend.tools {
+            let tool_id: ToolId = key.parse().unwrap();
+            let restored = tool_config.merge_into(ToolConfig::default());
+            assert_eq!(
+                serde_json::to_value(restored).unwrap(),
+                serde_json::to_value(config.get_tool_config(&tool_id)).unwrap(),
+                "{}",
+                key
+            );
+        }
+    }
 }
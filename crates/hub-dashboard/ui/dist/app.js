@@ -86,141 +86,34 @@ const DEFAULT_SPEEDS = {
     'quick-assistant': 1.0,
 };
 
-// All available hotkeys - lowercase kebab-case for clap ValueEnum
-const HOTKEY_OPTIONS = [
-    { value: '', label: '-- Select Key --' },
-    // Function keys
-    { value: 'f1', label: 'F1' },
-    { value: 'f2', label: 'F2' },
-    { value: 'f3', label: 'F3' },
-    { value: 'f4', label: 'F4' },
-    { value: 'f5', label: 'F5' },
-    { value: 'f6', label: 'F6' },
-    { value: 'f7', label: 'F7' },
-    { value: 'f8', label: 'F8' },
-    { value: 'f9', label: 'F9' },
-    { value: 'f10', label: 'F10' },
-    { value: 'f11', label: 'F11' },
-    { value: 'f12', label: 'F12' },
-    { value: 'f13', label: 'F13' },
-    { value: 'f14', label: 'F14' },
-    { value: 'f15', label: 'F15' },
-    { value: 'f16', label: 'F16' },
-    { value: 'f17', label: 'F17' },
-    { value: 'f18', label: 'F18' },
-    { value: 'f19', label: 'F19' },
-    { value: 'f20', label: 'F20' },
-    { value: 'f21', label: 'F21' },
-    { value: 'f22', label: 'F22' },
-    { value: 'f23', label: 'F23' },
-    { value: 'f24', label: 'F24' },
-    // Navigation
-    { value: 'insert', label: 'Insert' },
-    { value: 'delete', label: 'Delete' },
-    { value: 'home', label: 'Home' },
-    { value: 'end', label: 'End' },
-    { value: 'page-up', label: 'Page Up' },
-    { value: 'page-down', label: 'Page Down' },
-    { value: 'up-arrow', label: 'Up Arrow' },
-    { value: 'down-arrow', label: 'Down Arrow' },
-    { value: 'left-arrow', label: 'Left Arrow' },
-    { value: 'right-arrow', label: 'Right Arrow' },
-    // Modifiers
-    { value: 'alt', label: 'Alt' },
-    { value: 'alt-gr', label: 'AltGr' },
-    { value: 'control-left', label: 'Left Ctrl' },
-    { value: 'control-right', label: 'Right Ctrl' },
-    { value: 'shift-left', label: 'Left Shift' },
-    { value: 'shift-right', label: 'Right Shift' },
-    { value: 'meta-left', label: 'Left Win' },
-    { value: 'meta-right', label: 'Right Win' },
-    // Special
-    { value: 'escape', label: 'Escape' },
-    { value: 'tab', label: 'Tab' },
-    { value: 'caps-lock', label: 'Caps Lock' },
-    { value: 'space', label: 'Space' },
-    { value: 'backspace', label: 'Backspace' },
-    { value: 'return', label: 'Enter' },
-    { value: 'print-screen', label: 'Print Screen' },
-    { value: 'scroll-lock', label: 'Scroll Lock' },
-    { value: 'pause', label: 'Pause' },
-    { value: 'num-lock', label: 'Num Lock' },
-    // Number row
-    { value: 'back-quote', label: '` (Backtick)' },
-    { value: 'num1', label: '1' },
-    { value: 'num2', label: '2' },
-    { value: 'num3', label: '3' },
-    { value: 'num4', label: '4' },
-    { value: 'num5', label: '5' },
-    { value: 'num6', label: '6' },
-    { value: 'num7', label: '7' },
-    { value: 'num8', label: '8' },
-    { value: 'num9', label: '9' },
-    { value: 'num0', label: '0' },
-    { value: 'minus', label: '- (Minus)' },
-    { value: 'equal', label: '= (Equal)' },
-    // Letters
-    { value: 'key-q', label: 'Q' },
-    { value: 'key-w', label: 'W' },
-    { value: 'key-e', label: 'E' },
-    { value: 'key-r', label: 'R' },
-    { value: 'key-t', label: 'T' },
-    { value: 'key-y', label: 'Y' },
-    { value: 'key-u', label: 'U' },
-    { value: 'key-i', label: 'I' },
-    { value: 'key-o', label: 'O' },
-    { value: 'key-p', label: 'P' },
-    { value: 'left-bracket', label: '[ (Left Bracket)' },
-    { value: 'right-bracket', label: '] (Right Bracket)' },
-    { value: 'key-a', label: 'A' },
-    { value: 'key-s', label: 'S' },
-    { value: 'key-d', label: 'D' },
-    { value: 'key-f', label: 'F' },
-    { value: 'key-g', label: 'G' },
-    { value: 'key-h', label: 'H' },
-    { value: 'key-j', label: 'J' },
-    { value: 'key-k', label: 'K' },
-    { value: 'key-l', label: 'L' },
-    { value: 'semi-colon', label: '; (Semicolon)' },
-    { value: 'quote', label: "' (Quote)" },
-    { value: 'back-slash', label: '\\ (Backslash)' },
-    { value: 'key-z', label: 'Z' },
-    { value: 'key-x', label: 'X' },
-    { value: 'key-c', label: 'C' },
-    { value: 'key-v', label: 'V' },
-    { value: 'key-b', label: 'B' },
-    { value: 'key-n', label: 'N' },
-    { value: 'key-m', label: 'M' },
-    { value: 'comma', label: ', (Comma)' },
-    { value: 'dot', label: '. (Period)' },
-    { value: 'slash', label: '/ (Slash)' },
-    // Numpad
-    { value: 'kp0', label: 'Numpad 0' },
-    { value: 'kp1', label: 'Numpad 1' },
-    { value: 'kp2', label: 'Numpad 2' },
-    { value: 'kp3', label: 'Numpad 3' },
-    { value: 'kp4', label: 'Numpad 4' },
-    { value: 'kp5', label: 'Numpad 5' },
-    { value: 'kp6', label: 'Numpad 6' },
-    { value: 'kp7', label: 'Numpad 7' },
-    { value: 'kp8', label: 'Numpad 8' },
-    { value: 'kp9', label: 'Numpad 9' },
-    { value: 'kp-return', label: 'Numpad Enter' },
-    { value: 'kp-plus', label: 'Numpad +' },
-    { value: 'kp-minus', label: 'Numpad -' },
-    { value: 'kp-multiply', label: 'Numpad *' },
-    { value: 'kp-divide', label: 'Numpad /' },
-    { value: 'kp-delete', label: 'Numpad Delete' },
-];
+// Hotkey picker options, filled from get_assignable_keys - lowercase
+// kebab-case for clap ValueEnum
+const NO_HOTKEY_OPTION = { value: '', label: '-- Select Key --' };
+let HOTKEY_OPTIONS = [NO_HOTKEY_OPTION];
 
 // State
 let toolStatuses = {};
+let toolInstanceCounts = {}; // Only tools running more than once
+let toolOrigins = {}; // 'Spawned' or 'External' for running tools
+let lastExits = {}; // How stopped tools last exited on their own (crashes)
 let config = {};
 let tauriReady = false;
 let hasApiKey = false;
 let authStatus = null; // License/trial status
+let licenseDetails = null; // Plan, email and timestamps for an active license
+let licenseServerError = null; // Set while the license server can't be reached ({ message, since })
+let paused = false; // Global pause - no tools may start
+let otherHub = null; // Another running hub that manages tools ({ pid, path })
 let desktalkParallel = 1;
 
+const systemDarkQuery = window.matchMedia('(prefers-color-scheme: dark)');
+
+// Theme is 'light', 'dark' or 'system' (follow the OS setting)
+function applyTheme(theme) {
+    const dark = theme === 'system' ? systemDarkQuery.matches : theme !== 'light';
+    document.body.classList.toggle('light-mode', !dark);
+}
+
 function initTauri() {
     if (window.__TAURI_INTERNALS__) {
         invoke = window.__TAURI_INTERNALS__.invoke;
@@ -249,6 +142,7 @@ document.addEventListener('DOMContentLoaded', async () => {
         try {
             await loadConfig();
             await loadAuthStatus();
+            await showConfigRecovery();
             // Re-render with config but still checking
             renderTools();
             renderAutoStartTools();
@@ -277,6 +171,8 @@ document.addEventListener('DOMContentLoaded', async () => {
         
         setupEventListeners();
         setupLicenseEventListeners();
+        setupToolEventListeners();
+        setupTrialNotifications();
         // Fast polling - only checks processes we spawned
         setInterval(loadToolStatuses, 2000);
     }
@@ -305,6 +201,18 @@ window.switchToTab = function(tabId) {
     document.getElementById(tabId)?.classList.add('active');
 }
 
+// Tell the user if their config file couldn't be read and settings were reset
+async function showConfigRecovery() {
+    try {
+        const recovery = await invoke('get_config_recovery');
+        if (!recovery) return;
+        alert(`Your settings file could not be read, so settings were reset to defaults.\n\n` +
+            `${recovery.error}\n\nThe old file was moved to:\n${recovery.backup_path}`);
+    } catch (e) {
+        console.error('Failed to check config recovery:', e);
+    }
+}
+
 async function loadConfig() {
     if (!tauriReady) return;
     try {
@@ -312,14 +220,25 @@ async function loadConfig() {
         
         document.getElementById('autoStart').checked = config.auto_start || false;
         document.getElementById('startMinimized').checked = config.start_minimized || false;
-        document.getElementById('darkMode').checked = config.dark_mode !== false;
-        
-        if (config.dark_mode === false) {
-            document.body.classList.add('light-mode');
-        }
+        document.getElementById('restoreSession').checked = config.restore_session || false;
+        document.getElementById('stopToolsOnExit').checked = config.stop_tools_on_exit || false;
+        document.getElementById('apiProvider').value = config.api_provider || 'open_ai';
+        document.getElementById('apiBaseUrl').value = config.api_base_url || '';
+        document.getElementById('themeMode').value = config.theme || 'dark';
+        document.getElementById('externalScanInterval').value = String(config.external_scan_interval_secs || 0);
+        applyTheme(config.theme || 'dark');
         
         hasApiKey = await invoke('has_api_key');
         updateApiKeyUI();
+        paused = await invoke('is_paused');
+        otherHub = await invoke('get_other_hub');
+        
+        // Keys without a hotkey name (media keys) only work as special keys,
+        // which the picker can't set
+        const keys = await invoke('get_assignable_keys');
+        HOTKEY_OPTIONS = [NO_HOTKEY_OPTION].concat(
+            keys.filter(key => key.hotkey).map(key => ({ value: key.hotkey, label: key.label }))
+        );
         
         try {
             desktalkParallel = await invoke('get_desktalk_parallel');
@@ -346,6 +265,12 @@ async function updateApiKeyUI() {
         } catch (e) {
             maskedSpan.textContent = '••••••••••••••••';
         }
+        try {
+            const secure = await invoke('has_secure_storage');
+            document.getElementById('apiKeyStorageWarning').hidden = secure;
+        } catch (e) {
+            console.error('Failed to check key storage:', e);
+        }
     } else {
         notSetDiv.style.display = 'block';
         isSetDiv.style.display = 'none';
@@ -356,13 +281,57 @@ async function updateApiKeyUI() {
 async function loadToolStatuses() {
     if (!tauriReady) return;
     try {
-        toolStatuses = await invoke('get_tool_statuses');
+        const previous = toolStatuses;
+        const reports = await invoke('get_tool_status_reports');
+        toolStatuses = Object.fromEntries(Object.entries(reports).map(([id, r]) => [id, r.status]));
+        toolOrigins = Object.fromEntries(Object.entries(reports).map(([id, r]) => [id, r.origin]));
+        toolInstanceCounts = await invoke('get_tool_instance_counts');
+        await loadLastExits(previous);
         updateToolCards();
     } catch (e) {
         console.error('Failed to load tool statuses:', e);
     }
 }
 
+// Fetch exit details for tools that stopped since the last poll
+async function loadLastExits(previous) {
+    for (const tool of TOOLS) {
+        const status = toolStatuses[tool.id];
+        if (status !== 'Stopped') {
+            delete lastExits[tool.id];
+        } else if (previous[tool.id] !== 'Stopped') {
+            lastExits[tool.id] = await invoke('get_tool_last_exit', { toolId: tool.id });
+        }
+    }
+}
+
+// "Crashed 2 minutes ago with: <stderr>" for a tool that exited on its own
+function lastExitText(tool) {
+    const exit = lastExits[tool.id];
+    if (!exit) return '';
+    const verb = exit.code === 0 ? 'Exited' : 'Crashed';
+    const when = exit.exited_relative ? ` ${exit.exited_relative}` : '';
+    if (exit.stderr) return `${verb}${when} with: ${exit.stderr}`;
+    return exit.code == null ? `${verb}${when}` : `${verb}${when} (exit code ${exit.code})`;
+}
+
+function updateLastExit(card, tool) {
+    const exitEl = card.querySelector('.last-exit');
+    if (!exitEl) return;
+    const text = lastExitText(tool);
+    exitEl.hidden = !text;
+    exitEl.textContent = text;
+}
+
+// A hung tool ("Not responding") is still running: it can be stopped or restarted
+function isRunningStatus(status) {
+    return status === 'Running' || status === 'Not responding';
+}
+
+function runningClass(status) {
+    return status === 'Running' ? 'running' : 'unresponsive';
+}
+
 function renderTools() {
     const grid = document.getElementById('toolsGrid');
     if (!grid) return;
@@ -383,17 +352,62 @@ function renderTools() {
         grid.appendChild(banner);
     }
     
+    if (paused) {
+        const banner = document.createElement('div');
+        banner.className = 'license-warning-banner paused-banner';
+        banner.innerHTML = `
+            <div class="license-warning-content">
+                <span class="license-warning-icon">⏸️</span>
+                <span class="license-warning-text">All tools are paused. </span>
+                <button class="btn btn-primary btn-small" onclick="setPaused(false)">Resume</button>
+            </div>
+        `;
+        grid.appendChild(banner);
+    }
+    if (licenseServerError) {
+        const since = new Date(licenseServerError.since)
+            .toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' });
+        const banner = document.createElement('div');
+        banner.className = 'license-warning-banner paused-banner';
+        banner.innerHTML = `
+            <div class="license-warning-content">
+                <span class="license-warning-icon">📡</span>
+                <span class="license-warning-text"></span>
+                <button class="btn btn-primary btn-small" onclick="retryLicenseCheck()">Retry</button>
+            </div>
+        `;
+        const text = banner.querySelector('.license-warning-text');
+        text.textContent = `Couldn't reach the license server since ${since}. `;
+        text.title = licenseServerError.message;
+        grid.appendChild(banner);
+    }
+    if (otherHub) {
+        const banner = document.createElement('div');
+        banner.className = 'license-warning-banner paused-banner';
+        banner.innerHTML = `
+            <div class="license-warning-content">
+                <span class="license-warning-icon">⚠️</span>
+                <span class="license-warning-text"></span>
+            </div>
+        `;
+        banner.querySelector('.license-warning-text').textContent =
+            `Another hub is managing tools (${otherHub.path}). Quit it to manage tools here.`;
+        grid.appendChild(banner);
+    }
+    const pauseBtn = document.getElementById('pauseToolsBtn');
+    if (pauseBtn) pauseBtn.style.display = paused ? 'none' : '';
+    
     TOOLS.forEach(tool => {
         const status = toolStatuses[tool.id] || 'Stopped';
-        const isRunning = status === 'Running';
-        const isPending = status === 'Starting...' || status === 'Stopping...' || status === 'Checking...';
+        const isRunning = isRunningStatus(status);
+        const isPending = status === 'Starting...' || status === 'Starting' || status === 'Stopping...' || status === 'Checking...';
         const isChecking = status === 'Checking...';
         const toolConfig = config.tools?.[tool.id] || {};
         const currentHotkey = toolConfig.hotkey || '';
         const needsHotkey = tool.type === 'cli';
         const hasHotkeySet = currentHotkey || tool.type === 'gui';
         const needsApiKeyButMissing = tool.requiresApiKey && !hasApiKey;
-        const canStart = hasHotkeySet && !needsApiKeyButMissing;
+        const canStart = hasHotkeySet && !needsApiKeyButMissing && !paused && !otherHub;
         
         const card = document.createElement('div');
         card.className = `tool-card ${isRunning ? 'running' : ''} ${isPending ? 'pending' : ''} ${isChecking ? 'checking' : ''}`;
@@ -471,7 +485,9 @@ function renderTools() {
         // Determine what's blocking the start
         let blockReason = '';
         if (!isRunning && !isPending) {
-            if (needsApiKeyButMissing) {
+            if (paused) {
+                blockReason = 'Tools are paused';
+            } else if (needsApiKeyButMissing) {
                 blockReason = 'Set API key in Settings first';
             } else if (!hasHotkeySet) {
                 blockReason = 'Select a hotkey first';
@@ -479,7 +495,7 @@ function renderTools() {
         }
         
         // Determine status class
-        const statusClass = isChecking ? 'checking' : (isPending ? 'pending' : (isRunning ? 'running' : 'stopped'));
+        const statusClass = isChecking ? 'checking' : (isPending ? 'pending' : (isRunning ? runningClass(status) : 'stopped'));
         
         // Build button HTML based on state
         let buttonHtml = '';
@@ -490,7 +506,7 @@ function renderTools() {
         if (isChecking) {
             buttonHtml = `<button class="btn btn-checking" disabled><span class="spinner"></span> Checking...</button>`;
         } else if (isPending) {
-            const btnClass = status === 'Starting...' ? 'btn-pending-start' : 'btn-pending-stop';
+            const btnClass = status.startsWith('Starting') ? 'btn-pending-start' : 'btn-pending-stop';
             buttonHtml = `<button class="btn ${btnClass}" disabled><span class="spinner"></span> ${status}</button>`;
         } else {
             buttonHtml = `
@@ -521,7 +537,11 @@ function renderTools() {
                 ${buttonHtml}
             </div>
             ${blockReason ? `<p class="block-reason">${blockReason}</p>` : ''}
+            <p class="tool-origin hint" ${toolOrigins[tool.id] ? '' : 'hidden'}>${originText(tool)}</p>
+            <p class="duplicate-warning" ${toolInstanceCounts[tool.id] ? '' : 'hidden'}>${duplicateWarningText(tool)}</p>
+            <p class="last-exit" hidden></p>
         `;
+        updateLastExit(card, tool);
         
         grid.appendChild(card);
     });
@@ -557,25 +577,38 @@ function renderTools() {
     }
 }
 
+function originText(tool) {
+    switch (toolOrigins[tool.id]) {
+        case 'Spawned': return 'Managed by Hub';
+        case 'External': return 'Running externally - Stop also ends a copy you started yourself';
+        default: return '';
+    }
+}
+
+function duplicateWarningText(tool) {
+    const count = toolInstanceCounts[tool.id];
+    return count ? `⚠️ ${count} copies of ${tool.name} are running. Stop clears them all.` : '';
+}
+
 function updateToolCards() {
     TOOLS.forEach(tool => {
         const card = document.getElementById(`tool-${tool.id}`);
         if (!card) return;
         
         const status = toolStatuses[tool.id] || 'Stopped';
-        const isRunning = status === 'Running';
-        const isPending = status === 'Starting...' || status === 'Stopping...' || status === 'Checking...';
+        const isRunning = isRunningStatus(status);
+        const isPending = status === 'Starting...' || status === 'Starting' || status === 'Stopping...' || status === 'Checking...';
         const isChecking = status === 'Checking...';
         const toolConfig = config.tools?.[tool.id] || {};
         const hasHotkeySet = toolConfig.hotkey || tool.type === 'gui';
         const needsApiKeyButMissing = tool.requiresApiKey && !hasApiKey;
-        const canStart = hasHotkeySet && !needsApiKeyButMissing;
+        const canStart = hasHotkeySet && !needsApiKeyButMissing && !paused && !otherHub;
         
         card.className = `tool-card ${isRunning ? 'running' : ''} ${isPending ? 'pending' : ''} ${isChecking ? 'checking' : ''}`;
         
         const statusEl = card.querySelector('.tool-status');
         if (statusEl) {
-            const statusClass = isChecking ? 'checking' : (isPending ? 'pending' : (isRunning ? 'running' : 'stopped'));
+            const statusClass = isChecking ? 'checking' : (isPending ? 'pending' : (isRunning ? runningClass(status) : 'stopped'));
             statusEl.className = `tool-status ${statusClass}`;
             statusEl.innerHTML = `<span class="status-dot ${(isPending || isChecking) ? 'spinning' : ''}"></span>${status}`;
         }
@@ -587,7 +620,9 @@ function updateToolCards() {
         // Determine what's blocking the start
         let blockReason = '';
         if (!isRunning && !isPending) {
-            if (needsApiKeyButMissing) {
+            if (paused) {
+                blockReason = 'Tools are paused';
+            } else if (needsApiKeyButMissing) {
                 blockReason = 'Set API key in Settings first';
             } else if (!hasHotkeySet) {
                 blockReason = 'Select a hotkey first';
@@ -609,7 +644,7 @@ function updateToolCards() {
                 `;
             } else if (isPending) {
                 // Show pending button with spinner
-                const btnClass = status === 'Starting...' ? 'btn-pending-start' : 'btn-pending-stop';
+                const btnClass = status.startsWith('Starting') ? 'btn-pending-start' : 'btn-pending-stop';
                 actionsEl.innerHTML = `
                     <button class="btn ${btnClass}" disabled>
                         <span class="spinner"></span> ${status}
@@ -627,6 +662,19 @@ function updateToolCards() {
             }
         }
         
+        const originEl = card.querySelector('.tool-origin');
+        if (originEl) {
+            originEl.hidden = !toolOrigins[tool.id];
+            originEl.textContent = originText(tool);
+        }
+        
+        const duplicateEl = card.querySelector('.duplicate-warning');
+        if (duplicateEl) {
+            duplicateEl.hidden = !toolInstanceCounts[tool.id];
+            duplicateEl.textContent = duplicateWarningText(tool);
+        }
+        updateLastExit(card, tool);
+        
         // Disable voice, speed and parallel selects when running or pending
         const voiceSelect = card.querySelector('.voice-select');
         if (voiceSelect) voiceSelect.disabled = isRunning || isPending;
@@ -650,32 +698,38 @@ function updateToolCards() {
     });
 }
 
+// Offer to restart running tools whose launch args changed after a save
+// Non-fatal problems reported by save_config (e.g. an OS-reserved hotkey)
+function showSaveWarnings(warnings) {
+    if (warnings?.length) {
+        alert(`Saved, but note:\n\n${warnings.join('\n')}`);
+    }
+}
+
+async function offerRestart(toolIds) {
+    for (const toolId of toolIds || []) {
+        const name = TOOLS.find(t => t.id === toolId)?.name || toolId;
+        if (!confirm(`${name} is running with its old settings. Restart it now to apply the change?`)) {
+            continue;
+        }
+        try {
+            toolStatuses[toolId] = await invoke('restart_tool', { toolId });
+            updateToolCards();
+        } catch (e) {
+            alert(`Failed to restart ${name}: ${e.message || e}`);
+        }
+    }
+}
+
 async function saveHotkeyForTool(toolId, hotkey) {
     if (!tauriReady) return;
     
     try {
-        // Build updated config
-        const newConfig = {
-            auto_start: config.auto_start || false,
-            start_minimized: config.start_minimized || false,
-            dark_mode: config.dark_mode !== false,
-            tools: {}
-        };
-        
-        TOOLS.forEach(tool => {
-            const existingConfig = config.tools?.[tool.id] || {};
-            newConfig.tools[tool.id] = {
-                enabled: true,
-                auto_start: existingConfig.auto_start || false,
-                hotkey: tool.id === toolId ? (hotkey || null) : (existingConfig.hotkey || null),
-                special_hotkey: existingConfig.special_hotkey || null,
-                voice: existingConfig.voice || null,
-                speech_speed: existingConfig.speech_speed || null
-            };
-        });
-        
-        await invoke('save_config', { config: newConfig });
-        config = newConfig;
+        const result = await invoke('set_tool_hotkey', { toolId, hotkey: hotkey || null });
+        config.tools = config.tools || {};
+        config.tools[toolId] = { ...(config.tools[toolId] || {}), hotkey: hotkey || null };
+        showSaveWarnings(result.warning ? [result.warning] : []);
+        await offerRestart(result.needs_restart ? [toolId] : []);
         
         // Update button state
         updateToolCards();
@@ -683,6 +737,8 @@ async function saveHotkeyForTool(toolId, hotkey) {
     } catch (e) {
         console.error('Failed to save hotkey:', e);
         alert(`Failed to save hotkey: ${e}`);
+        // Put the select back to the stored hotkey
+        renderTools();
     }
 }
 
@@ -694,7 +750,13 @@ async function saveVoiceForTool(toolId, voice) {
         const newConfig = {
             auto_start: config.auto_start || false,
             start_minimized: config.start_minimized || false,
-            dark_mode: config.dark_mode !== false,
+            theme: config.theme || 'dark',
+            restore_session: config.restore_session || false,
+            stop_tools_on_exit: config.stop_tools_on_exit || false,
+            tool_directory: config.tool_directory || null,
+            external_scan_interval_secs: config.external_scan_interval_secs ?? null,
+            api_provider: config.api_provider || 'open_ai',
+            api_base_url: config.api_base_url || null,
             tools: {}
         };
         
@@ -710,8 +772,10 @@ async function saveVoiceForTool(toolId, voice) {
             };
         });
         
-        await invoke('save_config', { config: newConfig });
+        const saved = await invoke('save_config', { config: newConfig });
         config = newConfig;
+        showSaveWarnings(saved.warnings);
+        await offerRestart(saved.needs_restart);
         
         console.log(`Saved voice ${voice} for ${toolId}`);
     } catch (e) {
@@ -728,7 +792,13 @@ async function saveSpeedForTool(toolId, speed) {
         const newConfig = {
             auto_start: config.auto_start || false,
             start_minimized: config.start_minimized || false,
-            dark_mode: config.dark_mode !== false,
+            theme: config.theme || 'dark',
+            restore_session: config.restore_session || false,
+            stop_tools_on_exit: config.stop_tools_on_exit || false,
+            tool_directory: config.tool_directory || null,
+            external_scan_interval_secs: config.external_scan_interval_secs ?? null,
+            api_provider: config.api_provider || 'open_ai',
+            api_base_url: config.api_base_url || null,
             tools: {}
         };
         
@@ -744,8 +814,10 @@ async function saveSpeedForTool(toolId, speed) {
             };
         });
         
-        await invoke('save_config', { config: newConfig });
+        const saved = await invoke('save_config', { config: newConfig });
         config = newConfig;
+        showSaveWarnings(saved.warnings);
+        await offerRestart(saved.needs_restart);
         
         console.log(`Saved speed ${speed} for ${toolId}`);
     } catch (e) {
@@ -774,14 +846,71 @@ function renderAutoStartTools() {
     
     TOOLS.forEach(tool => {
         const toolConfig = config.tools?.[tool.id] || {};
-        const label = document.createElement('label');
-        label.className = 'checkbox';
-        label.innerHTML = `
-            <input type="checkbox" id="autoStart-${tool.id}" ${toolConfig.auto_start ? 'checked' : ''}>
-            <span>${tool.name}</span>
+        const row = document.createElement('div');
+        row.className = 'autostart-row';
+        row.innerHTML = `
+            <label class="checkbox">
+                <input type="checkbox" id="autoStart-${tool.id}" ${toolConfig.auto_start ? 'checked' : ''}>
+                <span>${tool.name}</span>
+            </label>
+            <button class="btn btn-secondary btn-small" onclick="testToolLaunch('${tool.id}', this)">Test</button>
+            <span class="status"></span>
         `;
-        container.appendChild(label);
+        container.appendChild(row);
     });
+    renderAutostartPreview();
+}
+
+// Start a tool, check it survives startup, then stop it again
+window.testToolLaunch = async function(toolId, button) {
+    if (!tauriReady) return;
+    const status = button.nextElementSibling;
+    button.disabled = true;
+    status.textContent = 'Testing...';
+    status.className = 'status';
+    try {
+        await invoke('test_tool_launch', { toolId });
+        status.textContent = 'Launches OK';
+        status.className = 'status success';
+    } catch (e) {
+        status.textContent = e?.message || `${e}`;
+        status.className = 'status error';
+    } finally {
+        button.disabled = false;
+    }
+};
+
+const AUTOSTART_SKIP_REASONS = {
+    NotAuthorized: 'license required',
+    Paused: 'paused',
+    Disabled: 'disabled',
+    AutoStartOff: 'auto-start off',
+    MissingApiKey: 'needs API key',
+};
+
+// Summarize which tools will start when Hub launches
+async function renderAutostartPreview() {
+    const container = document.getElementById('autoStartPreview');
+    if (!container || !tauriReady) return;
+
+    try {
+        const decisions = await invoke('preview_autostart');
+        const toolName = (id) => TOOLS.find(t => t.id === id)?.name || id;
+        const starting = decisions.filter(d => d.will_start).map(d => toolName(d.tool_id));
+        const blocked = decisions
+            .filter(d => !d.will_start && d.reason !== 'AutoStartOff')
+            .map(d => `${toolName(d.tool_id)} (${AUTOSTART_SKIP_REASONS[d.reason] || d.reason})`);
+
+        let text = starting.length
+            ? `On launch: ${starting.join(', ')} will start.`
+            : 'On launch: no tools will start.';
+        if (blocked.length) {
+            text += ` Skipped: ${blocked.join(', ')}.`;
+        }
+        container.textContent = text;
+    } catch (e) {
+        console.error('Failed to preview auto-start:', e);
+    }
 }
 
 function setupEventListeners() {
@@ -837,6 +966,12 @@ function setupEventListeners() {
         }
     });
     
+    // Testing a typed key doesn't save it
+    document.getElementById('testApiKeyBtn')?.addEventListener('click', () => {
+        testApiKey(document.getElementById('apiKey')?.value.trim() || null);
+    });
+    document.getElementById('testStoredApiKeyBtn')?.addEventListener('click', () => testApiKey(null));
+    
     document.getElementById('deleteApiKeyBtn')?.addEventListener('click', async () => {
         const status = document.getElementById('apiKeyStatus');
         if (!confirm('Are you sure you want to delete the API key?')) {
@@ -856,8 +991,11 @@ function setupEventListeners() {
         }
     });
     
-    document.getElementById('darkMode')?.addEventListener('change', (e) => {
-        document.body.classList.toggle('light-mode', !e.target.checked);
+    document.getElementById('themeMode')?.addEventListener('change', (e) => {
+        applyTheme(e.target.value);
+    });
+    systemDarkQuery.addEventListener('change', () => {
+        applyTheme(document.getElementById('themeMode')?.value || config.theme || 'dark');
     });
     
     document.getElementById('saveSettingsBtn')?.addEventListener('click', async () => {
@@ -866,7 +1004,13 @@ function setupEventListeners() {
             const newConfig = {
                 auto_start: document.getElementById('autoStart')?.checked || false,
                 start_minimized: document.getElementById('startMinimized')?.checked || false,
-                dark_mode: document.getElementById('darkMode')?.checked !== false,
+                theme: document.getElementById('themeMode')?.value || 'dark',
+                restore_session: document.getElementById('restoreSession')?.checked || false,
+                stop_tools_on_exit: document.getElementById('stopToolsOnExit')?.checked || false,
+                tool_directory: config.tool_directory || null,
+                external_scan_interval_secs: parseInt(document.getElementById('externalScanInterval')?.value) || null,
+                api_provider: document.getElementById('apiProvider')?.value || 'open_ai',
+                api_base_url: document.getElementById('apiBaseUrl')?.value.trim() || null,
                 tools: {}
             };
             
@@ -882,16 +1026,86 @@ function setupEventListeners() {
                 };
             });
             
-            await invoke('save_config', { config: newConfig });
+            const saved = await invoke('save_config', { config: newConfig });
             config = newConfig;
             status.textContent = 'Settings saved!';
             status.className = 'status success';
+            showSaveWarnings(saved.warnings);
+            renderAutostartPreview();
+        } catch (e) {
+            status.textContent = `Error: ${e}`;
+            status.className = 'status error';
+        }
+    });
+    
+    // Settings export/import
+    document.getElementById('exportConfigBtn')?.addEventListener('click', async () => {
+        const status = document.getElementById('configTransferStatus');
+        const path = document.getElementById('configTransferPath').value.trim();
+        if (!path) {
+            status.textContent = 'Please enter a file path';
+            status.className = 'status error';
+            return;
+        }
+        try {
+            await invoke('export_config', { path });
+            status.textContent = 'Settings exported!';
+            status.className = 'status success';
         } catch (e) {
             status.textContent = `Error: ${e}`;
             status.className = 'status error';
         }
     });
     
+    document.getElementById('importConfigBtn')?.addEventListener('click', async () => {
+        const status = document.getElementById('configTransferStatus');
+        const path = document.getElementById('configTransferPath').value.trim();
+        if (!path) {
+            status.textContent = 'Please enter a file path';
+            status.className = 'status error';
+            return;
+        }
+        if (!confirm('Replace your current settings with the imported ones?')) {
+            return;
+        }
+        try {
+            const result = await invoke('import_config', { path });
+            await loadConfig();
+            renderTools();
+            renderAutoStartTools();
+            status.textContent = result.exported_with_api_key && !result.has_api_key
+                ? 'Settings imported! Add your OpenAI API key to use AI tools.'
+                : 'Settings imported!';
+            status.className = 'status success';
+        } catch (e) {
+            status.textContent = `Error: ${e}`;
+            status.className = 'status error';
+        }
+    });
+    
+    document.getElementById('showLogBtn')?.addEventListener('click', async () => {
+        const logView = document.getElementById('hubLog');
+        try {
+            const lines = await invoke('get_hub_log', { lines: 200 });
+            logView.textContent = lines.length ? lines.join('\n') : 'The log is empty.';
+        } catch (e) {
+            logView.textContent = `Could not read the log: ${e}`;
+        }
+        logView.hidden = false;
+        logView.scrollTop = logView.scrollHeight;
+    });
+    
+    document.getElementById('openConfigDirBtn')?.addEventListener('click', async () => {
+        const pathHint = document.getElementById('configDirPath');
+        try {
+            const path = await invoke('open_config_dir');
+            pathHint.textContent = path;
+        } catch (e) {
+            pathHint.textContent = `${e}`;
+        }
+        pathHint.hidden = false;
+    });
+    
     // OpenAI API key help links
     document.getElementById('openaiApiKeysLink')?.addEventListener('click', async (e) => {
         e.preventDefault();
@@ -927,20 +1141,134 @@ window.startTool = async function(toolId) {
     toolStatuses[toolId] = 'Starting...';
     updateToolCards();
     
-    // Use setTimeout to let the UI update before the blocking call
-    setTimeout(async () => {
-        try {
-            await invoke('start_tool', { toolId });
-            toolStatuses[toolId] = 'Running';
-            updateToolCards();
-        } catch (e) {
-            toolStatuses[toolId] = 'Stopped';
-            updateToolCards();
-            alert(`Failed to start tool: ${e}`);
+    // Returns immediately; the health check result arrives as an event
+    try {
+        toolStatuses[toolId] = await invoke('start_tool', { toolId });
+        updateToolCards();
+    } catch (e) {
+        toolStatuses[toolId] = 'Stopped';
+        updateToolCards();
+        if (e.error?.kind === 'MissingApiKey') {
+            switchToTab('settings');
+            document.getElementById('apiKey')?.focus();
+        } else if (e.auth_prompt) {
+            // StartTrial / Purchase / Renew are all handled on the License tab
+            switchToTab('license');
         }
-    }, 10);
+        alert(`Failed to start tool: ${startFailureText(e)}`);
+    }
+};
+
+// Start failure message, with the searched paths when the binary is missing
+function startFailureText(failure) {
+    const message = failure.message || failure;
+    if (failure.error?.kind !== 'BinaryNotFound') return message;
+    return `${message}\n\nPlace the tool's binary in one of:\n${failure.error.searched.join('\n')}`;
+}
+
+// Manual "Scan" button: report what changed so the user isn't left guessing
+window.scanExternalTools = async function() {
+    const status = document.getElementById('scanStatus');
+    try {
+        const diff = await invoke('scan_external_processes');
+        await loadToolStatuses();
+        showScanResult(diff, true);
+    } catch (e) {
+        status.textContent = `Scan failed: ${e}`;
+        status.className = 'status error';
+    }
+};
+
+window.setPaused = async function(value) {
+    if (value && !confirm('Pause all tools? Running tools will be stopped and none can start until you resume.')) {
+        return;
+    }
+    try {
+        await invoke('set_paused', { paused: value, stopRunning: true });
+    } catch (e) {
+        alert(`Failed to ${value ? 'pause' : 'resume'} tools: ${e}`);
+    }
 };
 
+async function applyPausedState(value) {
+    paused = value;
+    await loadToolStatuses();
+    renderTools();
+    renderAutostartPreview();
+}
+
+function showScanResult(diff, manual) {
+    const status = document.getElementById('scanStatus');
+    if (!status) return;
+    const names = ids => ids.map(id => TOOLS.find(t => t.id === id)?.name || id).join(', ');
+    const parts = [];
+    if (diff.detected.length) parts.push(`Found ${names(diff.detected)} running externally`);
+    if (diff.disappeared.length) parts.push(`${names(diff.disappeared)} no longer running`);
+    if (!parts.length && !manual) return;
+    status.textContent = parts.length ? parts.join('. ') : 'No changes found';
+    status.className = 'status success';
+}
+
+function setupToolEventListeners() {
+    const listen = window.__TAURI__?.event?.listen;
+    if (!listen) return;
+
+    // Pause toggles come from the tray as well as the dashboard
+    listen('paused-changed', (event) => applyPausedState(event.payload));
+
+    listen('external-scan', async (event) => {
+        await loadToolStatuses();
+        showScanResult(event.payload, false);
+    });
+
+    listen('tool-started', (event) => {
+        toolStatuses[event.payload.tool_id] = 'Running';
+        updateToolCards();
+    });
+
+    listen('tool-start-failed', (event) => {
+        toolStatuses[event.payload.tool_id] = 'Stopped';
+        updateToolCards();
+        alert(`Failed to start tool: ${startFailureText(event.payload)}`);
+    });
+}
+
+// Trial countdown notifications: checked on load, then pushed daily by the backend
+async function setupTrialNotifications() {
+    window.__TAURI__?.event?.listen('trial-notification', (event) => {
+        showTrialNotification(event.payload);
+    });
+
+    try {
+        const notification = await invoke('check_trial_notifications');
+        if (notification) showTrialNotification(notification);
+    } catch (e) {
+        console.error('Failed to check trial notifications:', e);
+    }
+}
+
+async function showTrialNotification(notification) {
+    await loadAuthStatus();
+    renderLicenseTab();
+    updateToolCards();
+
+    if ('Notification' in window) {
+        if (Notification.permission === 'default') {
+            await Notification.requestPermission();
+        }
+        if (Notification.permission === 'granted') {
+            new Notification(notification.title, { body: notification.message });
+        }
+    }
+
+    if (notification.milestone === 'expired') {
+        switchToTab('license');
+        if (confirm(`${notification.message}\n\nOpen the purchase page now?`)) {
+            await openCheckout('monthly');
+        }
+    }
+}
+
 window.stopTool = async function(toolId) {
     if (!tauriReady) return;
     
@@ -983,6 +1311,69 @@ async function loadAuthStatus() {
         console.error('Failed to load auth status:', e);
         authStatus = { type: 'NoLicense' };
     }
+    await loadLicenseDetails();
+    try {
+        licenseServerError = await invoke('get_last_license_error');
+    } catch (e) {
+        console.error('Failed to load license server error:', e);
+    }
+}
+
+// Check the license again after the server couldn't be reached
+window.retryLicenseCheck = async function() {
+    if (!tauriReady) return;
+    try {
+        await invoke('validate_license');
+    } catch (e) {
+        console.error('License check failed:', e);
+    }
+    await loadAuthStatus();
+    renderLicenseTab();
+    renderTools();
+};
+
+async function loadLicenseDetails() {
+    if (!tauriReady) return;
+    try {
+        licenseDetails = await invoke('get_license_details');
+    } catch (e) {
+        console.error('Failed to load license details:', e);
+        licenseDetails = null;
+    }
+}
+
+function licenseDetailsHtml() {
+    if (!licenseDetails) return '';
+    const rows = [];
+    if (licenseDetails.customer_email) {
+        rows.push(`Licensed to ${licenseDetails.customer_email}`);
+    }
+    if (licenseDetails.expires_relative) {
+        rows.push(`Expires ${licenseDetails.expires_relative}`);
+    } else if (!licenseDetails.expires_at) {
+        rows.push('Never expires');
+    }
+    if (licenseDetails.offline) {
+        rows.push('Activated offline');
+    } else if (licenseDetails.last_validated_relative) {
+        rows.push(`Last checked ${licenseDetails.last_validated_relative}`);
+    }
+    return rows.map(row => `<div class="license-detail">${row}</div>`).join('');
+}
+
+// "3d 4h remaining", or minutes once under an hour is left
+function trialCountdownText(status) {
+    if (status.days_remaining === 0 && status.hours_remaining === 0) {
+        return `${status.minutes_remaining}m remaining`;
+    }
+    return `${status.days_remaining}d ${status.hours_remaining}h remaining`;
+}
+
+// "2 of 5 devices activated" / "Unlimited devices" (empty when unknown, e.g. offline tokens)
+function activationSlotsText(result) {
+    if (result.activations_unlimited) return 'Unlimited devices.';
+    if (result.activations_used == null || result.activations_limit == null) return '';
+    return `${result.activations_used} of ${result.activations_limit} devices activated.`;
 }
 
 function renderLicenseTab() {
@@ -992,6 +1383,7 @@ function renderLicenseTab() {
     const activateSection = document.getElementById('activateLicenseSection');
     const buySection = document.getElementById('buySection');
     const deactivateSection = document.getElementById('deactivateSection');
+    const trialExtensionSection = document.getElementById('trialExtensionSection');
     
     if (!authStatus) {
         statusContent.innerHTML = '<p>Loading...</p>';
@@ -1000,6 +1392,7 @@ function renderLicenseTab() {
     
     // Reset classes
     statusSection.classList.remove('licensed', 'trial', 'expired');
+    trialExtensionSection.style.display = authStatus.type === 'Trial' ? 'block' : 'none';
     
     switch (authStatus.type) {
         case 'Licensed':
@@ -1009,6 +1402,7 @@ function renderLicenseTab() {
                 <div class="license-status-title">Licensed - ${authStatus.plan}</div>
                 <div class="license-status-subtitle">All features unlocked</div>
                 <div class="license-key-display">Key: ${authStatus.key_preview}</div>
+                ${licenseDetailsHtml()}
             `;
             trialSection.style.display = 'none';
             activateSection.style.display = 'none';
@@ -1021,7 +1415,7 @@ function renderLicenseTab() {
             statusContent.innerHTML = `
                 <div class="license-status-icon">⏱️</div>
                 <div class="license-status-title">Free Trial</div>
-                <div class="trial-countdown">${authStatus.days_remaining}d ${authStatus.hours_remaining}h remaining</div>
+                <div class="trial-countdown">${trialCountdownText(authStatus)}</div>
                 <div class="license-status-subtitle">All features unlocked during trial</div>
             `;
             trialSection.style.display = 'none';
@@ -1030,6 +1424,22 @@ function renderLicenseTab() {
             deactivateSection.style.display = 'none';
             break;
             
+        case 'GracePeriod': {
+            const days = authStatus.days_remaining;
+            statusSection.classList.add('expired');
+            statusContent.innerHTML = `
+                <div class="license-status-icon">⏳</div>
+                <div class="license-status-title">Trial Expired</div>
+                <div class="trial-countdown">${days} day${days === 1 ? '' : 's'} of grace left</div>
+                <div class="license-status-subtitle">Purchase a license to keep using all features</div>
+            `;
+            trialSection.style.display = 'none';
+            activateSection.style.display = 'block';
+            buySection.style.display = 'block';
+            deactivateSection.style.display = 'none';
+            break;
+        }
+            
         case 'TrialExpired':
             statusSection.classList.add('expired');
             statusContent.innerHTML = `
@@ -1059,6 +1469,41 @@ function renderLicenseTab() {
 }
 
 function setupLicenseEventListeners() {
+    // The backend re-validates a stored license on launch
+    window.__TAURI__?.event?.listen('license-status-changed', (event) => {
+        authStatus = event.payload;
+        licenseServerError = null;
+        renderLicenseTab();
+        renderTools();
+    });
+
+    // The launch check couldn't reach the server; tools run on the stored status
+    window.__TAURI__?.event?.listen('license-server-unreachable', (event) => {
+        licenseServerError = event.payload;
+        renderTools();
+    });
+
+    // The server no longer accepts the license (refunded, disabled, ...)
+    window.__TAURI__?.event?.listen('license-rejected', (event) => {
+        switchToTab('license');
+        alert(`Your license was disabled: ${event.payload}`);
+    });
+
+    // A license removed while offline still couldn't be released from the server
+    window.__TAURI__?.event?.listen('deactivation-stuck', async (event) => {
+        const stop = confirm(
+            `A license removed from this machine still couldn't be released from your account after ${event.payload} attempts. ` +
+            'You can release the device from your LemonSqueezy orders page instead.\n\nStop retrying?'
+        );
+        if (stop) {
+            try {
+                await invoke('dismiss_pending_deactivation');
+            } catch (e) {
+                console.error('Failed to dismiss pending deactivation:', e);
+            }
+        }
+    });
+
     // Start trial button
     document.getElementById('startTrialBtn')?.addEventListener('click', async () => {
         try {
@@ -1070,6 +1515,31 @@ function setupLicenseEventListeners() {
         }
     });
     
+    // Redeem promo code button
+    document.getElementById('applyTrialExtensionBtn')?.addEventListener('click', async () => {
+        const codeInput = document.getElementById('trialExtensionInput');
+        const status = document.getElementById('trialExtensionStatus');
+        const code = codeInput.value.trim();
+        
+        if (!code) {
+            status.textContent = 'Please enter a promo code';
+            status.className = 'status error';
+            return;
+        }
+        
+        try {
+            const trial = await invoke('apply_trial_extension', { code });
+            status.textContent = `Trial extended - ${trial.days_remaining} days remaining`;
+            status.className = 'status success';
+            codeInput.value = '';
+            await loadAuthStatus();
+            renderLicenseTab();
+        } catch (e) {
+            status.textContent = `${e}`;
+            status.className = 'status error';
+        }
+    });
+    
     // Activate license button
     document.getElementById('activateLicenseBtn')?.addEventListener('click', async () => {
         const keyInput = document.getElementById('licenseKeyInput');
@@ -1086,13 +1556,20 @@ function setupLicenseEventListeners() {
         status.className = 'status';
         
         try {
-            const result = await invoke('activate_license', { licenseKey: key });
+            // Offline tokens are "<claims>.<signature>"; license keys never contain dots
+            const result = key.includes('.')
+                ? await invoke('activate_offline_token', { token: key }).then(() => ({ success: true }))
+                : await invoke('activate_license', { licenseKey: key });
             if (result.success) {
-                status.textContent = 'License activated!';
+                status.textContent = `License activated! ${activationSlotsText(result)}`.trim();
                 status.className = 'status success';
                 keyInput.value = '';
                 await loadAuthStatus();
                 renderLicenseTab();
+            } else if (result.activation_limit_reached) {
+                status.textContent = result.error;
+                status.className = 'status error';
+                showDeactivateAll(key);
             } else {
                 status.textContent = result.error || 'Activation failed';
                 status.className = 'status error';
@@ -1110,15 +1587,110 @@ function setupLicenseEventListeners() {
         }
         
         try {
-            await invoke('deactivate_license');
+            await invoke('deactivate_license', { force: false });
+        } catch (e) {
+            const force = confirm(
+                `Failed to deactivate: ${e}\n\nRemove the license from this machine anyway? ` +
+                'It will be released from your account the next time the hub can reach the license server.'
+            );
+            if (!force) {
+                return;
+            }
+            try {
+                await invoke('deactivate_license', { force: true });
+            } catch (e) {
+                alert(`Failed to deactivate: ${e}`);
+                return;
+            }
+        }
+        await loadAuthStatus();
+        renderLicenseTab();
+    });
+
+    document.getElementById('deactivateAllBtn')?.addEventListener('click', async () => {
+        const input = document.getElementById('deactivateAllKeyInput');
+        const licenseKey = input?.value.trim();
+        if (!licenseKey) {
+            alert('Enter the license key to deactivate everywhere.');
+            return;
+        }
+        if (!confirm('Deactivate this license on every machine, including this one? Each machine will need to activate it again.')) {
+            return;
+        }
+        
+        try {
+            const result = await invoke('deactivate_all_license_instances', { licenseKey });
+            let message = `Deactivated ${result.deactivated} machine(s).`;
+            if (result.failures.length > 0) {
+                message += `\n\nCould not deactivate ${result.failures.length}:\n` +
+                    result.failures.map(f => `${f.instance_id}: ${f.error}`).join('\n');
+            }
+            if (result.remaining_activations) {
+                message += `\n\n${result.remaining_activations} activation(s) the hub doesn't know about remain. ` +
+                    'Release them from your LemonSqueezy account.';
+            }
+            alert(message);
+            input.value = '';
+            if (result.cleared_local) {
+                await loadAuthStatus();
+                renderLicenseTab();
+            }
+        } catch (e) {
+            alert(`Failed to deactivate: ${e}`);
+        }
+    });
+
+    document.getElementById('resetMachineIdBtn')?.addEventListener('click', async () => {
+        const hadLicense = authStatus?.type === 'Licensed';
+        const warning = hadLicense
+            ? 'Reset this machine\'s ID? Your license will be deactivated here and must be activated again, which uses a new activation slot.'
+            : 'Reset this machine\'s ID?';
+        if (!confirm(warning)) {
+            return;
+        }
+        
+        try {
+            await invoke('reset_machine_id');
             await loadAuthStatus();
             renderLicenseTab();
+            if (hadLicense) {
+                alert('Machine ID reset. Enter your license key to activate it again.');
+            }
         } catch (e) {
-            alert(`Failed to deactivate: ${e}`);
+            alert(`Failed to reset machine ID: ${e}`);
         }
     });
 }
 
+// Point the user at freeing activation slots, with the key filled in
+function showDeactivateAll(licenseKey) {
+    const input = document.getElementById('deactivateAllKeyInput');
+    if (!input) return;
+    input.value = licenseKey;
+    input.scrollIntoView({ behavior: 'smooth', block: 'center' });
+    input.focus();
+}
+
+// Make a real request to the API provider with the key (the stored one if null)
+async function testApiKey(key) {
+    const status = document.getElementById('apiKeyStatus');
+    status.textContent = 'Testing connection...';
+    status.className = 'status';
+    try {
+        const result = await invoke('test_api_key', { key });
+        if (result.usable) {
+            status.textContent = `Connection OK (${result.latency_ms} ms)`;
+            status.className = 'status success';
+        } else {
+            status.textContent = result.error;
+            status.className = 'status error';
+        }
+    } catch (e) {
+        status.textContent = `Error: ${e}`;
+        status.className = 'status error';
+    }
+}
+
 // Open checkout URL
 window.openCheckout = async function(plan) {
     if (!tauriReady) return;
@@ -1128,6 +1700,19 @@ window.openCheckout = async function(plan) {
         await invoke('open_checkout', { plan });
     } catch (e) {
         console.error('Failed to open checkout:', e);
-        alert('Failed to open checkout page. Please visit: slking.lemonsqueezy.com');
+        showCopyableLink(e);
     }
 };
+
+// Show the URL from an open_checkout error ("Could not open browser, visit: <url>")
+// in a prompt so it can be copied
+function showCopyableLink(error) {
+    const message = `${error}`;
+    const marker = 'visit: ';
+    const index = message.indexOf(marker);
+    if (index === -1) {
+        alert(`Failed to open link: ${message}`);
+        return;
+    }
+    prompt('Could not open your browser. Copy this link:', message.slice(index + marker.length));
+}
@@ -0,0 +1,135 @@
+//! Append-only audit trail of license activity on this machine
+//!
+//! Every activation, validation and deactivation appends one JSON line to
+//! `license-audit.log` in the config dir, so organizations can see what
+//! happened to a machine's license. Keys are always masked, and the customer
+//! email is masked unless `LicenseConfig::audit_customer_email` is set.
+
+// Entries are only written by the `remote` license actions
+#![cfg_attr(not(feature = "remote"), allow(dead_code))]
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::config::LicenseConfig;
+use crate::error::{LicenseError, Result};
+
+const AUDIT_FILE_NAME: &str = "license-audit.log";
+
+/// What was done to the license
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Activate,
+    Validate,
+    Deactivate,
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub action: AuditAction,
+    /// Masked license key
+    pub key_preview: Option<String>,
+    pub instance_id: Option<String>,
+    /// Masked unless `audit_customer_email` is set
+    pub customer_email: Option<String>,
+    pub success: bool,
+    /// Why the action failed or was refused
+    pub error: Option<String>,
+}
+
+/// The audit log file path
+pub fn audit_path() -> Result<PathBuf> {
+    Ok(LicenseConfig::config_dir()?.join(AUDIT_FILE_NAME))
+}
+
+/// Append an entry. Failing to write the log never fails the action itself,
+/// so errors are only logged.
+pub(crate) fn record(
+    action: AuditAction,
+    license_key: Option<&str>,
+    instance_id: Option<&str>,
+    customer_email: Option<&str>,
+    outcome: std::result::Result<(), String>,
+) {
+    let full_email = LicenseConfig::load().is_ok_and(|config| config.audit_customer_email);
+    let entry = AuditEntry {
+        timestamp: Utc::now(),
+        action,
+        key_preview: license_key.map(crate::mask_secret),
+        instance_id: instance_id.map(str::to_string),
+        customer_email: customer_email.map(|email| {
+            if full_email {
+                email.to_string()
+            } else {
+                mask_email(email)
+            }
+        }),
+        success: outcome.is_ok(),
+        error: outcome.err(),
+    };
+    if let Err(e) = append(&entry) {
+        warn!("Failed to write license audit log: {:#}", e);
+    }
+}
+
+fn append(entry: &AuditEntry) -> Result<()> {
+    let path = audit_path()?;
+    let mut line = serde_json::to_string(entry).map_err(LicenseError::parse("audit entry"))?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(LicenseError::io(format!("Failed to append to {}", path.display())))
+}
+
+/// The most recent `limit` entries, oldest first. Lines that don't parse
+/// (e.g. from a newer version) are skipped.
+pub fn recent_entries(limit: usize) -> Result<Vec<AuditEntry>> {
+    let path = audit_path()?;
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(LicenseError::io(format!("Failed to read {}", path.display()))(e))
+        }
+    };
+    let entries: Vec<AuditEntry> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    let skip = entries.len().saturating_sub(limit);
+    Ok(entries.into_iter().skip(skip).collect())
+}
+
+/// Instance IDs the log has recorded for `license_key`. Entries only keep
+/// the masked key, so another key with the same preview may contribute some.
+pub(crate) fn instances_seen(license_key: &str) -> Vec<String> {
+    let preview = crate::mask_secret(license_key);
+    recent_entries(usize::MAX)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| entry.key_preview.as_deref() == Some(preview.as_str()))
+        .filter_map(|entry| entry.instance_id)
+        .collect()
+}
+
+/// "j•••@example.com": enough to tell accounts apart without recording who
+fn mask_email(email: &str) -> String {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            let first: String = local.chars().take(1).collect();
+            format!("{}•••@{}", first, domain)
+        }
+        None => crate::mask_secret(email),
+    }
+}
@@ -1,11 +1,25 @@
 //! License configuration storage
 
-use anyhow::{Context, Result};
+use anyhow::Context;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Once, OnceLock},
+};
+use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::error::{LicenseError, Result};
+use crate::trial::{TrialMilestone, TrialVerification};
+
+/// In-memory license config, used when the config directory is unusable
+static FALLBACK: OnceLock<LicenseConfig> = OnceLock::new();
+
+/// Files that lived in the license-only directory of earlier versions
+const LEGACY_FILES: [&str; 2] = ["license.json", "license-audit.log"];
+
 /// License plan types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -27,7 +41,7 @@ impl std::fmt::Display for LicensePlan {
 }
 
 /// Stored license configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LicenseConfig {
     /// The license key (if activated)
     pub license_key: Option<String>,
@@ -44,6 +58,12 @@ pub struct LicenseConfig {
     /// Machine ID (UUID, generated once per install)
     pub machine_id: String,
     
+    /// Hardware fingerprint recorded alongside `machine_id` (see
+    /// `machine_fingerprint`). A mismatch on load means the config was copied
+    /// from another machine.
+    #[serde(default)]
+    pub fingerprint_hash: Option<String>,
+    
     /// Whether trial has been started (one-time per machine)
     #[serde(default)]
     pub trial_started: bool,
@@ -51,20 +71,104 @@ pub struct LicenseConfig {
     /// Trial expiration timestamp (RFC3339)
     pub trial_expiration: Option<String>,
     
+    /// Whether the trial expiration came from the trial server
+    #[serde(default)]
+    pub trial_verification: TrialVerification,
+    
+    /// Trial countdown notifications already shown
+    #[serde(default)]
+    pub trial_notifications_shown: Vec<TrialMilestone>,
+    
+    /// Promo codes already redeemed on this machine (normalized)
+    #[serde(default)]
+    pub used_extension_codes: Vec<String>,
+    
     /// Last successful validation timestamp
     pub last_validated: Option<String>,
     
     /// Customer email (from LemonSqueezy)
     pub customer_email: Option<String>,
+    
+    /// License expiration timestamp (RFC3339), `None` for licenses that never expire
+    #[serde(default)]
+    pub license_expires_at: Option<String>,
+    
+    /// Signed offline license token (air-gapped activation instead of LemonSqueezy)
+    #[serde(default)]
+    pub offline_token: Option<String>,
+    
+    /// Instance that was removed locally by a forced deactivation but is still
+    /// registered with LemonSqueezy
+    #[serde(default)]
+    pub pending_deactivation: Option<PendingDeactivation>,
+    
+    /// Record the full customer email in the license audit log instead of a
+    /// masked one
+    #[serde(default)]
+    pub audit_customer_email: bool,
+
+    /// Days after the trial ends that are reported as `AuthStatus::GracePeriod`
+    /// rather than `TrialExpired` (0 = none), capped at `MAX_GRACE_DAYS`
+    #[serde(default)]
+    pub post_trial_grace_days: u32,
+}
+
+/// Server-side deactivation still owed for a license cleared while offline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingDeactivation {
+    pub license_key: String,
+    pub instance_id: String,
+    /// Failed retries so far (see `MAX_DEACTIVATION_ATTEMPTS`)
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+/// Retries of a pending deactivation before the hub stops trying and asks
+/// the user to release the device from their LemonSqueezy account instead
+pub const MAX_DEACTIVATION_ATTEMPTS: u32 = 5;
+
+impl PendingDeactivation {
+    /// Out of retries
+    pub fn is_stuck(&self) -> bool {
+        self.attempts >= MAX_DEACTIVATION_ATTEMPTS
+    }
+}
+
+impl Default for LicenseConfig {
+    fn default() -> Self {
+        Self {
+            license_key: None,
+            license_plan: None,
+            license_status: None,
+            instance_id: None,
+            machine_id: String::new(),
+            fingerprint_hash: None,
+            trial_started: false,
+            trial_expiration: None,
+            trial_verification: TrialVerification::Local,
+            trial_notifications_shown: Vec::new(),
+            used_extension_codes: Vec::new(),
+            last_validated: None,
+            customer_email: None,
+            license_expires_at: None,
+            offline_token: None,
+            pending_deactivation: None,
+            audit_customer_email: false,
+            post_trial_grace_days: 0,
+        }
+    }
 }
 
 impl LicenseConfig {
-    /// Get the configuration directory path
+    /// Get the configuration directory path, shared with the hub's main
+    /// config (see `hub_common::app_dirs`)
     pub fn config_dir() -> Result<PathBuf> {
-        let proj_dirs = ProjectDirs::from("com", "slking", "productivity-hub")
-            .context("Failed to determine project directories")?;
-        let config_dir = proj_dirs.config_dir().to_path_buf();
-        fs::create_dir_all(&config_dir)?;
+        let config_dir = hub_common::app_dirs()?;
+        // An overridden directory is kept isolated from the user's real files
+        if hub_common::config::config_dir_override().is_none() {
+            static MIGRATED: Once = Once::new();
+            MIGRATED.call_once(|| migrate_legacy_dir(&config_dir));
+        }
         Ok(config_dir)
     }
 
@@ -73,20 +177,58 @@ impl LicenseConfig {
         Ok(Self::config_dir()?.join("license.json"))
     }
 
-    /// Load configuration from disk
+    /// Load configuration from disk.
+    ///
+    /// If the config directory can't be created or written, an in-memory
+    /// config is used instead (see `is_read_only`).
     pub fn load() -> Result<Self> {
-        let config_path = Self::config_path()?;
+        if let Some(fallback) = FALLBACK.get() {
+            return Ok(fallback.clone());
+        }
+        let config_path = match Self::config_path() {
+            Ok(path) => path,
+            Err(e) => return Ok(Self::fallback(&e)),
+        };
 
         if config_path.exists() {
             let contents = fs::read_to_string(&config_path)
-                .context("Failed to read license config file")?;
+                .map_err(LicenseError::io("Failed to read license config file"))?;
             let mut config: LicenseConfig = serde_json::from_str(&contents)
-                .context("Failed to parse license config file")?;
+                .map_err(LicenseError::parse("license config file"))?;
             
+            let fingerprint = machine_fingerprint();
+            let mut changed = false;
+
             // Ensure machine_id exists
             if config.machine_id.is_empty() {
                 config.machine_id = Uuid::new_v4().to_string();
-                config.save()?;
+                changed = true;
+            }
+
+            match config.fingerprint_hash {
+                // Configs from before fingerprinting: adopt this machine
+                None => {
+                    config.fingerprint_hash = Some(fingerprint);
+                    changed = true;
+                }
+                // Recorded by an earlier version that hashed the hostname too
+                Some(ref stored) if *stored == legacy_machine_fingerprint() => {
+                    config.fingerprint_hash = Some(fingerprint);
+                    changed = true;
+                }
+                Some(ref stored) if *stored != fingerprint => {
+                    config.reset_cloned_identity(fingerprint);
+                    changed = true;
+                }
+                Some(_) => {}
+            }
+
+            // Best effort: a read-only directory mustn't cost the user their
+            // license, the update is retried on the next load
+            if changed {
+                if let Err(e) = config.save() {
+                    warn!("Could not save the updated license config: {:#}", e);
+                }
             }
             
             Ok(config)
@@ -94,20 +236,67 @@ impl LicenseConfig {
             // Create new config with fresh machine ID
             let config = LicenseConfig {
                 machine_id: Uuid::new_v4().to_string(),
+                fingerprint_hash: Some(machine_fingerprint()),
                 ..Default::default()
             };
-            config.save()?;
+            if let Err(e) = config.save() {
+                return Ok(Self::fallback(&e));
+            }
             Ok(config)
         }
     }
 
+    /// The config was copied from another machine: give this one its own
+    /// identity and drop the copied activation so it has to be activated (and
+    /// validated) again instead of silently sharing the original's instance.
+    /// Offline tokens aren't tied to a machine and are kept.
+    fn reset_cloned_identity(&mut self, fingerprint: String) {
+        warn!("Hardware fingerprint changed, treating license config as copied from elsewhere");
+        self.machine_id = Uuid::new_v4().to_string();
+        self.fingerprint_hash = Some(fingerprint);
+        self.instance_id = None;
+        if self.offline_token.is_none() {
+            self.license_status = None;
+            self.last_validated = None;
+        }
+    }
+
+    /// Switch to the in-memory config (read-only mode) after a storage error
+    fn fallback(error: &LicenseError) -> Self {
+        FALLBACK
+            .get_or_init(|| {
+                warn!(
+                    "License storage unavailable, running in read-only mode (no trial): {:#}",
+                    error
+                );
+                LicenseConfig {
+                    machine_id: Uuid::new_v4().to_string(),
+                    ..Default::default()
+                }
+            })
+            .clone()
+    }
+
+    /// Whether license storage is unavailable and nothing can be persisted
+    /// (licenses can't be activated and trials can't be started)
+    pub fn is_read_only() -> bool {
+        FALLBACK.get().is_some()
+    }
+
     /// Save configuration to disk
     pub fn save(&self) -> Result<()> {
+        if Self::is_read_only() {
+            return Err(anyhow::anyhow!(
+                "License storage is unavailable (read-only mode). \
+                 Check that the app's config directory is writable."
+            )
+            .into());
+        }
         let config_path = Self::config_path()?;
         let contents = serde_json::to_string_pretty(self)
             .context("Failed to serialize license config")?;
-        fs::write(&config_path, contents)
-            .context("Failed to write license config file")?;
+        hub_common::config::write_with_retry(&config_path, &contents)
+            .map_err(LicenseError::io("Failed to write license config file"))?;
         Ok(())
     }
 
@@ -119,13 +308,275 @@ impl LicenseConfig {
         self.instance_id = None;
         self.last_validated = None;
         self.customer_email = None;
+        self.license_expires_at = None;
+        self.offline_token = None;
         self.save()
     }
 
-    /// Get machine name for activation
+    /// The ID servers that track this machine (trial and promo servers) see:
+    /// the hardware fingerprint, which stays the same when `license.json` is
+    /// deleted and `machine_id` regenerated.
+    ///
+    /// Without an OS machine ID every machine has the same fingerprint, so
+    /// `machine_id` is used instead; it doesn't survive a reset, but doesn't
+    /// lump all such machines into one either.
+    pub fn hardware_id(&self) -> String {
+        match os_machine_id() {
+            Some(_) => self.fingerprint_hash.clone().unwrap_or_else(machine_fingerprint),
+            None => machine_id_fingerprint(&self.machine_id),
+        }
+    }
+
+    /// Get machine name for activation (the hostname, cleaned up to be a
+    /// valid LemonSqueezy `instance_name`)
     pub fn get_machine_name() -> String {
-        hostname::get()
-            .map(|h| h.to_string_lossy().to_string())
-            .unwrap_or_else(|_| "Unknown".to_string())
+        sanitize_instance_name(&raw_hostname())
+    }
+}
+
+/// Move license files out of the directory earlier versions used (organization
+/// "slking" instead of "hub"; the same path on Linux). Files already present
+/// in `config_dir` win.
+fn migrate_legacy_dir(config_dir: &Path) {
+    let Some(legacy) = ProjectDirs::from("com", "slking", "productivity-hub") else {
+        return;
+    };
+    move_legacy_files(legacy.config_dir(), config_dir);
+}
+
+/// Move `LEGACY_FILES` from `legacy_dir` to `config_dir`, keeping any that
+/// already exist there
+fn move_legacy_files(legacy_dir: &Path, config_dir: &Path) {
+    if legacy_dir == config_dir {
+        return;
+    }
+    for name in LEGACY_FILES {
+        let from = legacy_dir.join(name);
+        let to = config_dir.join(name);
+        if !from.exists() || to.exists() {
+            continue;
+        }
+        // Renaming fails across drives, where copying still works
+        let moved = fs::rename(&from, &to)
+            .or_else(|_| fs::copy(&from, &to).and_then(|_| fs::remove_file(&from)));
+        match moved {
+            Ok(()) => info!("Moved {} to {}", from.display(), to.display()),
+            Err(e) => warn!("Failed to move {} to {}: {}", from.display(), to.display(), e),
+        }
+    }
+}
+
+/// SHA-256 over the OS and its machine ID (see `os_machine_id`). Stable
+/// across restarts and hostname changes, different on a cloned VM once it's
+/// re-provisioned. Without a machine ID clones can't be told apart.
+fn machine_fingerprint() -> String {
+    let mut input = std::env::consts::OS.to_string();
+    if let Some(os_id) = os_machine_id() {
+        input.push('|');
+        input.push_str(&os_id);
+    }
+    sha256_hex(&input)
+}
+
+/// `hardware_id` for machines without an OS machine ID: SHA-256 over the OS
+/// and the hub's own `machine_id`
+fn machine_id_fingerprint(machine_id: &str) -> String {
+    sha256_hex(&format!("{}|hub|{}", std::env::consts::OS, machine_id))
+}
+
+/// The fingerprint earlier versions recorded: the OS, hostname and Linux
+/// machine ID
+fn legacy_machine_fingerprint() -> String {
+    let mut input = format!("{}|{}", std::env::consts::OS, raw_hostname());
+    #[cfg(target_os = "linux")]
+    if let Some(os_id) = os_machine_id() {
+        input.push('|');
+        input.push_str(&os_id);
+    }
+    sha256_hex(&input)
+}
+
+fn sha256_hex(input: &str) -> String {
+    ring::digest::digest(&ring::digest::SHA256, input.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn raw_hostname() -> String {
+    hostname::get()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "Unknown".to_string())
+}
+
+/// Longest `instance_name` sent to LemonSqueezy, in characters
+const MAX_INSTANCE_NAME_LEN: usize = 64;
+
+/// Make a machine name safe to send as an activation `instance_name`: keep
+/// letters, digits and `-_. `, collapse runs of whitespace and truncate to
+/// `MAX_INSTANCE_NAME_LEN`. Falls back to "Unknown" if nothing is left.
+fn sanitize_instance_name(name: &str) -> String {
+    let kept: String = name
+        .chars()
+        .map(|c| if c.is_whitespace() { ' ' } else { c })
+        .filter(|&c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ' '))
+        .collect();
+    let name: String = kept
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(MAX_INSTANCE_NAME_LEN)
+        .collect();
+
+    match name.trim_end() {
+        "" => "Unknown".to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// The ID the OS generates at install time: the systemd/dbus machine ID on
+/// Linux, `MachineGuid` on Windows, `IOPlatformUUID` on macOS
+#[cfg(not(any(windows, target_os = "macos")))]
+fn os_machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+#[cfg(windows)]
+fn os_machine_id() -> Option<String> {
+    use winreg::enums::{HKEY_LOCAL_MACHINE, KEY_READ, KEY_WOW64_64KEY};
+    use winreg::RegKey;
+
+    // The 64-bit view, so 32-bit builds read the same value
+    let key = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey_with_flags(
+            "SOFTWARE\\Microsoft\\Cryptography",
+            KEY_READ | KEY_WOW64_64KEY,
+        )
+        .ok()?;
+    let guid: String = key.get_value("MachineGuid").ok()?;
+    Some(guid.trim().to_string()).filter(|id| !id.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn os_machine_id() -> Option<String> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()?;
+    // `"IOPlatformUUID" = "XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX"`
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("\"IOPlatformUUID\""))
+        .and_then(|line| line.split('=').nth(1))
+        .map(|id| id.trim().trim_matches('"').to_string())
+        .filter(|id| !id.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Serializes tests that point `HUB_CONFIG_DIR` somewhere
+    static CONFIG_DIR_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Run `test` with the config dir set to `dir`
+    fn with_config_dir<T>(dir: &Path, test: impl FnOnce() -> T) -> T {
+        let _guard = CONFIG_DIR_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        std::env::set_var(hub_common::config::CONFIG_DIR_ENV, dir);
+        let result = test();
+        std::env::remove_var(hub_common::config::CONFIG_DIR_ENV);
+        result
+    }
+
+    #[test]
+    fn license_and_hub_configs_share_a_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let (license_dir, hub_dir) = with_config_dir(dir.path(), || {
+            (LicenseConfig::config_dir().unwrap(), hub_common::HubConfig::config_dir().unwrap())
+        });
+
+        assert_eq!(license_dir, hub_dir);
+        assert_eq!(license_dir, dir.path());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_directory_keeps_the_license() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        // Written before fingerprints existed, so loading wants to save it
+        let contents =
+            r#"{"machine_id": "id-1", "license_key": "KEY-1234", "license_status": "active"}"#;
+        fs::write(dir.path().join("license.json"), contents).unwrap();
+        fs::set_permissions(dir.path(), fs::Permissions::from_mode(0o555)).unwrap();
+        fs::set_permissions(dir.path().join("license.json"), fs::Permissions::from_mode(0o444))
+            .unwrap();
+
+        // Permissions don't stop root, so there's nothing to check then
+        let writable = fs::write(dir.path().join("license.json"), contents).is_ok();
+        let config = (!writable).then(|| with_config_dir(dir.path(), LicenseConfig::load));
+        fs::set_permissions(dir.path(), fs::Permissions::from_mode(0o755)).unwrap();
+
+        if let Some(config) = config {
+            let config = config.unwrap();
+            assert_eq!(config.license_key.as_deref(), Some("KEY-1234"));
+            assert_eq!(config.license_status.as_deref(), Some("active"));
+            assert!(config.fingerprint_hash.is_some());
+        }
+    }
+
+    #[test]
+    fn machines_without_an_os_id_are_told_apart_by_machine_id() {
+        let first = machine_id_fingerprint("machine-1");
+        assert_ne!(first, machine_id_fingerprint("machine-2"));
+        assert_eq!(first, machine_id_fingerprint("machine-1"));
+        // Not the fingerprint every such machine shares
+        assert_ne!(first, sha256_hex(std::env::consts::OS));
+    }
+
+    #[test]
+    fn legacy_license_files_move_to_the_shared_directory() {
+        let legacy = tempfile::tempdir().unwrap();
+        let shared = tempfile::tempdir().unwrap();
+        fs::write(legacy.path().join("license.json"), "{\"license_key\": \"OLD\"}").unwrap();
+        fs::write(legacy.path().join("license-audit.log"), "old log").unwrap();
+        fs::write(shared.path().join("license-audit.log"), "new log").unwrap();
+
+        move_legacy_files(legacy.path(), shared.path());
+
+        let license = fs::read_to_string(shared.path().join("license.json")).unwrap();
+        assert_eq!(license, "{\"license_key\": \"OLD\"}");
+        assert!(!legacy.path().join("license.json").exists());
+        // A file already in the shared directory wins
+        let log = fs::read_to_string(shared.path().join("license-audit.log")).unwrap();
+        assert_eq!(log, "new log");
+        assert!(legacy.path().join("license-audit.log").exists());
+    }
+
+    #[test]
+    fn overlong_instance_names_are_truncated() {
+        let name = sanitize_instance_name(&"workstation-".repeat(20));
+        assert_eq!(name.chars().count(), MAX_INSTANCE_NAME_LEN);
+        assert!(name.starts_with("workstation-workstation-"));
+    }
+
+    #[test]
+    fn emoji_and_control_chars_are_stripped() {
+        assert_eq!(sanitize_instance_name("Alex’s 💻 Laptop\u{7}\n(home)"), "Alexs Laptop home");
+        assert_eq!(sanitize_instance_name("büro-pc_2.local"), "büro-pc_2.local");
+    }
+
+    #[test]
+    fn names_with_nothing_left_fall_back() {
+        assert_eq!(sanitize_instance_name("💻🖥️"), "Unknown");
+        assert_eq!(sanitize_instance_name("  \t "), "Unknown");
     }
 }
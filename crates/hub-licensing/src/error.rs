@@ -0,0 +1,135 @@
+//! Error type for the licensing API
+//!
+//! Failures callers may want to react to (offline, rate limited, bad key, ...)
+//! have their own variant; everything else is `Other` with a message meant
+//! for the user.
+
+use std::fmt::Display;
+
+use thiserror::Error;
+
+/// Result of the public licensing functions
+pub type Result<T, E = LicenseError> = std::result::Result<T, E>;
+
+#[derive(Debug, Error)]
+pub enum LicenseError {
+    /// A license, trial or promo server couldn't be reached
+    #[cfg(feature = "remote")]
+    #[error("Failed to connect to {server}: {source}")]
+    Network {
+        server: &'static str,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    /// The server asked us to slow down (HTTP 429)
+    #[error("Too many requests, try again {}", retry_hint(*.retry_after_secs))]
+    RateLimited { retry_after_secs: Option<u64> },
+
+    /// The server failed to handle the request (HTTP 5xx)
+    #[error("{server} is having problems (HTTP {status}), try again later")]
+    ServerError { server: &'static str, status: u16 },
+
+    /// Malformed license key or offline token
+    #[error("{0}")]
+    InvalidKey(String),
+
+    /// The key is already activated on as many machines as it allows
+    #[error(
+        "This license key has reached its activation limit{}. Deactivate it on a machine \
+         you no longer use to activate it here.",
+        limit_hint(*.limit)
+    )]
+    ActivationLimitReached { limit: Option<u32> },
+
+    /// The server knows the key but it's no longer usable (`expired`, `disabled`)
+    #[error(
+        "This license is {status}. Renew it from your LemonSqueezy account, then activate it \
+         again."
+    )]
+    LicenseInactive { status: String },
+
+    /// The operation needs a license and none is configured
+    #[error("No license key configured")]
+    NoLicense,
+
+    /// Reading or writing license storage failed
+    #[error("{context}: {source}")]
+    Io {
+        context: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// A stored value or server response couldn't be parsed
+    #[error("Failed to parse {what}: {reason}")]
+    Parse { what: &'static str, reason: String },
+
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl LicenseError {
+    /// The server couldn't be reached or couldn't answer right now, as
+    /// opposed to rejecting the request
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            #[cfg(feature = "remote")]
+            LicenseError::Network { .. } => true,
+            LicenseError::RateLimited { .. } | LicenseError::ServerError { .. } => true,
+            _ => false,
+        }
+    }
+
+    #[cfg(feature = "remote")]
+    pub(crate) fn network(server: &'static str) -> impl FnOnce(reqwest::Error) -> Self {
+        move |source| Self::Network { server, source }
+    }
+
+    pub(crate) fn io(context: impl Into<String>) -> impl FnOnce(std::io::Error) -> Self {
+        let context = context.into();
+        move |source| Self::Io { context, source }
+    }
+
+    pub(crate) fn parse<E: Display>(what: &'static str) -> impl FnOnce(E) -> Self {
+        move |e| Self::Parse { what, reason: e.to_string() }
+    }
+}
+
+fn retry_hint(retry_after_secs: Option<u64>) -> String {
+    match retry_after_secs {
+        Some(secs) => format!("in {} seconds", secs),
+        None => "later".to_string(),
+    }
+}
+
+fn limit_hint(limit: Option<u32>) -> String {
+    match limit {
+        Some(1) => " (1 machine)".to_string(),
+        Some(limit) => format!(" ({} machines)", limit),
+        None => String::new(),
+    }
+}
+
+/// Map throttling and server failures to their variants. Other statuses are
+/// left for the caller, since LemonSqueezy reports rejected keys as 4xx
+/// responses with a JSON body.
+#[cfg(feature = "remote")]
+pub(crate) fn check_status(
+    server: &'static str,
+    response: reqwest::Response,
+) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after_secs = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok());
+        return Err(LicenseError::RateLimited { retry_after_secs });
+    }
+    if status.is_server_error() {
+        return Err(LicenseError::ServerError { server, status: status.as_u16() });
+    }
+    Ok(response)
+}
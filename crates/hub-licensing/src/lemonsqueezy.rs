@@ -1,21 +1,93 @@
 //! LemonSqueezy API client for license validation and activation
 
-use anyhow::{Context, Result};
+use std::sync::Mutex;
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use uuid::Uuid;
 
-use crate::config::{LicenseConfig, LicensePlan};
+use crate::audit::{self, AuditAction};
+use crate::config::{LicenseConfig, LicensePlan, PendingDeactivation, MAX_DEACTIVATION_ATTEMPTS};
+use crate::error::{check_status, LicenseError, Result};
+use crate::offline::{
+    check_offline_expiry, decode_offline_claims, invalid_token, offline_license_info,
+    offline_public_key,
+};
+use crate::LicenseInfo;
 
 const API_BASE: &str = "https://api.lemonsqueezy.com/v1/licenses";
 
+/// Server name used in connection errors
+const API_NAME: &str = "LemonSqueezy API";
+
+/// Set while the license server can't be reached; see `last_network_error`
+static LAST_NETWORK_ERROR: Mutex<Option<NetworkErrorRecord>> = Mutex::new(None);
+
+/// Storefront, used when no specific checkout applies
+pub const STORE_URL: &str = "https://slking.lemonsqueezy.com";
+
+/// Subscription checkout (monthly and yearly variants are on the same page)
+pub const SUBSCRIPTION_CHECKOUT_URL: &str =
+    "https://slking.lemonsqueezy.com/checkout/buy/e84ca54b-c009-4262-a434-2528592e4077";
+
+/// Lifetime product checkout
+pub const LIFETIME_CHECKOUT_URL: &str =
+    "https://slking.lemonsqueezy.com/checkout/buy/346b4776-f424-4c23-8980-227233e240cb";
+
+/// Build the checkout URL for a plan ("monthly", "yearly" or "lifetime"),
+/// optionally prefilling the customer's email and a discount code.
+///
+/// Unknown plans get the storefront URL (without params).
+pub fn checkout_url(plan: &str, email: Option<&str>, discount: Option<&str>) -> String {
+    let base = match plan {
+        "monthly" | "yearly" => SUBSCRIPTION_CHECKOUT_URL,
+        "lifetime" => LIFETIME_CHECKOUT_URL,
+        _ => return STORE_URL.to_string(),
+    };
+
+    let params: Vec<(&str, &str)> = [
+        ("checkout[email]", email),
+        ("checkout[discount_code]", discount),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| {
+        value
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(|v| (name, v))
+    })
+    .collect();
+
+    if params.is_empty() {
+        return base.to_string();
+    }
+    reqwest::Url::parse_with_params(base, &params)
+        .map(String::from)
+        .unwrap_or_else(|_| base.to_string())
+}
+
 /// LemonSqueezy API client
 pub struct LemonSqueezyClient {
     client: reqwest::Client,
+    api_base: String,
 }
 
 impl LemonSqueezyClient {
     pub fn new() -> Self {
         Self {
             client: reqwest::Client::new(),
+            api_base: API_BASE.to_string(),
+        }
+    }
+
+    /// A client talking to a stand-in for the license API
+    #[cfg(test)]
+    fn with_api_base(api_base: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_base,
         }
     }
 
@@ -26,18 +98,12 @@ impl LemonSqueezyClient {
             form.push(("instance_id", id.to_string()));
         }
 
-        let response = self.client
-            .post(format!("{}/validate", API_BASE))
-            .header("Accept", "application/json")
-            .form(&form)
-            .send()
-            .await
-            .context("Failed to connect to LemonSqueezy API")?;
-
-        let result: ApiValidateResponse = response
+        let result: ApiValidateResponse = self
+            .post("validate", &form)
+            .await?
             .json()
             .await
-            .context("Failed to parse LemonSqueezy response")?;
+            .map_err(LicenseError::parse("LemonSqueezy response"))?;
 
         Ok(ValidationResult {
             valid: result.valid,
@@ -71,18 +137,12 @@ impl LemonSqueezyClient {
             ("instance_name", instance_name.to_string()),
         ];
 
-        let response = self.client
-            .post(format!("{}/activate", API_BASE))
-            .header("Accept", "application/json")
-            .form(&form)
-            .send()
-            .await
-            .context("Failed to connect to LemonSqueezy API")?;
-
-        let result: ApiActivateResponse = response
+        let result: ApiActivateResponse = self
+            .post("activate", &form)
+            .await?
             .json()
             .await
-            .context("Failed to parse LemonSqueezy activation response")?;
+            .map_err(LicenseError::parse("LemonSqueezy activation response"))?;
 
         Ok(ActivationResult {
             activated: result.activated,
@@ -116,20 +176,66 @@ impl LemonSqueezyClient {
             ("instance_id", instance_id.to_string()),
         ];
 
-        let response = self.client
-            .post(format!("{}/deactivate", API_BASE))
+        let result: ApiDeactivateResponse = self
+            .post("deactivate", &form)
+            .await?
+            .json()
+            .await
+            .map_err(LicenseError::parse("LemonSqueezy deactivation response"))?;
+
+        Ok(result.deactivated)
+    }
+
+    /// POST a form to a license API endpoint, keeping `last_network_error`
+    /// up to date
+    async fn post(&self, endpoint: &str, form: &[(&str, String)]) -> Result<reqwest::Response> {
+        let result = match self
+            .client
+            .post(format!("{}/{}", self.api_base, endpoint))
             .header("Accept", "application/json")
-            .form(&form)
+            .form(form)
             .send()
             .await
-            .context("Failed to connect to LemonSqueezy API")?;
+        {
+            Ok(response) => check_status(API_NAME, response),
+            Err(e) => Err(LicenseError::network(API_NAME)(e)),
+        };
+        record_outcome(&result);
+        result
+    }
+}
 
-        let result: ApiDeactivateResponse = response
-            .json()
-            .await
-            .context("Failed to parse LemonSqueezy deactivation response")?;
+/// The license server couldn't be reached (or couldn't answer) on the most
+/// recent requests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkErrorRecord {
+    /// Error of the most recent failed request
+    pub message: String,
+    /// First failure since the server last answered
+    pub since: DateTime<Utc>,
+    /// Most recent failure
+    pub last_attempt: DateTime<Utc>,
+}
 
-        Ok(result.deactivated)
+/// Why the license server has been unreachable, if it was on the last
+/// request. Cleared by the next request it answers.
+pub fn last_network_error() -> Option<NetworkErrorRecord> {
+    LAST_NETWORK_ERROR.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn record_outcome<T>(result: &Result<T>) {
+    let mut last = LAST_NETWORK_ERROR.lock().unwrap_or_else(|e| e.into_inner());
+    match result {
+        Err(e) if e.is_retryable() => {
+            let now = Utc::now();
+            *last = Some(NetworkErrorRecord {
+                message: e.to_string(),
+                since: last.as_ref().map_or(now, |record| record.since),
+                last_attempt: now,
+            });
+        }
+        // Anything else came from a server that answered
+        _ => *last = None,
     }
 }
 
@@ -150,6 +256,22 @@ pub struct ValidationResult {
     pub meta: Option<LicenseMeta>,
 }
 
+impl ValidationResult {
+    /// Why the server rejected the license (refunded, disabled, expired,
+    /// unknown instance...), or `None` if it's valid
+    pub fn rejection_reason(&self) -> Option<String> {
+        if self.valid {
+            return None;
+        }
+        let status = self.license_info.as_ref().map(|info| info.status.as_str());
+        Some(match (&self.error, status) {
+            (Some(error), _) => error.clone(),
+            (None, Some(status)) if status != "active" => format!("license is {}", status),
+            _ => "license is no longer valid".to_string(),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActivationResult {
     pub activated: bool,
@@ -159,16 +281,6 @@ pub struct ActivationResult {
     pub meta: Option<LicenseMeta>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LicenseInfo {
-    pub id: u64,
-    pub status: String,
-    pub key: String,
-    pub activation_limit: Option<u32>,
-    pub activation_usage: u32,
-    pub expires_at: Option<String>,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LicenseMeta {
     pub store_id: u64,
@@ -257,73 +369,731 @@ pub fn plan_from_variant_name(variant_name: &str) -> LicensePlan {
     }
 }
 
-/// Activate and save license to config
+/// Normalize a pasted license key and check it has LemonSqueezy's shape
+/// (an uppercase UUID: `XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX`), so obviously
+/// malformed keys fail locally instead of with a confusing API error
+pub fn normalize_and_validate_key(license_key: &str) -> Result<String> {
+    let key = license_key.trim().to_uppercase();
+    if key.is_empty() {
+        return Err(LicenseError::InvalidKey("Please enter a license key".to_string()));
+    }
+
+    let groups: Vec<&str> = key.split('-').collect();
+    let well_formed = groups.len() == 5
+        && groups.iter().zip([8, 4, 4, 4, 12]).all(|(group, len)| {
+            group.len() == len && group.chars().all(|c| c.is_ascii_hexdigit())
+        });
+    if !well_formed {
+        return Err(LicenseError::InvalidKey(
+            "That doesn't look like a license key. Keys look like \
+             XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX - check your purchase email."
+                .to_string(),
+        ));
+    }
+
+    Ok(key)
+}
+
+/// Activate and save license to config.
+///
+/// Re-entering the key this machine is already activated with reuses the
+/// existing instance while it's still valid instead of using another slot.
+/// If the activation succeeds but can't be saved locally, the new instance is
+/// deactivated again so it doesn't use up an activation slot.
 pub async fn activate_and_save(license_key: &str) -> Result<ActivationResult> {
+    let result = activate(license_key).await;
+    let (instance_id, customer_email, outcome) = match result {
+        Ok(ref activation) => (
+            activation.instance_id.as_deref(),
+            activation.meta.as_ref().map(|meta| meta.customer_email.as_str()),
+            match activation.error {
+                _ if activation.activated => Ok(()),
+                Some(ref error) => Err(error.clone()),
+                None => Err("activation refused".to_string()),
+            },
+        ),
+        Err(ref e) => (None, None, Err(e.to_string())),
+    };
+    audit::record(
+        AuditAction::Activate,
+        Some(license_key.trim()),
+        instance_id,
+        customer_email,
+        outcome,
+    );
+    result
+}
+
+async fn activate(license_key: &str) -> Result<ActivationResult> {
+    let license_key = normalize_and_validate_key(license_key)?;
     let client = LemonSqueezyClient::new();
+
+    if let Some(result) = reuse_existing_instance(&client, &license_key).await? {
+        save_activation(&license_key, &result)?;
+        return Ok(result);
+    }
+
     let machine_name = LicenseConfig::get_machine_name();
-    
-    let result = client.activate_license(license_key, &machine_name).await?;
+    let result = client.activate_license(&license_key, &machine_name).await?;
+    if let Some(status) = inactive_status(&result) {
+        // The server may have taken a slot anyway; give it back
+        if let (true, Some(instance_id)) = (result.activated, result.instance_id.as_deref()) {
+            if let Err(e) = client.deactivate_license(&license_key, instance_id).await {
+                warn!("Could not release instance {} of an inactive license: {}", instance_id, e);
+            }
+        }
+        return Err(LicenseError::LicenseInactive { status: status.to_string() });
+    }
+    if let Some(limit) = activation_limit_reached(&result) {
+        return Err(LicenseError::ActivationLimitReached { limit });
+    }
     
     if result.activated {
-        let mut config = LicenseConfig::load()?;
-        config.license_key = Some(license_key.to_string());
-        config.instance_id = result.instance_id.clone();
-        
-        if let Some(ref info) = result.license_info {
-            config.license_status = Some(info.status.clone());
-        }
-        
-        if let Some(ref meta) = result.meta {
-            config.license_plan = Some(plan_from_variant_name(&meta.variant_name));
-            config.customer_email = Some(meta.customer_email.clone());
+        if let Err(save_error) = save_activation(&license_key, &result) {
+            let instance_id = result.instance_id.as_deref();
+            return Err(rollback_activation(&client, &license_key, instance_id, save_error).await);
         }
-        
-        config.last_validated = Some(chrono::Utc::now().to_rfc3339());
-        config.save()?;
     }
     
     Ok(result)
 }
 
-/// Validate existing license (refresh status)
+/// The license's status if the server reports it can't be used, whether or
+/// not it agreed to the activation
+fn inactive_status(result: &ActivationResult) -> Option<&str> {
+    let status = result.license_info.as_ref()?.status.as_str();
+    matches!(status, "expired" | "disabled").then_some(status)
+}
+
+/// `Some(limit)` if the activation was refused because every slot is taken.
+/// LemonSqueezy only says so in the error text ("This license key has reached
+/// the activation limit."), so the usage count is checked as well.
+fn activation_limit_reached(result: &ActivationResult) -> Option<Option<u32>> {
+    if result.activated {
+        return None;
+    }
+    let limit = result.license_info.as_ref().and_then(|info| info.activation_limit);
+    let says_limit = result
+        .error
+        .as_deref()
+        .is_some_and(|error| error.to_lowercase().contains("activation limit"));
+    let slots_full = result
+        .license_info
+        .as_ref()
+        .is_some_and(|info| info.activation_limit.is_some_and(|l| info.activation_usage >= l));
+    (says_limit || slots_full).then_some(limit)
+}
+
+/// The activation this machine already holds for `license_key`, if the
+/// server still accepts it
+async fn reuse_existing_instance(
+    client: &LemonSqueezyClient,
+    license_key: &str,
+) -> Result<Option<ActivationResult>> {
+    let config = LicenseConfig::load()?;
+    let Some(instance_id) = config.instance_id else {
+        return Ok(None);
+    };
+    // Keys saved before normalization may differ in case
+    let same_key = config
+        .license_key
+        .is_some_and(|key| key.trim().eq_ignore_ascii_case(license_key));
+    if !same_key {
+        return Ok(None);
+    }
+
+    let validation = client.validate_license(license_key, Some(&instance_id)).await?;
+    if !validation.valid {
+        return Ok(None);
+    }
+
+    Ok(Some(ActivationResult {
+        activated: true,
+        error: None,
+        license_info: validation.license_info,
+        instance_id: Some(instance_id),
+        meta: validation.meta,
+    }))
+}
+
+/// Record a successful activation in the license config
+fn save_activation(license_key: &str, result: &ActivationResult) -> Result<()> {
+    let mut config = LicenseConfig::load()?;
+    config.license_key = Some(license_key.to_string());
+    config.instance_id = result.instance_id.clone();
+    
+    if let Some(ref info) = result.license_info {
+        config.license_status = Some(info.status.clone());
+        config.license_expires_at = info.expires_at.clone();
+    }
+    
+    if let Some(ref meta) = result.meta {
+        config.license_plan = Some(plan_from_variant_name(&meta.variant_name));
+        config.customer_email = Some(meta.customer_email.clone());
+    }
+    
+    config.last_validated = Some(chrono::Utc::now().to_rfc3339());
+    config.save()
+}
+
+/// Undo an activation whose local save failed. Returns the error to report:
+/// if the rollback fails too, it names the instance so the user can
+/// deactivate it from their LemonSqueezy account.
+async fn rollback_activation(
+    client: &LemonSqueezyClient,
+    license_key: &str,
+    instance_id: Option<&str>,
+    save_error: LicenseError,
+) -> LicenseError {
+    let save_error = anyhow::Error::new(save_error);
+    let Some(instance_id) = instance_id else {
+        return save_error.context("License activated, but saving it failed").into();
+    };
+
+    match client.deactivate_license(license_key, instance_id).await {
+        Ok(true) => save_error
+            .context("Saving the license failed, so the activation was undone")
+            .into(),
+        outcome => {
+            let reason = match outcome {
+                Err(e) => format!("{:#}", e),
+                _ => "the server refused".to_string(),
+            };
+            anyhow::anyhow!(
+                "License activated as instance {} but saving it failed ({:#}), and undoing \
+                 the activation failed too ({}). Deactivate that instance from your \
+                 LemonSqueezy account before activating again.",
+                instance_id,
+                save_error,
+                reason
+            )
+            .into()
+        }
+    }
+}
+
+/// Validate existing license (refresh status).
+///
+/// A server answer updates the stored status either way, so a license
+/// disabled server-side stops working here too. Network errors leave the
+/// stored status untouched, so going offline doesn't lock the user out.
 pub async fn validate_existing() -> Result<ValidationResult> {
+    let before = LicenseConfig::load()?;
+    let result = validate().await;
+    if before.license_key.is_some() {
+        let outcome = match result {
+            Ok(ref validation) => validation.rejection_reason().map_or(Ok(()), Err),
+            Err(ref e) => Err(e.to_string()),
+        };
+        audit_stored_license(AuditAction::Validate, &before, outcome);
+    }
+    result
+}
+
+async fn validate() -> Result<ValidationResult> {
     let config = LicenseConfig::load()?;
+
+    // Offline licenses are re-checked locally (expiry), never against the API
+    if let Some(token) = config.offline_token.clone() {
+        return revalidate_offline_token(config, &token);
+    }
     
     let license_key = config.license_key
-        .ok_or_else(|| anyhow::anyhow!("No license key configured"))?;
+        .ok_or(LicenseError::NoLicense)?;
+    let license_key = normalize_and_validate_key(&license_key)?;
+
+    // LemonSqueezy reports a bare key as valid, so without this machine's
+    // instance (cleared when the config turned out to be copied, or the
+    // machine ID was reset) the key has to be activated again first
+    let Some(instance_id) = config.instance_id else {
+        let mut config = LicenseConfig::load()?;
+        config.license_status = None;
+        config.save()?;
+        return Ok(ValidationResult {
+            valid: false,
+            error: Some(
+                "This license isn't activated on this machine. Activate it again to use it here."
+                    .to_string(),
+            ),
+            license_info: None,
+            instance_id: None,
+            meta: None,
+        });
+    };
     
     let client = LemonSqueezyClient::new();
-    let result = client.validate_license(&license_key, config.instance_id.as_deref()).await?;
+    let result = client.validate_license(&license_key, Some(&instance_id)).await?;
     
     // Update config with fresh status
-    if result.valid {
-        let mut config = LicenseConfig::load()?;
-        if let Some(ref info) = result.license_info {
-            config.license_status = Some(info.status.clone());
-        }
-        config.last_validated = Some(chrono::Utc::now().to_rfc3339());
-        config.save()?;
+    let mut config = LicenseConfig::load()?;
+    if let Some(ref info) = result.license_info {
+        config.license_status = Some(info.status.clone());
+        config.license_expires_at = info.expires_at.clone();
     }
+    // Rejected without a more specific status: unknown key, or the key is
+    // "active" but this instance was deactivated from the store dashboard
+    let specific_status = matches!(&result.license_info, Some(info) if info.status != "active");
+    if !result.valid && !specific_status {
+        config.license_status = Some("invalid".to_string());
+    }
+    config.last_validated = Some(chrono::Utc::now().to_rfc3339());
+    config.save()?;
     
     Ok(result)
 }
 
-/// Deactivate and clear license from config
-pub async fn deactivate_and_clear() -> Result<bool> {
-    let config = LicenseConfig::load()?;
+/// Deactivate and clear license from config.
+///
+/// Normally the license is only cleared once LemonSqueezy confirms the
+/// deactivation. With `force`, it's cleared even if the server can't be
+/// reached, and `pending_deactivation` remembers the instance so it can be
+/// released on a later run.
+pub async fn deactivate_and_clear(force: bool) -> Result<bool> {
+    let before = LicenseConfig::load()?;
+    let result = deactivate(force).await;
+    if before.license_key.is_some() {
+        let outcome = match result {
+            Ok(true) => Ok(()),
+            Ok(false) => Err("deactivation refused".to_string()),
+            Err(ref e) => Err(e.to_string()),
+        };
+        audit_stored_license(AuditAction::Deactivate, &before, outcome);
+    }
+    result
+}
+
+/// Audit an action on the license stored in `config`
+fn audit_stored_license(
+    action: AuditAction,
+    config: &LicenseConfig,
+    outcome: std::result::Result<(), String>,
+) {
+    audit::record(
+        action,
+        config.license_key.as_deref(),
+        config.instance_id.as_deref(),
+        config.customer_email.as_deref(),
+        outcome,
+    );
+}
+
+async fn deactivate(force: bool) -> Result<bool> {
+    let mut config = LicenseConfig::load()?;
+
+    // Offline licenses aren't registered anywhere, just forget the token
+    if config.offline_token.is_some() {
+        config.clear_license()?;
+        return Ok(true);
+    }
     
     let license_key = config.license_key
-        .ok_or_else(|| anyhow::anyhow!("No license key configured"))?;
+        .ok_or(LicenseError::NoLicense)?;
     let instance_id = config.instance_id
-        .ok_or_else(|| anyhow::anyhow!("No instance ID configured"))?;
+        .context("No instance ID configured")?;
     
     let client = LemonSqueezyClient::new();
-    let deactivated = client.deactivate_license(&license_key, &instance_id).await?;
+    let result = client.deactivate_license(&license_key, &instance_id).await;
+
+    let pending = match result {
+        Ok(true) => None,
+        Ok(false) if !force => return Ok(false),
+        // The server answered but refused; there's nothing to retry later
+        Ok(false) => None,
+        Err(e) if !force => return Err(e),
+        Err(e) => {
+            warn!("Deactivation failed, clearing license locally: {}", e);
+            Some(PendingDeactivation { license_key, instance_id, attempts: 0 })
+        }
+    };
+
+    let mut config = LicenseConfig::load()?;
+    config.pending_deactivation = pending;
+    config.clear_license()?;
     
-    if deactivated {
-        let mut config = LicenseConfig::load()?;
+    Ok(true)
+}
+
+/// Retry the server-side half of an earlier forced deactivation (see
+/// `deactivate_and_clear`), freeing the activation slot it still holds.
+///
+/// Returns the record that's still pending: `None` once it's done (or there
+/// was nothing to do), otherwise with its updated attempt count. Once
+/// `is_stuck`, it's returned without contacting the server again.
+pub async fn complete_pending_deactivation() -> Result<Option<PendingDeactivation>> {
+    let config = LicenseConfig::load()?;
+    let Some(mut pending) = config.pending_deactivation else {
+        return Ok(None);
+    };
+    if pending.is_stuck() {
+        return Ok(Some(pending));
+    }
+
+    let client = LemonSqueezyClient::new();
+    let result = client
+        .deactivate_license(&pending.license_key, &pending.instance_id)
+        .await;
+
+    let mut config = LicenseConfig::load()?;
+    let remaining = match result {
+        Ok(deactivated) => {
+            // A refusal won't change on retry (e.g. the instance was already
+            // released from the store dashboard)
+            if !deactivated {
+                warn!("Server did not deactivate pending instance {}", pending.instance_id);
+            }
+            None
+        }
+        Err(e) => {
+            pending.attempts += 1;
+            warn!(
+                "Pending deactivation of {} failed (attempt {}/{}): {:#}",
+                pending.instance_id, pending.attempts, MAX_DEACTIVATION_ATTEMPTS, e
+            );
+            Some(pending)
+        }
+    };
+    config.pending_deactivation = remaining.clone();
+    config.save()?;
+
+    Ok(remaining)
+}
+
+/// Give this machine a fresh `machine_id`, e.g. after `license.json` was
+/// copied along with a cloned VM image and both copies share one activation.
+///
+/// The old instance is released (best effort - a clone may already have
+/// released it) and `instance_id` is cleared, so the license key stays but
+/// has to be activated again, which uses a new activation slot (validating
+/// the bare key won't bring it back). Offline
+/// licenses aren't tied to an instance and are kept. Safe to call without a
+/// license or repeatedly. Returns the new machine ID.
+pub async fn reset_machine_id() -> Result<String> {
+    let config = LicenseConfig::load()?;
+
+    if let (Some(key), Some(instance_id)) = (&config.license_key, &config.instance_id) {
+        let client = LemonSqueezyClient::new();
+        match client.deactivate_license(key, instance_id).await {
+            Ok(true) => {}
+            Ok(false) => warn!("Old license instance {} was not deactivated", instance_id),
+            Err(e) => warn!("Could not deactivate old license instance {}: {}", instance_id, e),
+        }
+    }
+
+    let mut config = LicenseConfig::load()?;
+    config.machine_id = Uuid::new_v4().to_string();
+    config.instance_id = None;
+    if config.offline_token.is_none() {
+        config.license_status = None;
+        config.last_validated = None;
+    }
+    config.save()?;
+
+    Ok(config.machine_id)
+}
+
+/// Outcome of `deactivate_all_instances`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeactivateAllResult {
+    /// Instances the server released
+    pub deactivated: usize,
+    /// Instances that couldn't be released
+    pub failures: Vec<InstanceFailure>,
+    /// Activations the server still counts for the key, if it could be asked
+    pub remaining_activations: Option<u32>,
+    /// This machine's activation was released, so the local license was cleared
+    pub cleared_local: bool,
+}
+
+/// An instance `deactivate_all_instances` couldn't release
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceFailure {
+    pub instance_id: String,
+    pub error: String,
+}
+
+/// Release every activation of `license_key` the hub knows of, including this
+/// machine's, e.g. to recover slots held by machines the user lost.
+///
+/// The license API can't list a key's instances, so "every" means the ones
+/// this machine has seen: its own, a pending deactivation and those in the
+/// audit log. `remaining_activations` tells whether others are left, which
+/// can only be released from the LemonSqueezy account.
+pub async fn deactivate_all_instances(license_key: &str) -> Result<DeactivateAllResult> {
+    let license_key = normalize_and_validate_key(license_key)?;
+    let config = LicenseConfig::load()?;
+    let same_key = |key: &str| key.trim().eq_ignore_ascii_case(&license_key);
+
+    let mut instances = audit::instances_seen(&license_key);
+    if config.license_key.as_deref().is_some_and(same_key) {
+        instances.extend(config.instance_id.clone());
+    }
+    if let Some(pending) = &config.pending_deactivation {
+        if same_key(&pending.license_key) {
+            instances.push(pending.instance_id.clone());
+        }
+    }
+    instances.sort();
+    instances.dedup();
+
+    let client = LemonSqueezyClient::new();
+    let mut deactivated = Vec::new();
+    let mut failures = Vec::new();
+    for instance_id in instances {
+        let outcome = match client.deactivate_license(&license_key, &instance_id).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err("deactivation refused".to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        audit::record(
+            AuditAction::Deactivate,
+            Some(&license_key),
+            Some(&instance_id),
+            None,
+            outcome.clone(),
+        );
+        match outcome {
+            Ok(()) => deactivated.push(instance_id),
+            Err(error) => failures.push(InstanceFailure { instance_id, error }),
+        }
+    }
+
+    let remaining_activations = match client.validate_license(&license_key, None).await {
+        Ok(validation) => validation.license_info.map(|info| info.activation_usage),
+        Err(e) => {
+            warn!("Could not check remaining activations: {}", e);
+            None
+        }
+    };
+
+    let released = |id: Option<&String>| id.is_some_and(|id| deactivated.contains(id));
+    let mut config = LicenseConfig::load()?;
+    if released(config.pending_deactivation.as_ref().map(|p| &p.instance_id)) {
+        config.pending_deactivation = None;
+    }
+    let cleared_local = config.license_key.as_deref().is_some_and(same_key)
+        && released(config.instance_id.as_ref());
+    if cleared_local {
         config.clear_license()?;
+    } else {
+        config.save()?;
+    }
+
+    info!(
+        "Deactivated {} license instance(s), {} failed",
+        deactivated.len(),
+        failures.len()
+    );
+    Ok(DeactivateAllResult {
+        deactivated: deactivated.len(),
+        failures,
+        remaining_activations,
+        cleared_local,
+    })
+}
+
+/// Re-check the stored offline token and save the outcome, so a token that
+/// expired (or no longer verifies) stops authorizing this machine
+fn revalidate_offline_token(mut config: LicenseConfig, token: &str) -> Result<ValidationResult> {
+    let public_key = offline_public_key()?;
+    let (status, verified) = match decode_offline_claims(token, &public_key) {
+        Ok(claims) => match check_offline_expiry(&claims) {
+            Ok(()) => ("active", Ok(offline_license_info(claims))),
+            Err(e) => ("expired", Err(invalid_token(e))),
+        },
+        Err(e) => ("invalid", Err(invalid_token(e))),
+    };
+
+    config.license_status = Some(status.to_string());
+    config.last_validated = Some(chrono::Utc::now().to_rfc3339());
+    config.save()?;
+
+    Ok(match verified {
+        Ok(info) => ValidationResult {
+            valid: true,
+            error: None,
+            license_info: Some(info),
+            instance_id: None,
+            meta: None,
+        },
+        Err(e) => ValidationResult {
+            valid: false,
+            error: Some(e.to_string()),
+            license_info: None,
+            instance_id: None,
+            meta: None,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    /// Answer one HTTP request with `body` as JSON. Returns the server's base
+    /// URL and a handle yielding the request it received.
+    fn serve_once(body: String) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 4096];
+            // Headers, then as much body as they announce
+            loop {
+                let read = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text
+                        .to_lowercase()
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length:")?.trim().parse().ok())
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + length || read == 0 {
+                        break;
+                    }
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+        (base, handle)
+    }
+
+    /// An activation response as LemonSqueezy sends it
+    fn activation_response(activated: bool, error: Option<&str>, status: &str) -> String {
+        serde_json::json!({
+            "activated": activated,
+            "error": error,
+            "license_key": {
+                "id": 1,
+                "status": status,
+                "key": "38b1460a-5104-4067-a91d-77b872934d51",
+                "activation_limit": 3,
+                "activation_usage": if activated { 1 } else { 3 },
+                "created_at": "2026-01-01T00:00:00.000000Z",
+                "expires_at": null
+            },
+            "instance": activated.then(|| serde_json::json!({
+                "id": "instance-1",
+                "name": "Test Machine",
+                "created_at": "2026-03-01T00:00:00.000000Z"
+            })),
+            "meta": {
+                "store_id": 1,
+                "order_id": 2,
+                "order_item_id": 3,
+                "product_id": 4,
+                "product_name": "Productivity Hub",
+                "variant_id": 5,
+                "variant_name": "Monthly",
+                "customer_id": 6,
+                "customer_name": "Test Customer",
+                "customer_email": "customer@example.com"
+            }
+        })
+        .to_string()
+    }
+
+    /// Activate against a stub server answering with `body`
+    async fn activate_with_response(body: String) -> ActivationResult {
+        let (base, server) = serve_once(body);
+        let client = LemonSqueezyClient::with_api_base(base);
+        let result = client.activate_license("KEY-1234", "Test Machine").await.unwrap();
+        assert!(server.join().unwrap().starts_with("POST /activate "));
+        result
+    }
+
+    #[tokio::test]
+    async fn limit_reached_response_is_recognized() {
+        let body = activation_response(
+            false,
+            Some("This license key has reached the activation limit."),
+            "active",
+        );
+        let result = activate_with_response(body).await;
+
+        assert_eq!(activation_limit_reached(&result), Some(Some(3)));
+        assert_eq!(inactive_status(&result), None);
+        let error = LicenseError::ActivationLimitReached { limit: Some(3) }.to_string();
+        assert!(error.contains("activation limit"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn full_slots_count_as_limit_reached_without_the_message() {
+        let result = activate_with_response(activation_response(false, None, "active")).await;
+        assert_eq!(activation_limit_reached(&result), Some(Some(3)));
+    }
+
+    #[tokio::test]
+    async fn successful_activation_is_not_limit_reached() {
+        let result = activate_with_response(activation_response(true, None, "active")).await;
+        assert_eq!(activation_limit_reached(&result), None);
+        assert_eq!(inactive_status(&result), None);
+        assert_eq!(result.instance_id.as_deref(), Some("instance-1"));
+    }
+
+    #[tokio::test]
+    async fn expired_license_is_not_usable_even_if_activated() {
+        let result = activate_with_response(activation_response(true, None, "expired")).await;
+
+        assert_eq!(inactive_status(&result), Some("expired"));
+        let error = LicenseError::LicenseInactive { status: "expired".to_string() }.to_string();
+        assert!(error.contains("This license is expired"), "{}", error);
+        assert!(error.contains("Renew"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn disabled_license_is_not_usable() {
+        let body = activation_response(false, Some("This license key is disabled."), "disabled");
+        let result = activate_with_response(body).await;
+        assert_eq!(inactive_status(&result), Some("disabled"));
+    }
+
+    fn save_failure() -> LicenseError {
+        LicenseError::io("Failed to write license config")(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "read-only",
+        ))
+    }
+
+    #[tokio::test]
+    async fn failed_save_deactivates_the_new_instance() {
+        let (base, server) = serve_once(r#"{"deactivated": true}"#.to_string());
+        let client = LemonSqueezyClient::with_api_base(base);
+
+        let error =
+            rollback_activation(&client, "KEY-1234", Some("instance-1"), save_failure()).await;
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /deactivate "));
+        assert!(request.contains("license_key=KEY-1234"));
+        assert!(request.contains("instance_id=instance-1"));
+        let message = format!("{:#}", anyhow::Error::new(error));
+        assert!(message.contains("activation was undone"), "{}", message);
+        assert!(message.contains("read-only"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn failed_rollback_names_the_instance() {
+        let (base, server) = serve_once(r#"{"deactivated": false, "error": "nope"}"#.to_string());
+        let client = LemonSqueezyClient::with_api_base(base);
+
+        let error =
+            rollback_activation(&client, "KEY-1234", Some("instance-1"), save_failure()).await;
+
+        server.join().unwrap();
+        let message = error.to_string();
+        assert!(message.contains("instance instance-1"), "{}", message);
+        assert!(message.contains("Deactivate that instance"), "{}", message);
     }
-    
-    Ok(deactivated)
 }
@@ -1,72 +1,164 @@
 //! Hub Licensing - LemonSqueezy integration for Productivity Hub
 //!
 //! Provides license validation, activation, and trial management.
+//!
+//! Everything that talks to a server (LemonSqueezy, trial and promo servers)
+//! needs the `remote` feature, on by default. Without it, authorization is
+//! decided from the stored license config and trials are local only; offline
+//! and kiosk tokens (`offline`) work either way.
 
+pub mod audit;
 mod config;
+mod error;
+#[cfg(feature = "remote")]
 pub mod lemonsqueezy;
+pub mod offline;
 pub mod trial;
 
-pub use config::{LicenseConfig, LicensePlan};
-pub use lemonsqueezy::{LemonSqueezyClient, LicenseInfo, LicenseStatus, ValidationResult, ActivationResult};
-pub use trial::{TrialInfo, TrialStatus};
+pub use audit::{AuditAction, AuditEntry};
+pub use config::{LicenseConfig, LicensePlan, PendingDeactivation, MAX_DEACTIVATION_ATTEMPTS};
+pub use error::LicenseError;
+#[cfg(feature = "remote")]
+pub use lemonsqueezy::{
+    ActivationResult, DeactivateAllResult, InstanceFailure, LemonSqueezyClient, LicenseStatus,
+    NetworkErrorRecord, ValidationResult,
+};
+pub use trial::{TrialInfo, TrialMilestone, TrialStatus, TrialVerification};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use tracing::warn;
 
 /// Check if the app is authorized to run (valid license OR active trial)
 pub fn is_authorized() -> bool {
-    let config = LicenseConfig::load().unwrap_or_default();
-    
-    // Check for valid license
-    if config.license_key.is_some() && config.license_status == Some("active".to_string()) {
-        return true;
-    }
-    
-    // Check for active trial
-    if let Some(trial_exp) = &config.trial_expiration {
-        if let Ok(exp) = chrono::DateTime::parse_from_rfc3339(trial_exp) {
-            if exp > chrono::Utc::now() {
-                return true;
-            }
-        }
-    }
-    
-    false
+    authorization_check().authorized
 }
 
 /// Get the current authorization status with details
 pub fn get_auth_status() -> AuthStatus {
-    let config = LicenseConfig::load().unwrap_or_default();
-    
+    auth_status_at(&authorization_config(), Utc::now())
+}
+
+/// Check authorization and, when it fails, what the user should be asked to do
+pub fn authorization_check() -> AuthDecision {
+    authorization_check_at(&authorization_config(), Utc::now())
+}
+
+/// The stored license config, plus a kiosk license for this session if one
+/// is set (see `offline::KIOSK_TOKEN_ENV`)
+fn authorization_config() -> LicenseConfig {
+    offline::with_kiosk_license(LicenseConfig::load().unwrap_or_default())
+}
+
+/// `authorization_check` for a given config as of `now`
+pub fn authorization_check_at(config: &LicenseConfig, now: DateTime<Utc>) -> AuthDecision {
+    let status = auth_status_at(config, now);
+    let prompt = match status {
+        AuthStatus::Licensed { .. } | AuthStatus::Trial { .. } => None,
+        // A key we know about that stopped being active (expired, disabled, ...)
+        _ if config.license_key.is_some() => Some(AuthPrompt::Renew),
+        AuthStatus::TrialExpired | AuthStatus::GracePeriod { .. } => Some(AuthPrompt::Purchase),
+        AuthStatus::NoLicense => Some(AuthPrompt::StartTrial),
+    };
+
+    AuthDecision {
+        authorized: status.is_authorized(),
+        status,
+        prompt,
+    }
+}
+
+/// Authorization status for a given config as of `now` (the clock matters
+/// for trials and offline licenses)
+pub fn auth_status_at(config: &LicenseConfig, now: DateTime<Utc>) -> AuthStatus {
     // Check for valid license
     if let Some(ref key) = config.license_key {
-        if config.license_status == Some("active".to_string()) {
+        let active = config.license_status == Some("active".to_string());
+        if active && !offline_license_expired(config, now) {
             return AuthStatus::Licensed {
                 plan: config.license_plan.unwrap_or(LicensePlan::Monthly),
-                key_preview: mask_license_key(key),
+                key_preview: mask_secret(key),
             };
         }
     }
     
-    // Check for active trial
-    if let Some(ref trial_exp) = config.trial_expiration {
-        if let Ok(exp) = chrono::DateTime::parse_from_rfc3339(trial_exp) {
-            let now = chrono::Utc::now();
-            if exp > now {
-                let remaining = exp.signed_duration_since(now);
-                return AuthStatus::Trial {
-                    days_remaining: remaining.num_days() as u32,
-                    hours_remaining: (remaining.num_hours() % 24) as u32,
-                };
-            } else {
-                return AuthStatus::TrialExpired;
-            }
+    match trial::trial_info_at(config, now) {
+        Ok(info) if info.active => AuthStatus::Trial {
+            days_remaining: info.days_remaining,
+            hours_remaining: info.hours_remaining,
+            minutes_remaining: info.minutes_remaining,
+        },
+        // Started but over (an unreadable expiration counts as over)
+        _ if config.trial_started => match trial::grace_days_remaining_at(config, now) {
+            Some(days_remaining) => AuthStatus::GracePeriod { days_remaining },
+            None => AuthStatus::TrialExpired,
+        },
+        _ => AuthStatus::NoLicense,
+    }
+}
+
+/// An offline license past its expiration. Nothing re-checks those with a
+/// server, so the stored status can still say "active" (an unreadable
+/// expiration counts as passed).
+fn offline_license_expired(config: &LicenseConfig, now: DateTime<Utc>) -> bool {
+    if config.offline_token.is_none() {
+        return false;
+    }
+    config
+        .license_expires_at
+        .as_deref()
+        .is_some_and(|expires_at| parse_timestamp(expires_at).is_none_or(|time| time <= now))
+}
+
+/// A license as reported by LemonSqueezy or read from an offline token
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LicenseInfo {
+    pub id: u64,
+    pub status: String,
+    pub key: String,
+    pub activation_limit: Option<u32>,
+    pub activation_usage: u32,
+    pub expires_at: Option<String>,
+}
+
+/// What to ask an unauthorized user to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum AuthPrompt {
+    /// Never licensed and the trial is unused
+    StartTrial,
+    /// Trial used up - buy a license
+    Purchase,
+    /// A license key exists but is no longer active
+    Renew,
+}
+
+impl AuthPrompt {
+    /// User-facing explanation of why tools can't start
+    pub fn message(&self) -> &'static str {
+        match self {
+            AuthPrompt::StartTrial => "Start your free trial to use the tools.",
+            AuthPrompt::Purchase => "Your trial has ended. Purchase a license to continue.",
+            AuthPrompt::Renew => "Your license is no longer active. Renew it to continue.",
         }
     }
-    
-    // Check if trial was already used
-    if config.trial_started {
-        return AuthStatus::TrialExpired;
+}
+
+/// Result of `authorization_check`: the verdict plus the status behind it
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AuthDecision {
+    pub authorized: bool,
+    pub status: AuthStatus,
+    /// Set when `authorized` is false
+    pub prompt: Option<AuthPrompt>,
+}
+
+impl AuthDecision {
+    /// User-facing explanation of why tools can't start
+    pub fn message(&self) -> &'static str {
+        self.prompt.map_or(
+            "License required. Please activate a license or start a free trial.",
+            |prompt| prompt.message(),
+        )
     }
-    
-    AuthStatus::NoLicense
 }
 
 /// Authorization status enum
@@ -82,7 +174,13 @@ pub enum AuthStatus {
     Trial {
         days_remaining: u32,
         hours_remaining: u32,
+        /// Minutes remaining (0-59), so the last hour still counts down
+        minutes_remaining: u32,
     },
+    /// Trial ended recently and the configured grace window
+    /// (`LicenseConfig::post_trial_grace_days`) is still open. Not authorized;
+    /// gating code may allow reduced use.
+    GracePeriod { days_remaining: u32 },
     /// Trial has expired
     TrialExpired,
     /// No license and no trial started
@@ -93,12 +191,257 @@ impl AuthStatus {
     pub fn is_authorized(&self) -> bool {
         matches!(self, AuthStatus::Licensed { .. } | AuthStatus::Trial { .. })
     }
+
+    /// Short user-facing label (e.g. "Licensed (Lifetime)", "Trial: 3 days left")
+    pub fn summary(&self) -> String {
+        match self {
+            AuthStatus::Licensed { plan, .. } => format!("Licensed ({})", plan),
+            AuthStatus::Trial {
+                days_remaining: 0,
+                hours_remaining: 0,
+                minutes_remaining,
+            } => {
+                let minutes = plural(*minutes_remaining, "minute");
+                format!("Trial: {} {} left", minutes_remaining, minutes)
+            }
+            AuthStatus::Trial {
+                days_remaining: 0,
+                hours_remaining,
+                ..
+            } => {
+                let hours = plural(*hours_remaining, "hour");
+                format!("Trial: 0 days, {} {} left", hours_remaining, hours)
+            }
+            AuthStatus::Trial { days_remaining, .. } => {
+                format!("Trial: {} {} left", days_remaining, plural(*days_remaining, "day"))
+            }
+            AuthStatus::GracePeriod { days_remaining } => {
+                let days = plural(*days_remaining, "day");
+                format!("Trial expired: {} {} of grace left", days_remaining, days)
+            }
+            AuthStatus::TrialExpired => "Trial expired".to_string(),
+            AuthStatus::NoLicense => "No license".to_string(),
+        }
+    }
+
+    /// Color for a status badge (matches the dashboard's success/warning/error palette)
+    pub fn badge_color(&self) -> &'static str {
+        match self {
+            AuthStatus::Licensed { .. } => "#3fb950",
+            AuthStatus::Trial { .. } => "#d29922",
+            AuthStatus::GracePeriod { .. } => "#db6d28",
+            AuthStatus::TrialExpired => "#f85149",
+            AuthStatus::NoLicense => "#6e7681",
+        }
+    }
 }
 
-fn mask_license_key(key: &str) -> String {
-    if key.len() > 8 {
-        format!("{}...{}", &key[..4], &key[key.len()-4..])
+impl std::fmt::Display for AuthStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.summary())
+    }
+}
+
+/// Formats older configs stored timestamps in, read as UTC
+const LEGACY_TIMESTAMP_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+
+/// Parse a stored timestamp: RFC3339, or a legacy format without a timezone
+/// (taken as UTC) or a plain date (midnight UTC). Logs a warning and returns
+/// `None` if nothing matches; callers treat that as already passed.
+pub fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    let trimmed = timestamp.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(trimmed) {
+        return Some(time.with_timezone(&Utc));
+    }
+    let legacy = LEGACY_TIMESTAMP_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(trimmed, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(trimmed, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        });
+    if legacy.is_none() {
+        warn!("Unreadable timestamp {:?}", timestamp);
+    }
+    legacy.map(|time| time.and_utc())
+}
+
+/// Describe a stored timestamp (see `parse_timestamp`) relative to now ("just now", "2 hours ago",
+/// "in 3 days"). Returns `None` if the timestamp can't be parsed.
+pub fn relative_time(timestamp: &str) -> Option<String> {
+    let delta = parse_timestamp(timestamp)? - Utc::now();
+    let future = delta > chrono::Duration::zero();
+    let delta = if future { delta } else { -delta };
+
+    let (count, unit) = if delta.num_days() >= 1 {
+        (delta.num_days(), "day")
+    } else if delta.num_hours() >= 1 {
+        (delta.num_hours(), "hour")
+    } else if delta.num_minutes() >= 1 {
+        (delta.num_minutes(), "minute")
+    } else {
+        return Some("just now".to_string());
+    };
+
+    let count = u32::try_from(count).unwrap_or(u32::MAX);
+    let amount = format!("{} {}", count, plural(count, unit));
+    Some(if future {
+        format!("in {}", amount)
+    } else {
+        format!("{} ago", amount)
+    })
+}
+
+fn plural(count: u32, unit: &str) -> String {
+    if count == 1 {
+        unit.to_string()
     } else {
-        "••••••••".to_string()
+        format!("{}s", unit)
+    }
+}
+
+/// Mask a secret (license or API key) for display: the first and last 4
+/// characters for keys longer than 8 characters, dots otherwise.
+///
+/// Works on `char` boundaries, so arbitrary (multi-byte) input can't panic.
+pub fn mask_secret(secret: &str) -> String {
+    let char_count = secret.chars().count();
+    if char_count <= 8 {
+        return "••••••••".to_string();
+    }
+
+    let head: String = secret.chars().take(4).collect();
+    let tail_start = secret
+        .char_indices()
+        .nth(char_count - 4)
+        .map(|(index, _)| index)
+        .unwrap_or(secret.len());
+    format!("{}...{}", head, &secret[tail_start..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn expiry() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn trial_config(grace_days: u32) -> LicenseConfig {
+        LicenseConfig {
+            trial_started: true,
+            trial_expiration: Some(expiry().to_rfc3339()),
+            post_trial_grace_days: grace_days,
+            ..LicenseConfig::default()
+        }
+    }
+
+    #[test]
+    fn trial_is_authorized_until_its_expiry() {
+        let config = trial_config(0);
+        let before = auth_status_at(&config, expiry() - Duration::seconds(1));
+        assert!(
+            matches!(
+                before,
+                AuthStatus::Trial { days_remaining: 0, hours_remaining: 0, minutes_remaining: 0 }
+            ),
+            "{:?}",
+            before
+        );
+        assert!(before.is_authorized());
+
+        for now in [expiry(), expiry() + Duration::seconds(1)] {
+            let status = auth_status_at(&config, now);
+            assert!(matches!(status, AuthStatus::TrialExpired), "{:?}", status);
+        }
+    }
+
+    #[test]
+    fn grace_period_follows_the_trial() {
+        let config = trial_config(2);
+        let status = auth_status_at(&config, expiry());
+        assert!(matches!(status, AuthStatus::GracePeriod { days_remaining: 2 }), "{:?}", status);
+        assert!(!status.is_authorized());
+
+        let grace_end = expiry() + Duration::days(2);
+        let status = auth_status_at(&config, grace_end - Duration::seconds(1));
+        assert!(matches!(status, AuthStatus::GracePeriod { days_remaining: 1 }), "{:?}", status);
+        let status = auth_status_at(&config, grace_end);
+        assert!(matches!(status, AuthStatus::TrialExpired), "{:?}", status);
+    }
+
+    #[test]
+    fn offline_license_stops_at_its_expiry() {
+        let config = LicenseConfig {
+            license_key: Some("OFFLINE-KEY-1234".to_string()),
+            license_status: Some("active".to_string()),
+            license_expires_at: Some(expiry().to_rfc3339()),
+            offline_token: Some("token".to_string()),
+            ..LicenseConfig::default()
+        };
+        let before = auth_status_at(&config, expiry() - Duration::seconds(1));
+        assert!(matches!(before, AuthStatus::Licensed { .. }), "{:?}", before);
+        let at = auth_status_at(&config, expiry());
+        assert!(matches!(at, AuthStatus::NoLicense), "{:?}", at);
+    }
+
+
+    #[test]
+    fn timestamps_parse_in_rfc3339_and_legacy_formats() {
+        let expected = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        assert_eq!(parse_timestamp("2026-03-01T12:00:00Z"), Some(expected));
+        assert_eq!(parse_timestamp("2026-03-01T14:00:00+02:00"), Some(expected));
+        assert_eq!(parse_timestamp("2026-03-01T12:00:00"), Some(expected));
+        assert_eq!(parse_timestamp(" 2026-03-01 12:00:00.000 "), Some(expected));
+        assert_eq!(parse_timestamp("2026-03-01"), Some(expected - Duration::hours(12)));
+    }
+
+    #[test]
+    fn malformed_and_empty_timestamps_do_not_parse() {
+        assert_eq!(parse_timestamp("next tuesday"), None);
+        assert_eq!(parse_timestamp("2026-13-45T99:00:00Z"), None);
+        assert_eq!(parse_timestamp(""), None);
+    }
+
+    #[test]
+    fn unreadable_trial_expiration_counts_as_expired() {
+        for expiration in ["not a date", ""] {
+            let config = LicenseConfig {
+                trial_expiration: Some(expiration.to_string()),
+                ..trial_config(0)
+            };
+            let info = trial::trial_info_at(&config, expiry()).unwrap();
+            assert!(!info.active, "{:?}", expiration);
+            let status = auth_status_at(&config, expiry());
+            assert!(matches!(status, AuthStatus::TrialExpired), "{:?}", status);
+        }
+    }
+
+    #[test]
+    fn short_secrets_are_fully_masked() {
+        assert_eq!(mask_secret(""), "••••••••");
+        assert_eq!(mask_secret("abc"), "••••••••");
+    }
+
+    #[test]
+    fn eight_char_secrets_are_fully_masked() {
+        assert_eq!(mask_secret("abcd1234"), "••••••••");
+        assert_eq!(mask_secret("abcd12345"), "abcd...2345");
+    }
+
+    #[test]
+    fn unicode_secrets_are_masked_by_char() {
+        assert_eq!(mask_secret("ключ-лицензии"), "ключ...нзии");
+        assert_eq!(mask_secret("ééééééééé"), "éééé...éééé");
+    }
+
+    #[test]
+    fn emoji_at_slice_boundaries_does_not_panic() {
+        // Multi-byte characters where byte slicing at 4 / len-4 would split them
+        assert_eq!(mask_secret("🔑🔑🔑🔑-KEY-🔒🔒🔒🔒"), "🔑🔑🔑🔑...🔒🔒🔒🔒");
+        assert_eq!(mask_secret("a🔑bcdefg🔒h"), "a🔑bc...fg🔒h");
+        assert_eq!(mask_secret("🔑🔑🔑🔑🔑🔑🔑🔑"), "••••••••");
     }
 }
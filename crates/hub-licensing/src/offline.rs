@@ -0,0 +1,186 @@
+//! Signed offline license tokens and kiosk licenses
+//!
+//! Tokens are verified against a public key baked in at build time, with no
+//! network call, so this module is available without the `remote` feature.
+
+use std::sync::OnceLock;
+
+use anyhow::Context;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::Utc;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::config::{LicenseConfig, LicensePlan, PendingDeactivation};
+use crate::error::{LicenseError, Result};
+use crate::LicenseInfo;
+
+/// Build-time environment variable with the base64url Ed25519 public key that
+/// offline license tokens are signed with
+const OFFLINE_PUBLIC_KEY: Option<&str> = option_env!("HUB_OFFLINE_TOKEN_PUBLIC_KEY");
+
+/// Claims embedded in an offline license token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineTokenClaims {
+    /// License ID
+    #[serde(default)]
+    pub id: u64,
+    /// License key
+    pub key: String,
+    /// License plan
+    pub plan: LicensePlan,
+    /// Customer email
+    pub customer_email: String,
+    /// Expiration timestamp (RFC3339), `None` for licenses that never expire
+    #[serde(default)]
+    pub expires_at: Option<String>,
+}
+
+/// Decode an offline token (`<base64url claims JSON>.<base64url signature>`),
+/// checking the Ed25519 signature over the claims segment and the expiry
+fn decode_offline_token(token: &str, public_key: &[u8]) -> anyhow::Result<OfflineTokenClaims> {
+    let claims = decode_offline_claims(token, public_key)?;
+    check_offline_expiry(&claims)?;
+    Ok(claims)
+}
+
+/// Decode an offline token and check its signature, but not its expiry
+pub(crate) fn decode_offline_claims(token: &str, public_key: &[u8]) -> anyhow::Result<OfflineTokenClaims> {
+    let (payload, signature) = token
+        .trim()
+        .split_once('.')
+        .context("Malformed offline token")?;
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .context("Malformed offline token signature")?;
+
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(payload.as_bytes(), &signature)
+        .map_err(|_| anyhow::anyhow!("Offline token signature is invalid"))?;
+
+    let claims = URL_SAFE_NO_PAD
+        .decode(payload)
+        .context("Malformed offline token claims")?;
+    let claims: OfflineTokenClaims =
+        serde_json::from_slice(&claims).context("Failed to parse offline token claims")?;
+    Ok(claims)
+}
+
+pub(crate) fn check_offline_expiry(claims: &OfflineTokenClaims) -> anyhow::Result<()> {
+    if let Some(ref expires_at) = claims.expires_at {
+        let expiration = chrono::DateTime::parse_from_rfc3339(expires_at)
+            .context("Failed to parse offline token expiration")?;
+        if expiration <= chrono::Utc::now() {
+            anyhow::bail!("Offline license expired on {}", expires_at);
+        }
+    }
+    Ok(())
+}
+
+/// Verify a signed offline license token without any network call
+pub fn verify_offline_token(token: &str, public_key: &[u8]) -> Result<LicenseInfo> {
+    let claims = decode_offline_token(token, public_key).map_err(invalid_token)?;
+    Ok(offline_license_info(claims))
+}
+
+pub(crate) fn offline_license_info(claims: OfflineTokenClaims) -> LicenseInfo {
+    LicenseInfo {
+        id: claims.id,
+        status: "active".to_string(),
+        key: claims.key,
+        activation_limit: None,
+        activation_usage: 0,
+        expires_at: claims.expires_at,
+    }
+}
+
+/// Get the public key offline tokens are verified against
+pub(crate) fn offline_public_key() -> Result<Vec<u8>> {
+    let key = OFFLINE_PUBLIC_KEY.context("Offline activation is not available in this build")?;
+    let key = URL_SAFE_NO_PAD
+        .decode(key.trim())
+        .context("Invalid offline token public key")?;
+    Ok(key)
+}
+
+/// Report a token that failed to decode or verify as an invalid key
+pub(crate) fn invalid_token(error: anyhow::Error) -> LicenseError {
+    LicenseError::InvalidKey(format!("{:#}", error))
+}
+
+/// Verify an offline token and save it as this machine's license.
+///
+/// An online activation this machine held is queued in
+/// `pending_deactivation`, so its slot is released on the server rather
+/// than forgotten.
+pub fn activate_offline_token(token: &str) -> Result<LicenseInfo> {
+    let claims = decode_offline_token(token, &offline_public_key()?).map_err(invalid_token)?;
+
+    let mut config = LicenseConfig::load()?;
+    if let (Some(license_key), Some(instance_id)) = (&config.license_key, &config.instance_id) {
+        config.pending_deactivation = Some(PendingDeactivation {
+            license_key: license_key.clone(),
+            instance_id: instance_id.clone(),
+            attempts: 0,
+        });
+    }
+    config.license_key = Some(claims.key.clone());
+    config.license_plan = Some(claims.plan);
+    config.license_status = Some("active".to_string());
+    config.instance_id = None;
+    config.customer_email = Some(claims.customer_email.clone());
+    config.license_expires_at = claims.expires_at.clone();
+    config.offline_token = Some(token.trim().to_string());
+    config.last_validated = Some(chrono::Utc::now().to_rfc3339());
+    config.save()?;
+
+    Ok(offline_license_info(claims))
+}
+
+// === Kiosk mode ===
+
+/// Environment variable with a kiosk license token, for shared or demo
+/// machines: an offline token that authorizes the session it's set for
+/// without using an activation slot. It's never written to `license.json`.
+pub const KIOSK_TOKEN_ENV: &str = "HUB_KIOSK_LICENSE_TOKEN";
+
+/// The kiosk token's claims if one was set at startup and verified. Checked
+/// once per process; the expiry is checked on every call.
+pub fn kiosk_license() -> Option<&'static OfflineTokenClaims> {
+    static KIOSK: OnceLock<Option<OfflineTokenClaims>> = OnceLock::new();
+    let claims = KIOSK.get_or_init(|| {
+        let token = std::env::var(KIOSK_TOKEN_ENV).ok().filter(|t| !t.trim().is_empty())?;
+        let verified = offline_public_key()
+            .and_then(|key| decode_offline_token(&token, &key).map_err(invalid_token));
+        match verified {
+            Ok(claims) => {
+                info!("Kiosk license for {} (session only)", claims.customer_email);
+                Some(claims)
+            }
+            Err(e) => {
+                warn!("Ignoring {}: {}", KIOSK_TOKEN_ENV, e);
+                None
+            }
+        }
+    });
+    claims.as_ref().filter(|claims| {
+        claims.expires_at.as_deref().is_none_or(|expires_at| {
+            crate::parse_timestamp(expires_at).is_some_and(|expiration| expiration > Utc::now())
+        })
+    })
+}
+
+/// `config` with the kiosk license, if any, applied in memory. Only for
+/// deciding authorization: the result must never be saved.
+pub(crate) fn with_kiosk_license(mut config: LicenseConfig) -> LicenseConfig {
+    if let Some(kiosk) = kiosk_license() {
+        config.license_key = Some(kiosk.key.clone());
+        config.license_plan = Some(kiosk.plan);
+        config.license_status = Some("active".to_string());
+        config.customer_email = Some(kiosk.customer_email.clone());
+        config.license_expires_at = kiosk.expires_at.clone();
+    }
+    config
+}
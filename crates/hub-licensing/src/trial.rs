@@ -1,14 +1,41 @@
 //! Trial system - 7 day free trial, one-time per machine
 
-use anyhow::{Context, Result};
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 use crate::config::LicenseConfig;
+#[cfg(feature = "remote")]
+use crate::error::{check_status, LicenseError};
+use crate::error::Result;
 
 /// Trial duration in days
 pub const TRIAL_DAYS: i64 = 7;
 
+/// Number of days a promo code extends the trial by
+pub const TRIAL_EXTENSION_DAYS: i64 = 7;
+
+/// Build-time environment variable with the promo server that approves every
+/// promo code. Builds without one can't redeem codes.
+const PROMO_SERVER_URL: Option<&str> = option_env!("HUB_PROMO_SERVER_URL");
+
+/// Longest post-trial grace window `post_trial_grace_days` can ask for
+pub const MAX_GRACE_DAYS: u32 = 7;
+
+/// Environment variable holding the trial server base URL. Can also be set at
+/// build time to bake the URL into release builds.
+pub const TRIAL_SERVER_ENV: &str = "HUB_TRIAL_SERVER_URL";
+
+/// How long to wait for the trial server before falling back to a local trial
+#[cfg(feature = "remote")]
+const TRIAL_SERVER_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Server names used in connection errors
+#[cfg(feature = "remote")]
+const TRIAL_SERVER_NAME: &str = "trial server";
+#[cfg(feature = "remote")]
+const PROMO_SERVER_NAME: &str = "promo server";
+
 /// Trial status information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrialInfo {
@@ -20,10 +47,30 @@ pub struct TrialInfo {
     pub hours_remaining: u32,
     /// Minutes remaining (0-59)
     pub minutes_remaining: u32,
+    /// Total seconds remaining (0 unless active), for a countdown the UI
+    /// ticks itself
+    #[serde(default)]
+    pub seconds_remaining: u64,
     /// Expiration timestamp (RFC3339)
     pub expires_at: Option<String>,
     /// Whether trial was already used (can't start again)
     pub already_used: bool,
+    /// Whether the trial start is anchored on the trial server
+    #[serde(default)]
+    pub verification: TrialVerification,
+}
+
+/// Whether a trial's start date is backed by the trial server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TrialVerification {
+    /// No trial server is configured, the trial is tracked locally
+    #[default]
+    Local,
+    /// The trial server issued the expiration
+    Verified,
+    /// A trial server is configured but was unreachable when the trial started
+    Unverified,
 }
 
 /// Trial status enum
@@ -37,10 +84,67 @@ pub enum TrialStatus {
     Expired,
 }
 
+/// Trial countdown points at which the user is notified (once each)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TrialMilestone {
+    /// 3 days or less remaining
+    ThreeDaysLeft,
+    /// 1 day or less remaining
+    OneDayLeft,
+    /// Trial has ended
+    Expired,
+}
+
+impl TrialMilestone {
+    /// Notification title
+    pub fn title(&self) -> &'static str {
+        match self {
+            TrialMilestone::ThreeDaysLeft | TrialMilestone::OneDayLeft => "Trial ending soon",
+            TrialMilestone::Expired => "Trial expired",
+        }
+    }
+
+    /// Notification body
+    pub fn message(&self) -> &'static str {
+        match self {
+            TrialMilestone::ThreeDaysLeft => {
+                "Your free trial ends in 3 days. Purchase a license to keep using your tools."
+            }
+            TrialMilestone::OneDayLeft => {
+                "Your free trial ends within a day. Purchase a license to keep using your tools."
+            }
+            TrialMilestone::Expired => {
+                "Your free trial has ended. Purchase a license to continue using all features."
+            }
+        }
+    }
+
+    /// Get the most urgent milestone the trial has reached, if any
+    pub fn reached(info: &TrialInfo) -> Option<Self> {
+        if !info.already_used {
+            return None;
+        }
+        if !info.active {
+            return Some(TrialMilestone::Expired);
+        }
+        match info.days_remaining {
+            0 => Some(TrialMilestone::OneDayLeft),
+            1 | 2 => Some(TrialMilestone::ThreeDaysLeft),
+            _ => None,
+        }
+    }
+}
+
 /// Get current trial status
 pub fn get_trial_status() -> Result<TrialInfo> {
-    let config = LicenseConfig::load()?;
-    
+    trial_info_at(&LicenseConfig::load()?, Utc::now())
+}
+
+/// Trial status for an already loaded config as of `now`. The single place
+/// that does trial expiry math (auth status is derived from this too); pass
+/// a fixed time to check behavior around expiry.
+pub fn trial_info_at(config: &LicenseConfig, now: DateTime<Utc>) -> Result<TrialInfo> {
     // Check if trial was never started
     if !config.trial_started {
         return Ok(TrialInfo {
@@ -48,16 +152,17 @@ pub fn get_trial_status() -> Result<TrialInfo> {
             days_remaining: TRIAL_DAYS as u32,
             hours_remaining: 0,
             minutes_remaining: 0,
+            seconds_remaining: 0,
             expires_at: None,
             already_used: false,
+            verification: config.trial_verification,
         });
     }
     
-    // Trial was started, check if still active
+    // Trial was started, check if still active. An unreadable expiration
+    // counts as expired.
     if let Some(ref exp_str) = config.trial_expiration {
-        let expiration = chrono::DateTime::parse_from_rfc3339(exp_str)
-            .context("Failed to parse trial expiration")?;
-        let now = Utc::now();
+        let expiration = crate::parse_timestamp(exp_str).unwrap_or(DateTime::<Utc>::MIN_UTC);
         
         if expiration > now {
             // Trial still active
@@ -67,8 +172,10 @@ pub fn get_trial_status() -> Result<TrialInfo> {
                 days_remaining: remaining.num_days() as u32,
                 hours_remaining: (remaining.num_hours() % 24) as u32,
                 minutes_remaining: (remaining.num_minutes() % 60) as u32,
+                seconds_remaining: remaining.num_seconds() as u64,
                 expires_at: Some(exp_str.clone()),
                 already_used: true,
+                verification: config.trial_verification,
             });
         }
     }
@@ -79,34 +186,256 @@ pub fn get_trial_status() -> Result<TrialInfo> {
         days_remaining: 0,
         hours_remaining: 0,
         minutes_remaining: 0,
-        expires_at: config.trial_expiration,
+        seconds_remaining: 0,
+        expires_at: config.trial_expiration.clone(),
         already_used: true,
+        verification: config.trial_verification,
     })
 }
 
+/// Whole days (rounded up) left in the post-trial grace window as of `now`,
+/// or `None` if the trial isn't over, no grace is configured or it has run out
+pub fn grace_days_remaining_at(config: &LicenseConfig, now: DateTime<Utc>) -> Option<u32> {
+    let grace_days = config.post_trial_grace_days.min(MAX_GRACE_DAYS);
+    if !config.trial_started || grace_days == 0 {
+        return None;
+    }
+    let expiration = crate::parse_timestamp(config.trial_expiration.as_deref()?)?;
+    if expiration > now {
+        return None;
+    }
+    let grace_end = expiration + Duration::days(i64::from(grace_days));
+    let remaining = grace_end.signed_duration_since(now);
+    if remaining <= Duration::zero() {
+        return None;
+    }
+    let days = remaining.num_days() + i64::from(remaining.num_seconds() % 86_400 != 0);
+    Some(days as u32)
+}
+
+/// Fraction of the trial used up: 0.0 when it starts, 1.0 once it expires.
+/// `None` if no trial has been started.
+pub fn trial_progress() -> Option<f32> {
+    trial_progress_at(&LicenseConfig::load().ok()?, Utc::now())
+}
+
+/// Trial progress for an already loaded config as of `now`. The trial's
+/// start is worked back from its expiration using the trial length plus any
+/// promo code extensions.
+pub fn trial_progress_at(config: &LicenseConfig, now: DateTime<Utc>) -> Option<f32> {
+    if !config.trial_started {
+        return None;
+    }
+    // An unreadable expiration counts as expired
+    let expiration = crate::parse_timestamp(config.trial_expiration.as_deref()?)
+        .unwrap_or(DateTime::<Utc>::MIN_UTC);
+
+    let extension_days = config.used_extension_codes.len() as i64 * TRIAL_EXTENSION_DAYS;
+    let length = Duration::days(TRIAL_DAYS + extension_days);
+    let remaining = expiration.signed_duration_since(now);
+    let progress = 1.0 - remaining.num_seconds() as f64 / length.num_seconds() as f64;
+    Some(progress.clamp(0.0, 1.0) as f32)
+}
+
+/// Get the trial server base URL, if server-anchored trials are enabled
+pub fn trial_server_url() -> Option<String> {
+    std::env::var(TRIAL_SERVER_ENV)
+        .ok()
+        .or_else(|| option_env!("HUB_TRIAL_SERVER_URL").map(str::to_string))
+        .filter(|url| !url.trim().is_empty())
+}
+
 /// Start the trial (one-time per machine)
-pub fn start_trial() -> Result<TrialInfo> {
+///
+/// If a trial server is configured, this machine's hardware fingerprint (which,
+/// unlike `machine_id`, survives deleting `license.json`) is registered with it
+/// and the server-issued expiration is used, so reinstalling doesn't hand out a
+/// fresh trial. If the server can't be reached the trial starts
+/// locally and is marked unverified.
+pub async fn start_trial() -> Result<TrialInfo> {
     let mut config = LicenseConfig::load()?;
     
     // Check if trial was already used
     if config.trial_started {
-        anyhow::bail!("Trial has already been used on this machine");
+        return Err(anyhow::anyhow!("Trial has already been used on this machine").into());
+    }
+    if LicenseConfig::is_read_only() {
+        return Err(anyhow::anyhow!("Can't start a trial: license storage is unavailable").into());
     }
     
+    let (expiration, verification) = match trial_server_url() {
+        Some(url) => match register_trial(&url, &config.hardware_id()).await {
+            Ok(expiration) => (expiration, TrialVerification::Verified),
+            Err(e) => {
+                warn!("Trial server unavailable, starting unverified trial: {}", e);
+                (Utc::now() + Duration::days(TRIAL_DAYS), TrialVerification::Unverified)
+            }
+        },
+        None => (Utc::now() + Duration::days(TRIAL_DAYS), TrialVerification::Local),
+    };
+
     // Start trial
-    let expiration = Utc::now() + Duration::days(TRIAL_DAYS);
     config.trial_started = true;
     config.trial_expiration = Some(expiration.to_rfc3339());
+    config.trial_verification = verification;
     config.save()?;
     
-    Ok(TrialInfo {
-        active: true,
-        days_remaining: TRIAL_DAYS as u32,
-        hours_remaining: 0,
-        minutes_remaining: 0,
-        expires_at: Some(expiration.to_rfc3339()),
-        already_used: true,
-    })
+    get_trial_status()
+}
+
+/// Register this machine with the trial server and get its trial expiration.
+/// Machines that already registered get their original expiration back.
+#[cfg(feature = "remote")]
+async fn register_trial(base_url: &str, hardware_id: &str) -> Result<chrono::DateTime<Utc>> {
+    let response = reqwest::Client::new()
+        .post(format!("{}/trials", base_url.trim_end_matches('/')))
+        .header("Accept", "application/json")
+        .timeout(TRIAL_SERVER_TIMEOUT)
+        .form(&[("machine_id", hardware_id)])
+        .send()
+        .await
+        .map_err(LicenseError::network(TRIAL_SERVER_NAME))?;
+    let response: TrialServerResponse = check_status(TRIAL_SERVER_NAME, response)?
+        .error_for_status()
+        .map_err(|e| anyhow::anyhow!("Trial server rejected the request: {}", e))?
+        .json()
+        .await
+        .map_err(LicenseError::parse("trial server response"))?;
+
+    let expiration = chrono::DateTime::parse_from_rfc3339(&response.expires_at)
+        .map_err(LicenseError::parse("trial expiration from server"))?;
+    Ok(expiration.with_timezone(&Utc))
+}
+
+/// Without the `remote` feature a configured trial server is never reachable,
+/// so trials start unverified
+#[cfg(not(feature = "remote"))]
+async fn register_trial(_base_url: &str, _hardware_id: &str) -> Result<chrono::DateTime<Utc>> {
+    Err(anyhow::anyhow!("This build can't contact a trial server").into())
+}
+
+/// Trial server response
+#[cfg(feature = "remote")]
+#[derive(Debug, Deserialize)]
+struct TrialServerResponse {
+    expires_at: String,
+}
+
+/// Normalize a promo code and check its format: 8-32 letters/digits, optionally
+/// grouped with dashes (e.g. "SPRING-2025-HUB")
+pub fn normalize_extension_code(code: &str) -> Result<String> {
+    let code = code.trim().to_ascii_uppercase();
+    let len = code.chars().filter(|c| *c != '-').count();
+
+    if !(8..=32).contains(&len)
+        || !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        || code.starts_with('-')
+        || code.ends_with('-')
+        || code.contains("--")
+    {
+        return Err(anyhow::anyhow!("Invalid promo code format").into());
+    }
+
+    Ok(code)
+}
+
+/// Extend the active trial with a (normalized, server-approved) promo code.
+///
+/// Adds `TRIAL_EXTENSION_DAYS` to the trial expiration. Each code can only be
+/// redeemed once per machine.
+fn apply_extension_code(code: String) -> Result<TrialInfo> {
+    let mut config = LicenseConfig::load()?;
+    check_extendable(&config, &code)?;
+
+    let expiration = trial_expiration(&config)? + Duration::days(TRIAL_EXTENSION_DAYS);
+    config.trial_expiration = Some(expiration.to_rfc3339());
+    config.used_extension_codes.push(code);
+    // Countdown milestones apply again to the new expiration
+    config.trial_notifications_shown.clear();
+    config.save()?;
+
+    get_trial_status()
+}
+
+/// Check a promo code could extend the trial in `config`, before asking the
+/// server about it
+fn check_extendable(config: &LicenseConfig, code: &str) -> Result<()> {
+    if config.used_extension_codes.iter().any(|used| used == code) {
+        return Err(anyhow::anyhow!("This promo code has already been used").into());
+    }
+    trial_expiration(config).map(|_| ())
+}
+
+/// Expiration of the active trial a promo code would extend
+fn trial_expiration(config: &LicenseConfig) -> Result<DateTime<Utc>> {
+    let expiration = match config.trial_expiration {
+        Some(ref exp_str) if config.trial_started => {
+            crate::parse_timestamp(exp_str).unwrap_or(DateTime::<Utc>::MIN_UTC)
+        }
+        _ => {
+            return Err(
+                anyhow::anyhow!("Start your free trial before redeeming a promo code").into(),
+            )
+        }
+    };
+    if expiration <= Utc::now() {
+        return Err(anyhow::anyhow!("Promo codes can only extend an active trial").into());
+    }
+    Ok(expiration)
+}
+
+/// Redeem a promo code. Every code has to be approved by the promo server
+/// (`HUB_PROMO_SERVER_URL`, set at build time).
+pub async fn redeem_extension_code(code: &str) -> Result<TrialInfo> {
+    let normalized = normalize_extension_code(code)?;
+    let config = LicenseConfig::load()?;
+    check_extendable(&config, &normalized)?;
+
+    let url = PROMO_SERVER_URL
+        .filter(|url| !url.trim().is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Promo codes can't be redeemed in this build"))?;
+    check_code_with_server(url, &normalized, &config.hardware_id()).await?;
+
+    apply_extension_code(normalized)
+}
+
+/// Ask the promo server whether a (normalized) code may be redeemed
+#[cfg(feature = "remote")]
+async fn check_code_with_server(url: &str, code: &str, hardware_id: &str) -> Result<()> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .header("Accept", "application/json")
+        .form(&[("code", code), ("machine_id", hardware_id)])
+        .send()
+        .await
+        .map_err(LicenseError::network(PROMO_SERVER_NAME))?;
+    let response: ExtensionCodeResponse = check_status(PROMO_SERVER_NAME, response)?
+        .json()
+        .await
+        .map_err(LicenseError::parse("promo server response"))?;
+
+    if !response.valid {
+        let message = response
+            .error
+            .unwrap_or_else(|| "Promo code is not valid".to_string());
+        return Err(anyhow::anyhow!(message).into());
+    }
+    Ok(())
+}
+
+/// Without the `remote` feature codes that need server approval can't be
+/// redeemed
+#[cfg(not(feature = "remote"))]
+async fn check_code_with_server(_url: &str, _code: &str, _hardware_id: &str) -> Result<()> {
+    Err(anyhow::anyhow!("This build can't contact the promo server").into())
+}
+
+/// Promo server response
+#[cfg(feature = "remote")]
+#[derive(Debug, Deserialize)]
+struct ExtensionCodeResponse {
+    valid: bool,
+    error: Option<String>,
 }
 
 /// Format trial remaining time as a human-readable string
@@ -127,3 +456,21 @@ pub fn format_trial_remaining(info: &TrialInfo) -> String {
         format!("{} minutes remaining", info.minutes_remaining)
     }
 }
+
+/// Get the trial milestone to notify the user about, if one was reached and
+/// hasn't been shown yet. The milestone is recorded as shown.
+pub fn check_trial_notification() -> Result<Option<TrialMilestone>> {
+    let info = get_trial_status()?;
+    let Some(milestone) = TrialMilestone::reached(&info) else {
+        return Ok(None);
+    };
+
+    let mut config = LicenseConfig::load()?;
+    if config.trial_notifications_shown.contains(&milestone) {
+        return Ok(None);
+    }
+    config.trial_notifications_shown.push(milestone);
+    config.save()?;
+
+    Ok(Some(milestone))
+}